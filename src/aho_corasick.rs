@@ -0,0 +1,384 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Multi-pattern search with the Aho-Corasick algorithm.
+//!
+//! The patterns are inserted in a trie whose failure links are computed
+//! with the leftmost rules: once a match has been seen, the automaton never
+//! follows a failure link that would forget the start of that match, and
+//! goes to a dead state instead. The resulting automaton is then converted
+//! into a DFA over byte equivalence classes, so searching does a single
+//! table lookup per byte.
+
+use std;
+
+use ByteStr;
+use Matcher;
+use IntoMatcher;
+use ForwardSearcher;
+use IntoSearcher;
+use Searcher;
+use SearchStep;
+
+/// Selects which match is reported when several patterns match at the same
+/// position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchKind {
+    /// Among the matches that start at the leftmost position, report the one
+    /// whose pattern was given first.
+    LeftmostFirst,
+    /// Among the matches that start at the leftmost position, report the
+    /// longest one.
+    LeftmostLongest,
+}
+
+const DEAD: usize = 0;
+const START: usize = 1;
+
+/// Matcher that searches for any of a list of patterns at once.
+///
+/// Matches report the index of the pattern in the list given to the
+/// constructor with `find_pattern`.
+#[derive(Clone, Debug)]
+pub struct AhoCorasickMatcher {
+    match_kind: MatchKind,
+    num_patterns: usize,
+    // Maps each byte to its equivalence class.
+    classes: [u8; 256],
+    num_classes: usize,
+    // `trans[state * num_classes + class]` is the next state.
+    trans: Vec<usize>,
+    // Pattern index and length of the match reported at each state.
+    matches: Vec<Option<(usize, usize)>>,
+}
+
+// State of the trie used during construction.
+struct TrieState {
+    next: Vec<(u8, usize)>,
+    fail: usize,
+    depth: usize,
+    own_match: Option<(usize, usize)>,
+    matched: Option<(usize, usize)>,
+}
+
+impl TrieState {
+    fn new(depth: usize) -> Self {
+        Self {
+            next: Vec::new(),
+            fail: START,
+            depth,
+            own_match: None,
+            matched: None,
+        }
+    }
+    
+    fn next_state(&self, byte: u8) -> Option<usize> {
+        self.next.iter().find(|&&(b, _)| b == byte).map(|&(_, s)| s)
+    }
+}
+
+impl AhoCorasickMatcher {
+    /// Creates a matcher for `patterns` with leftmost-first semantics.
+    pub fn new<I>(patterns: I) -> Self
+        where I: IntoIterator,
+              I::Item: AsRef<[u8]>
+    {
+        Self::with_match_kind(MatchKind::LeftmostFirst, patterns)
+    }
+    
+    /// Creates a matcher for `patterns` with the given match semantics.
+    pub fn with_match_kind<I>(match_kind: MatchKind, patterns: I) -> Self
+        where I: IntoIterator,
+              I::Item: AsRef<[u8]>
+    {
+        let mut trie = vec![TrieState::new(0), TrieState::new(0)];
+        let mut used_bytes = [false; 256];
+        let mut num_patterns = 0;
+        
+        'patterns: for (pattern_id, pattern) in patterns.into_iter().enumerate() {
+            num_patterns += 1;
+            let pattern = pattern.as_ref();
+            let mut state = START;
+            for &byte in pattern {
+                // With leftmost-first semantics, a pattern that has a previous
+                // pattern as prefix can never match.
+                if match_kind == MatchKind::LeftmostFirst && trie[state].own_match.is_some() {
+                    continue 'patterns;
+                }
+                used_bytes[byte as usize] = true;
+                state = match trie[state].next_state(byte) {
+                    Some(next) => next,
+                    None => {
+                        let next = trie.len();
+                        let depth = trie[state].depth + 1;
+                        trie.push(TrieState::new(depth));
+                        trie[state].next.push((byte, next));
+                        next
+                    }
+                };
+            }
+            if trie[state].own_match.is_none() {
+                trie[state].own_match = Some((pattern_id, pattern.len()));
+            }
+        }
+        
+        // Compute the failure links in breadth-first order. Each queued state
+        // carries the depth at which the earliest match on its path starts.
+        let mut order = Vec::with_capacity(trie.len());
+        let mut queue = std::collections::VecDeque::new();
+        trie[START].matched = trie[START].own_match;
+        let start_match = trie[START].own_match.map(|_| 0);
+        for i in 0 .. trie[START].next.len() {
+            let next = trie[START].next[i].1;
+            let match_start = start_match.or(trie[next].own_match.map(|(_, len)| trie[next].depth - len));
+            trie[next].matched = trie[next].own_match;
+            trie[next].fail = if match_start.is_some() { DEAD } else { START };
+            queue.push_back((next, match_start));
+        }
+        while let Some((state, match_start)) = queue.pop_front() {
+            order.push(state);
+            for i in 0 .. trie[state].next.len() {
+                let (byte, next) = trie[state].next[i];
+                let next_match_start = match_start.or(trie[next].own_match.map(|(_, len)| trie[next].depth - len));
+                queue.push_back((next, next_match_start));
+                
+                let mut fail = trie[state].fail;
+                let fail = loop {
+                    if fail == DEAD {
+                        break DEAD;
+                    }
+                    if let Some(s) = trie[fail].next_state(byte) {
+                        break s;
+                    }
+                    if fail == START {
+                        break START;
+                    }
+                    fail = trie[fail].fail;
+                };
+                
+                if let Some(match_start) = next_match_start {
+                    // Following the failure link must not move the start of
+                    // the pending match.
+                    if fail == DEAD || trie[next].depth - match_start > trie[fail].depth {
+                        trie[next].fail = DEAD;
+                        trie[next].matched = trie[next].own_match;
+                        continue;
+                    }
+                }
+                trie[next].fail = fail;
+                trie[next].matched = trie[next].own_match.or(trie[fail].matched);
+            }
+            if trie[state].next.is_empty() && trie[state].matched.is_some() {
+                trie[state].fail = DEAD;
+            }
+        }
+        
+        // Byte equivalence classes: every byte used by a pattern gets its
+        // own class, all the other bytes share class 0.
+        let mut classes = [0u8; 256];
+        let mut num_classes = 1;
+        for byte in 0 .. 256 {
+            if used_bytes[byte] {
+                classes[byte] = num_classes as u8;
+                num_classes += 1;
+            }
+        }
+        // Up to 256 used bytes plus the unused class do not fit in `u8`;
+        // in that case no byte is unused and the classes are shifted.
+        if num_classes > 256 {
+            for (byte, class) in classes.iter_mut().enumerate() {
+                *class = byte as u8;
+            }
+            num_classes = 256;
+        }
+        
+        // Build the DFA. The rows of the failure states are always filled
+        // before the rows that depend on them.
+        let num_states = trie.len();
+        let mut trans = vec![DEAD; num_states * num_classes];
+        let start_self = if trie[START].own_match.is_some() { DEAD } else { START };
+        for (byte, &class) in classes.iter().enumerate() {
+            let class = class as usize;
+            trans[START * num_classes + class] = trie[START].next_state(byte as u8).unwrap_or(start_self);
+        }
+        for &state in order.iter() {
+            let fail = trie[state].fail;
+            for (byte, &class) in classes.iter().enumerate() {
+                let class = class as usize;
+                trans[state * num_classes + class] = match trie[state].next_state(byte as u8) {
+                    Some(next) => next,
+                    None => trans[fail * num_classes + class],
+                };
+            }
+        }
+        
+        Self {
+            match_kind,
+            num_patterns,
+            classes,
+            num_classes,
+            trans,
+            matches: trie.iter().map(|s| s.matched).collect(),
+        }
+    }
+    
+    /// Returns the match semantics of the matcher.
+    #[inline]
+    pub fn match_kind(&self) -> MatchKind {
+        self.match_kind
+    }
+    
+    /// Returns the number of patterns of the matcher.
+    #[inline]
+    pub fn pattern_count(&self) -> usize {
+        self.num_patterns
+    }
+    
+    /// Returns the leftmost match in `haystack` as `(pattern, start, end)`,
+    /// where `pattern` is the index of the pattern that matched.
+    #[inline]
+    pub fn find_pattern(&self, haystack: &ByteStr) -> Option<(usize, usize, usize)> {
+        self.find_pattern_at(haystack, 0)
+    }
+    
+    // Returns the leftmost match that starts at or after `start`. The
+    // automaton runs until it reaches the dead state, which happens at most
+    // the length of the longest pattern after the end of the match.
+    fn find_pattern_at(&self, haystack: &ByteStr, start: usize) -> Option<(usize, usize, usize)> {
+        let mut state = START;
+        let mut last_match = self.matches[START].map(|(pattern, _)| (pattern, start, start));
+        for (i, &byte) in haystack[start ..].iter().enumerate() {
+            state = self.trans[state * self.num_classes + self.classes[byte as usize] as usize];
+            if state == DEAD {
+                break;
+            }
+            if let Some((pattern, len)) = self.matches[state] {
+                let end = start + i + 1;
+                last_match = Some((pattern, end - len, end));
+            }
+        }
+        last_match
+    }
+}
+
+impl Matcher for AhoCorasickMatcher {}
+
+impl<'a> IntoMatcher for &'a AhoCorasickMatcher {
+    type Matcher = &'a AhoCorasickMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a AhoCorasickMatcher {
+        self
+    }
+}
+
+impl<'m, 'a> IntoSearcher<'a> for &'m AhoCorasickMatcher {
+    type Searcher = AhoCorasickSearcher<'m, 'a>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> AhoCorasickSearcher<'m, 'a> {
+        AhoCorasickSearcher {
+            matcher: self,
+            haystack,
+            pos: start,
+            empty: false,
+            last_pattern: None,
+        }
+    }
+}
+
+impl ForwardSearcher for AhoCorasickMatcher {
+    #[inline]
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        self.find_pattern(haystack).map(|(_, a, b)| (a, b))
+    }
+}
+
+/// `Searcher` for an `AhoCorasickMatcher`.
+///
+/// It keeps the position where the automaton restarts, which is the end of
+/// the previous match, and runs it over the haystack itself instead of
+/// over copies of the remaining part. Only the bytes scanned after a match
+/// to confirm it is the leftmost one are scanned again, which are at most
+/// as many as the length of the longest pattern, so the total time is
+/// linear in the length of the haystack plus the number of matches times
+/// that length.
+#[derive(Clone, Debug)]
+pub struct AhoCorasickSearcher<'m, 'a> {
+    matcher: &'m AhoCorasickMatcher,
+    haystack: &'a ByteStr,
+    pos: usize,
+    // Whether an empty match at `pos` has been reported.
+    empty: bool,
+    last_pattern: Option<usize>,
+}
+
+impl<'m, 'a> AhoCorasickSearcher<'m, 'a> {
+    /// Returns the index of the pattern of the last match reported.
+    #[inline]
+    pub fn last_pattern(&self) -> Option<usize> {
+        self.last_pattern
+    }
+}
+
+impl<'m, 'a> Searcher<'a> for AhoCorasickSearcher<'m, 'a> {
+    #[inline]
+    fn haystack(&self) -> &'a ByteStr {
+        self.haystack
+    }
+    
+    #[inline]
+    fn cursor(&self) -> usize {
+        self.pos
+    }
+    
+    fn next(&mut self) -> SearchStep {
+        let pos = self.pos;
+        if self.empty {
+            if pos == self.haystack.len() {
+                return SearchStep::Done;
+            }
+            self.empty = false;
+            self.pos += 1;
+            return SearchStep::Reject(pos, pos + 1);
+        }
+        match self.matcher.find_pattern_at(self.haystack, pos) {
+            Some((pattern, a, b)) if a == pos => {
+                self.pos = b;
+                self.empty = a == b;
+                self.last_pattern = Some(pattern);
+                SearchStep::Match(a, b)
+            }
+            Some((_, a, _)) => {
+                // The match is found again by the next call.
+                self.pos = a;
+                SearchStep::Reject(pos, a)
+            }
+            None if pos != self.haystack.len() => {
+                self.pos = self.haystack.len();
+                SearchStep::Reject(pos, self.pos)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.empty {
+            if self.pos == self.haystack.len() {
+                return None;
+            }
+            self.empty = false;
+            self.pos += 1;
+        }
+        let (pattern, a, b) = self.matcher.find_pattern_at(self.haystack, self.pos)?;
+        self.pos = b;
+        self.empty = a == b;
+        self.last_pattern = Some(pattern);
+        Some((a, b))
+    }
+}
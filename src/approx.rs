@@ -0,0 +1,383 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Approximate substring search.
+//!
+//! `HammingMatcher` allows substitutions only, so its matches have the
+//! length of the needle, and scans the haystack with the bit-parallel
+//! k-mismatch algorithm of Wu and Manber. `EditDistanceMatcher` also allows
+//! insertions and deletions, and scans the haystack with the bit-parallel
+//! algorithm of Myers. Both process 64 needle bytes per machine word.
+
+use ByteStr;
+use ByteString;
+use Matcher;
+use IntoMatcher;
+use FullMatcher;
+use ForwardSearcher;
+use IntoSearcher;
+use FindSearcher;
+
+// HammingMatcher
+/// Matcher for the substrings that differ from a needle in at most `k`
+/// bytes.
+///
+/// Searches report the leftmost match. `find_with_distance` also returns
+/// the number of differing bytes, and `distance` computes it for a string
+/// returned by other searches, such as `match_indices`.
+#[derive(Clone, Debug)]
+pub struct HammingMatcher {
+    needle: ByteString,
+    max_distance: usize,
+    // Bit masks of the positions of each byte in the needle, built by
+    // `byte_masks`.
+    peq: Vec<u64>,
+}
+
+impl HammingMatcher {
+    /// Creates a matcher for `needle` that allows up to `max_distance`
+    /// substitutions.
+    pub fn new<B: ?Sized + AsRef<[u8]>>(needle: &B, max_distance: usize) -> Self {
+        let needle = needle.as_ref();
+        Self {
+            needle: ByteString::from_slice(needle),
+            max_distance,
+            peq: byte_masks(needle),
+        }
+    }
+    
+    /// Returns the needle of the matcher.
+    #[inline]
+    pub fn needle(&self) -> &ByteStr {
+        &self.needle
+    }
+    
+    /// Returns the maximum number of substitutions allowed in a match.
+    #[inline]
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+    
+    /// Returns the Hamming distance between the needle and `candidate`, or
+    /// `None` if it is greater than the maximum or their lengths differ.
+    pub fn distance(&self, candidate: &ByteStr) -> Option<usize> {
+        if candidate.len() != self.needle.len() {
+            return None;
+        }
+        let mut distance = 0;
+        for (a, b) in self.needle.iter().zip(candidate.iter()) {
+            if a != b {
+                if distance == self.max_distance {
+                    return None;
+                }
+                distance += 1;
+            }
+        }
+        Some(distance)
+    }
+    
+    /// Finds the leftmost match in `haystack`, returning its start, end and
+    /// distance.
+    pub fn find_with_distance(&self, haystack: &ByteStr) -> Option<(usize, usize, usize)> {
+        let len = self.needle.len();
+        if len == 0 {
+            return Some((0, 0, 0));
+        }
+        
+        // Bit `i` of `states[d]` is set when the `i + 1` bytes that end at
+        // the current position differ from the start of the needle in at
+        // most `d` bytes. More than `len` substitutions are never needed.
+        let max_distance = self.max_distance.min(len);
+        let blocks = (len + 63) / 64;
+        let (last_block, last_bit) = ((len - 1) / 64, 1 << ((len - 1) % 64));
+        let mut states = vec![0u64; (max_distance + 1) * blocks];
+        for (j, &byte) in haystack.iter().enumerate() {
+            let peq = &self.peq[byte as usize * blocks .. (byte as usize + 1) * blocks];
+            // Going down, `states[d - 1]` still holds the previous position.
+            for d in (0 ..= max_distance).rev() {
+                let (fewer, current) = states.split_at_mut(d * blocks);
+                let fewer = if d == 0 { None } else { Some(&fewer[(d - 1) * blocks ..]) };
+                // A new prefix starts at every position.
+                let (mut carry, mut carry_fewer) = (1, 1);
+                for block in 0 .. blocks {
+                    let state = current[block];
+                    // Extending with an equal byte keeps the distance.
+                    let mut next = ((state << 1) | carry) & peq[block];
+                    carry = state >> 63;
+                    if let Some(fewer) = fewer {
+                        // Extending with a different byte adds one.
+                        next |= (fewer[block] << 1) | carry_fewer;
+                        carry_fewer = fewer[block] >> 63;
+                    }
+                    current[block] = next;
+                }
+            }
+            
+            if states[max_distance * blocks + last_block] & last_bit != 0 {
+                let distance = (0 ..= max_distance)
+                    .find(|&d| states[d * blocks + last_block] & last_bit != 0)
+                    .unwrap();
+                return Some((j + 1 - len, j + 1, distance));
+            }
+        }
+        None
+    }
+}
+
+impl Matcher for HammingMatcher {}
+
+impl<'a> IntoMatcher for &'a HammingMatcher {
+    type Matcher = &'a HammingMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a HammingMatcher {
+        self
+    }
+}
+
+impl<'m, 'a> IntoSearcher<'a> for &'m HammingMatcher {
+    type Searcher = FindSearcher<'a, &'m HammingMatcher>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> FindSearcher<'a, &'m HammingMatcher> {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+impl FullMatcher for HammingMatcher {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        self.distance(haystack).is_some()
+    }
+}
+
+impl ForwardSearcher for HammingMatcher {
+    #[inline]
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        self.find_with_distance(haystack).map(|(start, end, _)| (start, end))
+    }
+}
+
+// EditDistanceMatcher
+/// Matcher for the substrings within Levenshtein distance `k` of a needle,
+/// that is, that can be turned into the needle with at most `k` byte
+/// insertions, deletions and substitutions.
+///
+/// Searches look for the first position where a match ends, keep
+/// extending the end while that lowers the distance, and then take the
+/// longest match that ends there with the lowest distance. For example,
+/// `"abc"` with `k = 1` finds `"abc"` in `"xabcx"`, not `"ab"` or `"xabc"`.
+///
+/// `find_with_distance` also returns the distance, and `distance` computes
+/// it for a string returned by other searches, such as `match_indices`.
+#[derive(Clone, Debug)]
+pub struct EditDistanceMatcher {
+    needle: ByteString,
+    max_distance: usize,
+    // Bit masks of the positions of each byte in the needle, built by
+    // `byte_masks`.
+    peq: Vec<u64>,
+}
+
+impl EditDistanceMatcher {
+    /// Creates a matcher for `needle` that allows up to `max_distance` edits.
+    pub fn new<B: ?Sized + AsRef<[u8]>>(needle: &B, max_distance: usize) -> Self {
+        let needle = needle.as_ref();
+        Self {
+            needle: ByteString::from_slice(needle),
+            max_distance,
+            peq: byte_masks(needle),
+        }
+    }
+    
+    /// Returns the needle of the matcher.
+    #[inline]
+    pub fn needle(&self) -> &ByteStr {
+        &self.needle
+    }
+    
+    /// Returns the maximum number of edits allowed in a match.
+    #[inline]
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+    
+    /// Returns the edit distance between the needle and `candidate`, or
+    /// `None` if it is greater than the maximum.
+    pub fn distance(&self, candidate: &ByteStr) -> Option<usize> {
+        let needle = self.needle.as_slice();
+        let diff = if needle.len() > candidate.len() {
+            needle.len() - candidate.len()
+        } else {
+            candidate.len() - needle.len()
+        };
+        if diff > self.max_distance {
+            return None;
+        }
+        // Classic dynamic programming, one row per byte of `candidate`.
+        let mut row: Vec<usize> = (0 ..= needle.len()).collect();
+        for (j, &byte) in candidate.iter().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = j + 1;
+            for i in 1 ..= needle.len() {
+                let substitution = diagonal + (needle[i - 1] != byte) as usize;
+                diagonal = row[i];
+                row[i] = substitution.min(row[i] + 1).min(row[i - 1] + 1);
+            }
+        }
+        let distance = row[needle.len()];
+        if distance <= self.max_distance {
+            Some(distance)
+        } else {
+            None
+        }
+    }
+    
+    /// Finds the leftmost match in `haystack`, returning its start, end and
+    /// distance.
+    pub fn find_with_distance(&self, haystack: &ByteStr) -> Option<(usize, usize, usize)> {
+        let (end, distance) = self.find_end(haystack)?;
+        let start = self.find_start(&haystack[.. end], distance);
+        Some((start, end, distance))
+    }
+    
+    // Scans `haystack` keeping the lowest distance between the needle and a
+    // substring that ends at the current position, and returns the end and
+    // distance of the first local minimum within the limit.
+    fn find_end(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        let len = self.needle.len();
+        let mut score = len;
+        if len == 0 {
+            return Some((0, 0));
+        }
+        
+        let blocks = (len + 63) / 64;
+        let last_bit = 1 << ((len - 1) % 64);
+        let mut pv = vec![!0u64; blocks];
+        let mut mv = vec![0u64; blocks];
+        let mut best = if score <= self.max_distance { Some((0, score)) } else { None };
+        for (j, &byte) in haystack.iter().enumerate() {
+            let peq = &self.peq[byte as usize * blocks .. (byte as usize + 1) * blocks];
+            // Substrings may start anywhere, so nothing enters the first
+            // block from above.
+            let mut carry = 0;
+            for block in 0 .. blocks {
+                let high = if block == blocks - 1 { last_bit } else { 1 << 63 };
+                carry = advance_block(&mut pv[block], &mut mv[block], peq[block], carry, high);
+            }
+            score = (score as isize + carry as isize) as usize;
+            
+            match best {
+                Some((_, distance)) if score < distance => best = Some((j + 1, score)),
+                Some(_) => break,
+                None if score <= self.max_distance => best = Some((j + 1, score)),
+                None => {}
+            }
+        }
+        best
+    }
+    
+    // Returns the start of the longest suffix of `haystack` whose distance
+    // to the needle is `distance`.
+    fn find_start(&self, haystack: &ByteStr, distance: usize) -> usize {
+        // Dynamic programming over the reversed strings, one row per byte
+        // of `haystack`, with at most `len + distance` of them.
+        let needle = self.needle.as_slice();
+        let mut row: Vec<usize> = (0 ..= needle.len()).collect();
+        let mut start = haystack.len();
+        let limit = haystack.len().saturating_sub(needle.len() + distance);
+        for (t, &byte) in haystack[limit ..].iter().enumerate().rev() {
+            let mut diagonal = row[0];
+            row[0] += 1;
+            for i in 1 ..= needle.len() {
+                let substitution = diagonal + (needle[needle.len() - i] != byte) as usize;
+                diagonal = row[i];
+                row[i] = substitution.min(row[i] + 1).min(row[i - 1] + 1);
+            }
+            if row[needle.len()] == distance {
+                start = limit + t;
+            }
+        }
+        start
+    }
+}
+
+// Returns the bit masks of the positions of each byte in `needle`, one
+// block of 64 positions after another: `peq[byte * blocks + block]`.
+fn byte_masks(needle: &[u8]) -> Vec<u64> {
+    let blocks = (needle.len() + 63) / 64;
+    let mut peq = vec![0; 256 * blocks];
+    for (i, &byte) in needle.iter().enumerate() {
+        peq[byte as usize * blocks + i / 64] |= 1 << (i % 64);
+    }
+    peq
+}
+
+// Advances one block of the Myers bit vectors over a byte of the haystack,
+// given the change of the score entering the block from above, and returns
+// the change leaving it through the `high` bit.
+#[inline]
+fn advance_block(pv: &mut u64, mv: &mut u64, mut eq: u64, carry: i8, high: u64) -> i8 {
+    let xv = eq | *mv;
+    if carry < 0 {
+        eq |= 1;
+    }
+    let xh = ((eq & *pv).wrapping_add(*pv) ^ *pv) | eq;
+    let mut ph = *mv | !(xh | *pv);
+    let mut mh = *pv & xh;
+    let carry_out = if ph & high != 0 {
+        1
+    } else if mh & high != 0 {
+        -1
+    } else {
+        0
+    };
+    ph <<= 1;
+    mh <<= 1;
+    if carry < 0 {
+        mh |= 1;
+    } else if carry > 0 {
+        ph |= 1;
+    }
+    *pv = mh | !(xv | ph);
+    *mv = ph & xv;
+    carry_out
+}
+
+impl Matcher for EditDistanceMatcher {}
+
+impl<'a> IntoMatcher for &'a EditDistanceMatcher {
+    type Matcher = &'a EditDistanceMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a EditDistanceMatcher {
+        self
+    }
+}
+
+impl<'m, 'a> IntoSearcher<'a> for &'m EditDistanceMatcher {
+    type Searcher = FindSearcher<'a, &'m EditDistanceMatcher>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> FindSearcher<'a, &'m EditDistanceMatcher> {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+impl FullMatcher for EditDistanceMatcher {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        self.distance(haystack).is_some()
+    }
+}
+
+impl ForwardSearcher for EditDistanceMatcher {
+    #[inline]
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        self.find_with_distance(haystack).map(|(start, end, _)| (start, end))
+    }
+}
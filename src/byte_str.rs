@@ -11,18 +11,30 @@ use std;
 
 #[cfg(not(feature="no_std"))]
 use ByteString;
+#[cfg(not(feature="no_std"))]
+use AhoCorasickMatcher;
 
 use iterators;
+use display;
+use utf8;
+use Utf8Error;
 
 use IntoMatcher;
 use PrefixMatcher;
 use SufixMatcher;
 use ForwardSearcher;
 use ReverseSearcher;
+use IntoSearcher;
+use IntoRSearcher;
+use search::prefix_len;
+use search::sufix_start;
 
 /// Borrowed reference to a byte string. It provides similar functionality as `str`
 /// and `[u8]`.
-#[derive(PartialEq, Eq)]
+///
+/// Strings are compared and hashed like their bytes, so a `ByteString` key
+/// can be looked up with a `ByteStr`, or a `[u8]`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ByteStr {
     inner: [u8],
 }
@@ -53,12 +65,20 @@ impl ByteStr {
     }
     
     /// Forms a `ByteStr` from a pointer and a length.
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as `slice::from_raw_parts()`.
     #[inline]
     pub unsafe fn from_raw_parts<'a>(ptr: *const u8, len: usize) -> &'a Self {
         ByteStr::from_slice(core::slice::from_raw_parts(ptr, len))
     }
     
     /// Forms a mutable `ByteStr` from a pointer and a length.
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as `slice::from_raw_parts_mut()`.
     #[inline]
     pub unsafe fn from_raw_parts_mut<'a>(ptr: *mut u8, len: usize) -> &'a mut Self {
         ByteStr::from_slice_mut(core::slice::from_raw_parts_mut(ptr, len))
@@ -108,6 +128,65 @@ impl ByteStr {
         ByteString::from_vec(self.into_vec())
     }
     
+    /// Converts `self` into a `&str` if it is valid UTF-8.
+    #[inline]
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        utf8::validate(self.as_slice())?;
+        Ok(unsafe { core::str::from_utf8_unchecked(self.as_slice()) })
+    }
+    
+    /// Converts `self` into a string, replacing each invalid UTF-8 sequence
+    /// with U+FFFD REPLACEMENT CHARACTER. Only allocates if the string is
+    /// not valid UTF-8.
+    #[cfg(not(feature="no_std"))]
+    #[inline]
+    pub fn to_str_lossy(&self) -> std::borrow::Cow<'_, str> {
+        utf8::to_str_lossy(self.as_slice())
+    }
+    
+    /// Returns an object that shows the string with the escapes of a byte
+    /// string literal, like `Debug` but without the quotes.
+    #[inline]
+    pub fn display_escaped<'a>(&'a self) -> display::DisplayEscaped<'a> {
+        display::DisplayEscaped::new(self)
+    }
+    
+    /// Returns an object that shows the string in hexadecimal.
+    #[inline]
+    pub fn display_hex<'a>(&'a self) -> display::DisplayHex<'a> {
+        display::DisplayHex::new(self)
+    }
+    
+    /// Returns an object that shows at most the first `max` bytes of the
+    /// string like `Display`, followed by `...` if it is longer.
+    #[inline]
+    pub fn display_truncated<'a>(&'a self, max: usize) -> display::DisplayTruncated<'a> {
+        display::DisplayTruncated::new(self, max)
+    }
+    
+    /// Returns an iterator over the chars of the string, decoded as UTF-8.
+    /// Each invalid sequence gives a U+FFFD REPLACEMENT CHARACTER, like in
+    /// `to_str_lossy`.
+    #[inline]
+    pub fn chars<'a>(&'a self) -> iterators::Chars<'a> {
+        iterators::Chars::new(self)
+    }
+    
+    /// Like `chars`, but also returns the offset of each char, or of the
+    /// invalid sequence replaced by it.
+    #[inline]
+    pub fn char_indices<'a>(&'a self) -> iterators::CharIndices<'a> {
+        iterators::CharIndices::new(self)
+    }
+    
+    /// Returns an iterator that splits the string into chunks made of valid
+    /// UTF-8 followed by an invalid sequence. The invalid part is empty only
+    /// in the last chunk, and the valid part can be empty in any of them.
+    #[inline]
+    pub fn utf8_chunks<'a>(&'a self) -> iterators::Utf8Chunks<'a> {
+        iterators::Utf8Chunks::new(self)
+    }
+    
     /// Returns the length of `self`.
     #[inline]
     pub fn len(&self) -> usize {
@@ -271,7 +350,7 @@ impl ByteStr {
     /// Returns an iterator over substrings of this string, separated by a matcher.
     #[inline]
     pub fn split<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::Split<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
     {
         iterators::Split::new(self, m.into_matcher())
     }
@@ -279,7 +358,7 @@ impl ByteStr {
     /// Returns an iterator over mutable substrings of this string, separated by a matcher.
     #[inline]
     pub fn split_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::SplitMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoSearcher<'s>
     {
         iterators::SplitMut::new(self, m.into_matcher())
     }
@@ -288,7 +367,7 @@ impl ByteStr {
     /// starting at the end of the slice and working backwards.
     #[inline]
     pub fn rsplit<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::RSplit<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: IntoRSearcher<'a>
     {
         iterators::RSplit::new(self, m.into_matcher())
     }
@@ -297,18 +376,87 @@ impl ByteStr {
     /// starting at the end of the slice and working backwards.
     #[inline]
     pub fn rsplit_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::RSplitMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoRSearcher<'s>
     {
         iterators::RSplitMut::new(self, m.into_matcher())
     }
     
+    /// Like `split`, but the last item is skipped if it is empty, which is
+    /// the case when the string ends with a match. Useful for records that
+    /// end with a terminator, such as lines.
+    #[inline]
+    pub fn split_terminator<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::SplitTerminator<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
+    {
+        iterators::SplitTerminator::new(self, m.into_matcher())
+    }
+    
+    /// Mutable version of `split_terminator`.
+    #[inline]
+    pub fn split_terminator_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::SplitTerminatorMut<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoSearcher<'s>
+    {
+        iterators::SplitTerminatorMut::new(self, m.into_matcher())
+    }
+    
+    /// Like `split_terminator`, but starting at the end of the string and
+    /// working backwards.
+    #[inline]
+    pub fn rsplit_terminator<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::RSplitTerminator<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: IntoRSearcher<'a>
+    {
+        iterators::RSplitTerminator::new(self, m.into_matcher())
+    }
+    
+    /// Mutable version of `rsplit_terminator`.
+    #[inline]
+    pub fn rsplit_terminator_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::RSplitTerminatorMut<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoRSearcher<'s>
+    {
+        iterators::RSplitTerminatorMut::new(self, m.into_matcher())
+    }
+    
+    /// Like `split`, but each item keeps the match that ends it. The last
+    /// item is skipped if it is empty.
+    #[inline]
+    pub fn split_inclusive<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::SplitInclusive<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
+    {
+        iterators::SplitInclusive::new(self, m.into_matcher())
+    }
+    
+    /// Mutable version of `split_inclusive`.
+    #[inline]
+    pub fn split_inclusive_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::SplitInclusiveMut<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoSearcher<'s>
+    {
+        iterators::SplitInclusiveMut::new(self, m.into_matcher())
+    }
+    
+    /// Like `split_inclusive`, but starting at the end of the string and
+    /// working backwards.
+    #[inline]
+    pub fn rsplit_inclusive<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::RSplitInclusive<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: IntoRSearcher<'a>
+    {
+        iterators::RSplitInclusive::new(self, m.into_matcher())
+    }
+    
+    /// Mutable version of `rsplit_inclusive`.
+    #[inline]
+    pub fn rsplit_inclusive_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::RSplitInclusiveMut<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoRSearcher<'s>
+    {
+        iterators::RSplitInclusiveMut::new(self, m.into_matcher())
+    }
+    
     /// Returns an iterator over substrings of this string, separated by a matcher, returning
     /// at most `n` items.
     ///
     /// If `n` substrings are returned, the last substring will contain the remainder of the string.
     #[inline]
     pub fn splitn<'a, M: IntoMatcher>(&'a self, n: usize, m: M) -> iterators::SplitN<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
     {
         iterators::SplitN::new(self, n, m.into_matcher())
     }
@@ -319,7 +467,7 @@ impl ByteStr {
     /// If `n` substrings are returned, the last substring will contain the remainder of the string.
     #[inline]
     pub fn splitn_mut<'a, M: IntoMatcher>(&'a mut self, n: usize, m: M) -> iterators::SplitNMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoSearcher<'s>
     {
         iterators::SplitNMut::new(self, n, m.into_matcher())
     }
@@ -330,7 +478,7 @@ impl ByteStr {
     /// If `n` substrings are returned, the last substring will contain the remainder of the string.
     #[inline]
     pub fn rsplitn<'a, M: IntoMatcher>(&'a self, n: usize, m: M) -> iterators::RSplitN<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: IntoRSearcher<'a>
     {
         iterators::RSplitN::new(self, n, m.into_matcher())
     }
@@ -341,15 +489,96 @@ impl ByteStr {
     /// If `n` substrings are returned, the last substring will contain the remainder of the string.
     #[inline]
     pub fn rsplitn_mut<'a, M: IntoMatcher>(&'a mut self, n: usize, m: M) -> iterators::RSplitNMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoRSearcher<'s>
     {
         iterators::RSplitNMut::new(self, n, m.into_matcher())
     }
     
+    /// Returns an iterator over the lines of the string, without their
+    /// terminators. Lines end with `\n` or `\r\n`, and the terminator of the
+    /// last line is optional, so a string that ends with a line break does
+    /// not give an empty last line.
+    #[inline]
+    pub fn lines<'a>(&'a self) -> iterators::Lines<'a> {
+        self.lines_by(iterators::LineBreaks::Newline)
+    }
+    
+    /// Mutable version of `lines`.
+    #[inline]
+    pub fn lines_mut<'a>(&'a mut self) -> iterators::LinesMut<'a> {
+        self.lines_by_mut(iterators::LineBreaks::Newline)
+    }
+    
+    /// Like `lines`, but the lines keep their terminators.
+    #[inline]
+    pub fn lines_with_terminator<'a>(&'a self) -> iterators::Lines<'a> {
+        self.lines_with_terminator_by(iterators::LineBreaks::Newline)
+    }
+    
+    /// Mutable version of `lines_with_terminator`.
+    #[inline]
+    pub fn lines_with_terminator_mut<'a>(&'a mut self) -> iterators::LinesMut<'a> {
+        self.lines_with_terminator_by_mut(iterators::LineBreaks::Newline)
+    }
+    
+    /// Like `lines`, with the given line breaks.
+    #[inline]
+    pub fn lines_by<'a>(&'a self, breaks: iterators::LineBreaks) -> iterators::Lines<'a> {
+        iterators::Lines::new(self, breaks, false)
+    }
+    
+    /// Mutable version of `lines_by`.
+    #[inline]
+    pub fn lines_by_mut<'a>(&'a mut self, breaks: iterators::LineBreaks) -> iterators::LinesMut<'a> {
+        iterators::LinesMut::new(self, breaks, false)
+    }
+    
+    /// Like `lines_with_terminator`, with the given line breaks.
+    #[inline]
+    pub fn lines_with_terminator_by<'a>(&'a self, breaks: iterators::LineBreaks) -> iterators::Lines<'a> {
+        iterators::Lines::new(self, breaks, true)
+    }
+    
+    /// Mutable version of `lines_with_terminator_by`.
+    #[inline]
+    pub fn lines_with_terminator_by_mut<'a>(&'a mut self, breaks: iterators::LineBreaks) -> iterators::LinesMut<'a> {
+        iterators::LinesMut::new(self, breaks, true)
+    }
+    
+    /// Like `lines`, but also returns the offset of each line in the string.
+    /// Combine it with `enumerate` to get line numbers too.
+    #[inline]
+    pub fn line_indices<'a>(&'a self) -> iterators::LineIndices<'a> {
+        self.line_indices_by(iterators::LineBreaks::Newline)
+    }
+    
+    /// Like `line_indices`, with the given line breaks.
+    #[inline]
+    pub fn line_indices_by<'a>(&'a self, breaks: iterators::LineBreaks) -> iterators::LineIndices<'a> {
+        iterators::LineIndices::new(self, breaks)
+    }
+    
+    /// Returns an iterator over the fields of the string separated by ASCII
+    /// whitespace, as defined by `u8::is_ascii_whitespace`. Empty fields are
+    /// skipped.
+    #[inline]
+    pub fn split_ascii_whitespace<'a>(&'a self) -> iterators::SplitAsciiWhitespace<'a> {
+        iterators::SplitAsciiWhitespace::new(self)
+    }
+    
+    /// Like `split_ascii_whitespace`, but the fields are separated by Unicode
+    /// whitespace, as defined by `char::is_whitespace`. Multi-byte whitespace
+    /// is only recognized where it is valid UTF-8, so invalid sequences end
+    /// up inside the fields.
+    #[inline]
+    pub fn split_whitespace<'a>(&'a self) -> iterators::SplitWhitespace<'a> {
+        iterators::SplitWhitespace::new(self)
+    }
+    
     /// Returns an iterator over the disjoint matches within the given string.
     #[inline]
     pub fn matches<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::Matches<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
     {
         iterators::Matches::new(self, m.into_matcher())
     }
@@ -357,7 +586,7 @@ impl ByteStr {
     /// Returns an iterator over the mutable disjoint matches within the given string.
     #[inline]
     pub fn matches_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::MatchesMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoSearcher<'s>
     {
         iterators::MatchesMut::new(self, m.into_matcher())
     }
@@ -365,7 +594,7 @@ impl ByteStr {
     /// Returns an iterator over the disjoint matches within the given string, yielded in reverse order.
     #[inline]
     pub fn rmatches<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::RMatches<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: IntoRSearcher<'a>
     {
         iterators::RMatches::new(self, m.into_matcher())
     }
@@ -374,7 +603,7 @@ impl ByteStr {
     /// in reverse order.
     #[inline]
     pub fn rmatches_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::RMatchesMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoRSearcher<'s>
     {
         iterators::RMatchesMut::new(self, m.into_matcher())
     }
@@ -383,7 +612,7 @@ impl ByteStr {
     /// that the match starts at.
     #[inline]
     pub fn match_indices<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::MatchIndices<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
     {
         iterators::MatchIndices::new(self, m.into_matcher())
     }
@@ -392,7 +621,7 @@ impl ByteStr {
     /// the index that the match starts at.
     #[inline]
     pub fn match_indices_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::MatchIndicesMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ForwardSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoSearcher<'s>
     {
         iterators::MatchIndicesMut::new(self, m.into_matcher())
     }
@@ -401,7 +630,7 @@ impl ByteStr {
     /// as well as the index that the match starts at.
     #[inline]
     pub fn rmatch_indices<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::RMatchIndices<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: IntoRSearcher<'a>
     {
         iterators::RMatchIndices::new(self, m.into_matcher())
     }
@@ -410,11 +639,73 @@ impl ByteStr {
     /// order, as well as the index that the match starts at.
     #[inline]
     pub fn rmatch_indices_mut<'a, M: IntoMatcher>(&'a mut self, m: M) -> iterators::RMatchIndicesMut<'a, M::Matcher>
-        where <M as IntoMatcher>::Matcher: ReverseSearcher
+        where <M as IntoMatcher>::Matcher: Clone + for<'s> IntoRSearcher<'s>
     {
         iterators::RMatchIndicesMut::new(self, m.into_matcher())
     }
     
+    /// Returns an iterator over all the matches within the given string, including the
+    /// ones that overlap.
+    ///
+    /// After each match, the search continues one byte after its start.
+    #[inline]
+    pub fn matches_overlapping<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::MatchesOverlapping<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + IntoSearcher<'a>
+    {
+        iterators::MatchesOverlapping::new(self, m.into_matcher())
+    }
+    
+    /// Returns an iterator over all the matches within the given string, including the
+    /// ones that overlap, yielded in reverse order.
+    ///
+    /// After each match, the search continues one byte before its end.
+    #[inline]
+    pub fn rmatches_overlapping<'a, M: IntoMatcher>(&'a self, m: M) -> iterators::RMatchesOverlapping<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + IntoRSearcher<'a>
+    {
+        iterators::RMatchesOverlapping::new(self, m.into_matcher())
+    }
+    
+    /// Returns an iterator over all the matches within the given string, including the
+    /// ones that overlap, as well as the index that the match starts at.
+    #[inline]
+    pub fn match_indices_overlapping<'a, M: IntoMatcher>(&'a self, m: M)
+        -> iterators::MatchIndicesOverlapping<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + IntoSearcher<'a>
+    {
+        iterators::MatchIndicesOverlapping::new(self, m.into_matcher())
+    }
+    
+    /// Returns an iterator over all the matches within the given string, including the
+    /// ones that overlap, yielded in reverse order, as well as the index that the match
+    /// starts at.
+    #[inline]
+    pub fn rmatch_indices_overlapping<'a, M: IntoMatcher>(&'a self, m: M)
+        -> iterators::RMatchIndicesOverlapping<'a, M::Matcher>
+        where <M as IntoMatcher>::Matcher: Clone + IntoRSearcher<'a>
+    {
+        iterators::RMatchIndicesOverlapping::new(self, m.into_matcher())
+    }
+    
+    /// Returns the number of matches within the given string, including the ones that
+    /// overlap. It is the number of items of `matches_overlapping`, but substring
+    /// and byte matchers count them without restarting the search after each match.
+    #[inline]
+    pub fn count_overlapping<M: IntoMatcher>(&self, m: M) -> usize
+        where <M as IntoMatcher>::Matcher: ForwardSearcher
+    {
+        m.into_matcher().count_overlapping(self)
+    }
+    
+    /// Returns an iterator over the disjoint matches of the patterns of an
+    /// `AhoCorasickMatcher` within the given string, as `(pattern, start, end)`
+    /// tuples, where `pattern` is the index of the pattern that matched.
+    #[cfg(not(feature="no_std"))]
+    #[inline]
+    pub fn pattern_matches<'a, 'b>(&'a self, matcher: &'b AhoCorasickMatcher) -> iterators::PatternMatches<'a, 'b> {
+        iterators::PatternMatches::new(self, matcher)
+    }
+    
     /// Returns `true` if the string contains a substring that matches the given matcher.
     #[inline]
     pub fn contains<M: IntoMatcher>(&self, m: M) -> bool
@@ -457,6 +748,248 @@ impl ByteStr {
         m.into_matcher().rfind(self).map(|(_, b)| b)
     }
     
+    /// Returns the string without the prefix that matches the matcher, or
+    /// `None` if it does not start with a match.
+    #[inline]
+    pub fn strip_prefix<M: IntoMatcher>(&self, m: M) -> Option<&ByteStr>
+        where <M as IntoMatcher>::Matcher: PrefixMatcher + ForwardSearcher
+    {
+        prefix_len(&m.into_matcher(), self).map(move |i| &self[i ..])
+    }
+    
+    /// Mutable version of `strip_prefix`.
+    #[inline]
+    pub fn strip_prefix_mut<M: IntoMatcher>(&mut self, m: M) -> Option<&mut ByteStr>
+        where <M as IntoMatcher>::Matcher: PrefixMatcher + ForwardSearcher
+    {
+        prefix_len(&m.into_matcher(), self).map(move |i| &mut self[i ..])
+    }
+    
+    /// Returns the string without the suffix that matches the matcher, or
+    /// `None` if it does not end with a match.
+    #[inline]
+    pub fn strip_suffix<M: IntoMatcher>(&self, m: M) -> Option<&ByteStr>
+        where <M as IntoMatcher>::Matcher: SufixMatcher + ReverseSearcher
+    {
+        sufix_start(&m.into_matcher(), self).map(move |i| &self[.. i])
+    }
+    
+    /// Mutable version of `strip_suffix`.
+    #[inline]
+    pub fn strip_suffix_mut<M: IntoMatcher>(&mut self, m: M) -> Option<&mut ByteStr>
+        where <M as IntoMatcher>::Matcher: SufixMatcher + ReverseSearcher
+    {
+        sufix_start(&m.into_matcher(), self).map(move |i| &mut self[.. i])
+    }
+    
+    // Returns the end of the consecutive matches at the start of the string.
+    fn trim_start_index<M: PrefixMatcher + ForwardSearcher>(&self, m: &M) -> usize {
+        let mut start = 0;
+        while let Some(len) = prefix_len(m, &self[start ..]) {
+            if len == 0 {
+                break;
+            }
+            start += len;
+        }
+        start
+    }
+    
+    // Returns the start of the consecutive matches at the end of the string.
+    fn trim_end_index<M: SufixMatcher + ReverseSearcher>(&self, m: &M) -> usize {
+        let mut end = self.len();
+        while let Some(start) = sufix_start(m, &self[.. end]) {
+            if start == end {
+                break;
+            }
+            end = start;
+        }
+        end
+    }
+    
+    /// Returns the string with all the consecutive matches at its start
+    /// removed.
+    #[inline]
+    pub fn trim_start_matches<M: IntoMatcher>(&self, m: M) -> &ByteStr
+        where <M as IntoMatcher>::Matcher: PrefixMatcher + ForwardSearcher
+    {
+        &self[self.trim_start_index(&m.into_matcher()) ..]
+    }
+    
+    /// Mutable version of `trim_start_matches`.
+    #[inline]
+    pub fn trim_start_matches_mut<M: IntoMatcher>(&mut self, m: M) -> &mut ByteStr
+        where <M as IntoMatcher>::Matcher: PrefixMatcher + ForwardSearcher
+    {
+        let start = self.trim_start_index(&m.into_matcher());
+        &mut self[start ..]
+    }
+    
+    /// Returns the string with all the consecutive matches at its end
+    /// removed.
+    #[inline]
+    pub fn trim_end_matches<M: IntoMatcher>(&self, m: M) -> &ByteStr
+        where <M as IntoMatcher>::Matcher: SufixMatcher + ReverseSearcher
+    {
+        &self[.. self.trim_end_index(&m.into_matcher())]
+    }
+    
+    /// Mutable version of `trim_end_matches`.
+    #[inline]
+    pub fn trim_end_matches_mut<M: IntoMatcher>(&mut self, m: M) -> &mut ByteStr
+        where <M as IntoMatcher>::Matcher: SufixMatcher + ReverseSearcher
+    {
+        let end = self.trim_end_index(&m.into_matcher());
+        &mut self[.. end]
+    }
+    
+    /// Returns the string with all the consecutive matches at its start and
+    /// at its end removed.
+    #[inline]
+    pub fn trim_matches<M: IntoMatcher>(&self, m: M) -> &ByteStr
+        where <M as IntoMatcher>::Matcher: PrefixMatcher + SufixMatcher + ForwardSearcher + ReverseSearcher
+    {
+        let m = m.into_matcher();
+        let start = self.trim_start_index(&m);
+        &self[start ..][.. self[start ..].trim_end_index(&m)]
+    }
+    
+    /// Mutable version of `trim_matches`.
+    #[inline]
+    pub fn trim_matches_mut<M: IntoMatcher>(&mut self, m: M) -> &mut ByteStr
+        where <M as IntoMatcher>::Matcher: PrefixMatcher + SufixMatcher + ForwardSearcher + ReverseSearcher
+    {
+        let m = m.into_matcher();
+        let start = self.trim_start_index(&m);
+        let end = start + self[start ..].trim_end_index(&m);
+        &mut self[start .. end]
+    }
+    
+    /// Returns the string without leading and trailing ASCII whitespace.
+    #[inline]
+    pub fn trim(&self) -> &ByteStr {
+        self.trim_start().trim_end()
+    }
+    
+    /// Mutable version of `trim`.
+    #[inline]
+    pub fn trim_mut(&mut self) -> &mut ByteStr {
+        self.trim_start_mut().trim_end_mut()
+    }
+    
+    /// Returns the string without leading ASCII whitespace.
+    #[inline]
+    pub fn trim_start(&self) -> &ByteStr {
+        let start = self.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(self.len());
+        &self[start ..]
+    }
+    
+    /// Mutable version of `trim_start`.
+    #[inline]
+    pub fn trim_start_mut(&mut self) -> &mut ByteStr {
+        let start = self.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(self.len());
+        &mut self[start ..]
+    }
+    
+    /// Returns the string without trailing ASCII whitespace.
+    #[inline]
+    pub fn trim_end(&self) -> &ByteStr {
+        let end = self.iter().rposition(|byte| !byte.is_ascii_whitespace()).map_or(0, |i| i + 1);
+        &self[.. end]
+    }
+    
+    /// Mutable version of `trim_end`.
+    #[inline]
+    pub fn trim_end_mut(&mut self) -> &mut ByteStr {
+        let end = self.iter().rposition(|byte| !byte.is_ascii_whitespace()).map_or(0, |i| i + 1);
+        &mut self[.. end]
+    }
+    
+    /// Splits the string on the first match of the matcher, returning the
+    /// parts before and after it, or `None` if there is no match.
+    #[inline]
+    pub fn split_once<M: IntoMatcher>(&self, m: M) -> Option<(&ByteStr, &ByteStr)>
+        where <M as IntoMatcher>::Matcher: ForwardSearcher
+    {
+        let (a, b) = m.into_matcher().find(self)?;
+        Some((&self[.. a], &self[b ..]))
+    }
+    
+    /// Mutable version of `split_once`.
+    #[inline]
+    pub fn split_once_mut<M: IntoMatcher>(&mut self, m: M) -> Option<(&mut ByteStr, &mut ByteStr)>
+        where <M as IntoMatcher>::Matcher: ForwardSearcher
+    {
+        let (a, b) = m.into_matcher().find(self)?;
+        let (left, right) = self.split_at_mut(b);
+        Some((&mut left[.. a], right))
+    }
+    
+    /// Splits the string on the last match of the matcher, returning the
+    /// parts before and after it, or `None` if there is no match.
+    #[inline]
+    pub fn rsplit_once<M: IntoMatcher>(&self, m: M) -> Option<(&ByteStr, &ByteStr)>
+        where <M as IntoMatcher>::Matcher: ReverseSearcher
+    {
+        let (a, b) = m.into_matcher().rfind(self)?;
+        Some((&self[.. a], &self[b ..]))
+    }
+    
+    /// Mutable version of `rsplit_once`.
+    #[inline]
+    pub fn rsplit_once_mut<M: IntoMatcher>(&mut self, m: M) -> Option<(&mut ByteStr, &mut ByteStr)>
+        where <M as IntoMatcher>::Matcher: ReverseSearcher
+    {
+        let (a, b) = m.into_matcher().rfind(self)?;
+        let (left, right) = self.split_at_mut(b);
+        Some((&mut left[.. a], right))
+    }
+    
+    /// Replaces all the matches of a matcher with `to`, returning the result
+    /// as a new string.
+    #[cfg(not(feature="no_std"))]
+    #[inline]
+    pub fn replace<'a, M: IntoMatcher, B: ?Sized + AsRef<[u8]>>(&'a self, m: M, to: &B) -> ByteString
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
+    {
+        self.replacen(m, to, usize::MAX)
+    }
+    
+    /// Like `replace`, but only the first `count` matches are replaced.
+    #[cfg(not(feature="no_std"))]
+    pub fn replacen<'a, M: IntoMatcher, B: ?Sized + AsRef<[u8]>>(&'a self, m: M, to: &B, count: usize) -> ByteString
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>
+    {
+        let to = to.as_ref();
+        self.replace_matches(m, count, |result, _| result.push_slice(to))
+    }
+    
+    /// Replaces every match of a matcher with the string returned by `f`
+    /// when called with it, returning the result as a new string.
+    #[cfg(not(feature="no_std"))]
+    pub fn replace_with<'a, 'b, M: IntoMatcher, F>(&'a self, m: M, mut f: F) -> ByteString
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>,
+              F: FnMut(&'a ByteStr) -> std::borrow::Cow<'b, ByteStr>
+    {
+        self.replace_matches(m, usize::MAX, |result, part| result.push_byte_str(&f(part)))
+    }
+    
+    // Copies the string, passing the first `count` matches to `push` instead.
+    #[cfg(not(feature="no_std"))]
+    fn replace_matches<'a, M: IntoMatcher, F>(&'a self, m: M, count: usize, mut push: F) -> ByteString
+        where <M as IntoMatcher>::Matcher: IntoSearcher<'a>,
+              F: FnMut(&mut ByteString, &'a ByteStr)
+    {
+        let mut result = ByteString::from_vec(Vec::with_capacity(self.len()));
+        let mut last = 0;
+        for (start, part) in self.match_indices(m).take(count) {
+            result.push_byte_str(&self[last .. start]);
+            push(&mut result, part);
+            last = start + part.len();
+        }
+        result.push_byte_str(&self[last ..]);
+        result
+    }
+    
     /// Swaps two bytes in the string, indexed by `a` and `b`.
     ///
     /// # Panics
@@ -488,17 +1021,51 @@ impl ByteStr {
     pub fn copy_from_byte_str(&mut self, src: &ByteStr) {
         self.as_mut_slice().copy_from_slice(src.as_slice());
     }
+    
+    /// Checks that two strings are equal, ignoring ASCII case.
+    #[inline]
+    pub fn eq_ignore_ascii_case<B: ?Sized + AsRef<[u8]>>(&self, other: &B) -> bool {
+        self.as_slice().eq_ignore_ascii_case(other.as_ref())
+    }
+    
+    /// Returns a copy of `self` with every ASCII lowercase letter converted to
+    /// uppercase.
+    #[cfg(not(feature="no_std"))]
+    #[inline]
+    pub fn to_ascii_uppercase(&self) -> ByteString {
+        ByteString::from_vec(self.as_slice().to_ascii_uppercase())
+    }
+    
+    /// Returns a copy of `self` with every ASCII uppercase letter converted to
+    /// lowercase.
+    #[cfg(not(feature="no_std"))]
+    #[inline]
+    pub fn to_ascii_lowercase(&self) -> ByteString {
+        ByteString::from_vec(self.as_slice().to_ascii_lowercase())
+    }
+    
+    /// Converts every ASCII lowercase letter of `self` to uppercase in place.
+    #[inline]
+    pub fn make_ascii_uppercase(&mut self) {
+        self.as_mut_slice().make_ascii_uppercase();
+    }
+    
+    /// Converts every ASCII uppercase letter of `self` to lowercase in place.
+    #[inline]
+    pub fn make_ascii_lowercase(&mut self) {
+        self.as_mut_slice().make_ascii_lowercase();
+    }
 }
 
 // Default
-impl<'a> Default for &'a ByteStr {
+impl Default for &ByteStr {
     #[inline]
     fn default() -> Self {
         ByteStr::empty()
     }
 }
 
-impl<'a> Default for &'a mut ByteStr {
+impl Default for &mut ByteStr {
     #[inline]
     fn default() -> Self {
         ByteStr::empty_mut()
@@ -508,34 +1075,17 @@ impl<'a> Default for &'a mut ByteStr {
 // Debug
 impl core::fmt::Debug for ByteStr {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
-        use core::fmt::Write;
-        
-        fn to_hex(nibble: u8) -> u8 {
-            if nibble < 10 {
-                b'0' + nibble
-            } else {
-                b'a' + nibble - 10
-            }
-        }
-        
         f.write_str("b\"")?;
-        for &byte in self.iter() {
-            match byte {
-                b'\t' => f.write_str("\\t")?,
-                b'\r' => f.write_str("\\r")?,
-                b'\n' => f.write_str("\\n")?,
-                b'\\' => f.write_str("\\\\")?,
-                b'"' => f.write_str("\\\"")?,
-                0x20 ... 0x7E => f.write_char(byte as char)?,
-                _ => {
-                    f.write_str("\\x")?;
-                    f.write_char(to_hex(byte >> 4) as char)?;
-                    f.write_char(to_hex(byte & 0xF) as char)?;
-                }
-            }
-        }
-        f.write_char('"')?;
-        Ok(())
+        display::write_escaped(self.as_slice(), true, f)?;
+        f.write_str("\"")
+    }
+}
+
+// Display
+impl core::fmt::Display for ByteStr {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        display::pad_lossy(self.as_slice(), "", f)
     }
 }
 
@@ -581,21 +1131,14 @@ impl core::convert::AsMut<[u8]> for ByteStr {
 }
 
 // PartialEq
-impl<'a> core::cmp::PartialEq<ByteStr> for &'a ByteStr {
+impl core::cmp::PartialEq<ByteStr> for &ByteStr {
     #[inline]
     fn eq(&self, other: &ByteStr) -> bool {
         self.as_slice() == other.as_slice()
     }
 }
 
-impl core::cmp::PartialEq<[u8]> for ByteStr {
-    #[inline]
-    fn eq(&self, other: &[u8]) -> bool {
-        self.as_slice() == other
-    }
-}
-
-impl<'a> core::cmp::PartialEq<[u8]> for &'a ByteStr {
+impl core::cmp::PartialEq<[u8]> for &ByteStr {
     #[inline]
     fn eq(&self, other: &[u8]) -> bool {
         self.as_slice() == other
@@ -655,46 +1198,143 @@ impl_partial_eq_array!(31);
 impl_partial_eq_array!(32);
 
 #[cfg(not(feature="no_std"))]
-impl core::cmp::PartialEq<ByteString> for ByteStr {
+impl<'b> core::cmp::PartialEq<std::borrow::Cow<'b, ByteStr>> for &ByteStr {
     #[inline]
-    fn eq(&self, other: &ByteString) -> bool {
+    fn eq(&self, other: &std::borrow::Cow<'b, ByteStr>) -> bool {
         self.as_slice() == other.as_slice()
     }
 }
 
+impl<'a, T: ?Sized> core::cmp::PartialEq<&'a T> for ByteStr
+    where ByteStr: core::cmp::PartialEq<T>
+{
+    #[inline]
+    fn eq(&self, other: &&'a T) -> bool {
+        self == *other
+    }
+}
+
+impl<'a, T: ?Sized> core::cmp::PartialOrd<&'a T> for ByteStr
+    where ByteStr: core::cmp::PartialOrd<T>
+{
+    #[inline]
+    fn partial_cmp(&self, other: &&'a T) -> Option<core::cmp::Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+
+// AsBytes
+// Gives the bytes of the string types that can be compared with each other.
+pub(crate) trait AsBytes {
+    fn as_bytes(&self) -> &[u8];
+}
+
+impl AsBytes for ByteStr {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsBytes for [u8] {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AsBytes for str {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+}
+
 #[cfg(not(feature="no_std"))]
-impl<'a> core::cmp::PartialEq<ByteString> for &'a ByteStr {
+impl AsBytes for Vec<u8> {
     #[inline]
-    fn eq(&self, other: &ByteString) -> bool {
-        self.as_slice() == other.as_slice()
+    fn as_bytes(&self) -> &[u8] {
+        self
     }
 }
 
 #[cfg(not(feature="no_std"))]
-impl<'b> core::cmp::PartialEq<std::borrow::Cow<'b, ByteStr>> for ByteStr {
+impl AsBytes for String {
     #[inline]
-    fn eq(&self, other: &std::borrow::Cow<'b, ByteStr>) -> bool {
-        self.as_slice() == other.as_slice()
+    fn as_bytes(&self) -> &[u8] {
+        String::as_bytes(self)
     }
 }
 
 #[cfg(not(feature="no_std"))]
-impl<'a, 'b> core::cmp::PartialEq<std::borrow::Cow<'b, ByteStr>> for &'a ByteStr {
+impl AsBytes for ByteString {
     #[inline]
-    fn eq(&self, other: &std::borrow::Cow<'b, ByteStr>) -> bool {
-        self.as_slice() == other.as_slice()
+    fn as_bytes(&self) -> &[u8] {
+        self.as_slice()
     }
 }
 
-impl<'a, T: ?Sized> core::cmp::PartialEq<&'a T> for ByteStr
-    where ByteStr: core::cmp::PartialEq<T>
-{
+#[cfg(not(feature="no_std"))]
+impl<'a> AsBytes for std::borrow::Cow<'a, ByteStr> {
     #[inline]
-    fn eq(&self, other: &&'a T) -> bool {
-        self == *other
+    fn as_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<T: ?Sized + AsBytes> AsBytes for &T {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        (**self).as_bytes()
+    }
+}
+
+// PartialOrd
+// Implements `PartialEq` and `PartialOrd` between two string types, in both
+// directions, comparing their bytes.
+macro_rules! impl_partial_cmp {
+    ([$($gen:tt)*] $a:ty, $b:ty) => {
+        impl<$($gen)*> core::cmp::PartialEq<$b> for $a {
+            #[inline]
+            fn eq(&self, other: &$b) -> bool {
+                AsBytes::as_bytes(self) == AsBytes::as_bytes(other)
+            }
+        }
+        
+        impl<$($gen)*> core::cmp::PartialEq<$a> for $b {
+            #[inline]
+            fn eq(&self, other: &$a) -> bool {
+                AsBytes::as_bytes(self) == AsBytes::as_bytes(other)
+            }
+        }
+        
+        impl<$($gen)*> core::cmp::PartialOrd<$b> for $a {
+            #[inline]
+            fn partial_cmp(&self, other: &$b) -> Option<core::cmp::Ordering> {
+                AsBytes::as_bytes(self).partial_cmp(AsBytes::as_bytes(other))
+            }
+        }
+        
+        impl<$($gen)*> core::cmp::PartialOrd<$a> for $b {
+            #[inline]
+            fn partial_cmp(&self, other: &$a) -> Option<core::cmp::Ordering> {
+                AsBytes::as_bytes(self).partial_cmp(AsBytes::as_bytes(other))
+            }
+        }
     }
 }
 
+impl_partial_cmp!([] ByteStr, [u8]);
+impl_partial_cmp!([] ByteStr, str);
+#[cfg(not(feature="no_std"))]
+impl_partial_cmp!([] ByteStr, Vec<u8>);
+#[cfg(not(feature="no_std"))]
+impl_partial_cmp!([] ByteStr, String);
+#[cfg(not(feature="no_std"))]
+impl_partial_cmp!([] ByteStr, ByteString);
+#[cfg(not(feature="no_std"))]
+impl_partial_cmp!(['b] ByteStr, std::borrow::Cow<'b, ByteStr>);
+
 // Index
 impl core::ops::Index<usize> for ByteStr {
     type Output = u8;
@@ -736,8 +1376,8 @@ impl core::ops::Index<core::ops::RangeFull> for ByteStr {
     type Output = ByteStr;
     
     #[inline]
-    fn index(&self, index: core::ops::RangeFull) -> &ByteStr {
-        ByteStr::from_slice(&self.as_slice()[index])
+    fn index(&self, _index: core::ops::RangeFull) -> &ByteStr {
+        ByteStr::from_slice(self.as_slice())
     }
 }
 
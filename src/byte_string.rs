@@ -7,10 +7,15 @@
 
 use std;
 use ByteStr;
+use byte_str::AsBytes;
+use IntoMatcher;
+use IntoSearcher;
+use FromUtf8Error;
+use utf8;
 
 // An owned byte string. It provides similar functionality as `String`
 /// and `Vec<u8>`.
-#[derive(Clone, Default, PartialEq, Eq)]
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ByteString {
     inner: Vec<u8>,
 }
@@ -64,6 +69,23 @@ impl ByteString {
         unsafe { Box::from_raw(Box::into_raw(self.into_boxed_slice()) as *mut ByteStr) }
     }
     
+    /// Converts `self` into a `String` without clones or allocation if it is
+    /// valid UTF-8. Otherwise, the error gives `self` back.
+    #[inline]
+    pub fn into_string(self) -> Result<String, FromUtf8Error> {
+        utf8::into_string(self)
+    }
+    
+    /// Converts `self` into a `String`, replacing each invalid UTF-8
+    /// sequence with U+FFFD REPLACEMENT CHARACTER. Only allocates if `self`
+    /// is not valid UTF-8.
+    pub fn into_string_lossy(self) -> String {
+        match self.into_string() {
+            Ok(string) => string,
+            Err(error) => utf8::to_str_lossy(error.as_byte_str().as_slice()).into_owned(),
+        }
+    }
+    
     /// Returns a `ByteStr` containing the entiere string.
     #[inline]
     pub fn as_byte_str(&self) -> &ByteStr {
@@ -118,6 +140,10 @@ impl ByteString {
     /// This will explicitly set the size of the vector, without actually modifying
     /// its buffers, so it is up to the caller to ensure that the vector is actually
     /// the specified size.
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as `Vec::set_len()`.
     #[inline]
     pub unsafe fn set_len(&mut self, len: usize) {
         self.as_mut_vec().set_len(len);
@@ -234,10 +260,10 @@ impl ByteString {
         self.reserve(other.len());
         
         unsafe {
-            std::ptr::copy(self.as_ptr().offset(index as isize),
-                           self.as_mut_ptr().offset((index + other.len()) as isize),
+            std::ptr::copy(self.as_ptr().add(index),
+                           self.as_mut_ptr().add(index + other.len()),
                            old_len - index);
-            std::ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().offset(index as isize), other.len());
+            std::ptr::copy_nonoverlapping(other.as_ptr(), self.as_mut_ptr().add(index), other.len());
         }
     }
     
@@ -255,7 +281,7 @@ impl ByteString {
     /// Creates a draining iterator that removes the specified range in the
     /// vector and yields the removed items.
     #[inline]
-    pub fn drain(&mut self, start: Option<usize>, end: Option<usize>) -> std::vec::Drain<u8> {
+    pub fn drain(&mut self, start: Option<usize>, end: Option<usize>) -> std::vec::Drain<'_, u8> {
         match (start, end) {
             (None, None) => self.as_mut_vec().drain(..),
             (Some(start), None) => self.as_mut_vec().drain(start ..),
@@ -276,6 +302,69 @@ impl ByteString {
     pub fn split_off(&mut self, at: usize) -> Self {
         Self::from_vec(self.as_mut_vec().split_off(at))
     }
+    
+    /// Replaces all the matches of a matcher with `to`, like
+    /// `ByteStr::replace`, but reusing the buffer of the string. All the
+    /// matches are found before replacing any of them, so they are the same
+    /// as in `replace`. Apart from the list of matches, nothing is allocated
+    /// unless the string grows past its capacity, or some replacements are
+    /// longer and others shorter than their matches and one of them does not
+    /// fit in the space left by the previous ones.
+    pub fn replace_in_place<M: IntoMatcher, B: ?Sized + AsRef<[u8]>>(&mut self, m: M, to: &B)
+        where for<'a> <M as IntoMatcher>::Matcher: IntoSearcher<'a>
+    {
+        let to = to.as_ref();
+        let matches: Vec<(usize, usize)> = self.match_indices(m).map(|(start, part)| (start, start + part.len())).collect();
+        let len = self.len();
+        if matches.iter().all(|&(a, b)| b - a <= to.len()) {
+            // Every replacement is at least as long as its match, so the
+            // bytes are moved from the back without overwriting the ones
+            // still to be moved.
+            let new_len = len + matches.iter().map(|&(a, b)| to.len() - (b - a)).sum::<usize>();
+            self.inner.resize(new_len, 0);
+            let (mut read, mut write) = (len, new_len);
+            for &(a, b) in matches.iter().rev() {
+                write -= read - b;
+                self.inner.copy_within(b .. read, write);
+                write -= to.len();
+                self.inner[write .. write + to.len()].copy_from_slice(to);
+                read = a;
+            }
+            return;
+        }
+        
+        // The bytes before `write` are the result, and those from `read` are
+        // the original ones. Once a replacement does not fit the result goes
+        // to `spill` instead.
+        let (mut read, mut write) = (0, 0);
+        let mut spill: Option<Vec<u8>> = None;
+        for &(a, b) in matches.iter() {
+            match spill {
+                None if write + (a - read) + to.len() <= b => {
+                    self.inner.copy_within(read .. a, write);
+                    write += a - read;
+                    self.inner[write .. write + to.len()].copy_from_slice(to);
+                    write += to.len();
+                }
+                _ => {
+                    let result = spill.get_or_insert_with(|| self.inner[.. write].to_vec());
+                    result.extend_from_slice(&self.inner[read .. a]);
+                    result.extend_from_slice(to);
+                }
+            }
+            read = b;
+        }
+        match spill {
+            None => {
+                self.inner.copy_within(read .., write);
+                self.inner.truncate(write + (len - read));
+            }
+            Some(mut result) => {
+                result.extend_from_slice(&self.inner[read ..]);
+                self.inner = result;
+            }
+        }
+    }
 }
 
 // From
@@ -352,6 +441,24 @@ impl std::fmt::Debug for ByteString {
     }
 }
 
+// Display
+impl std::fmt::Display for ByteString {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        std::fmt::Display::fmt(self.as_byte_str(), f)
+    }
+}
+
+// PartialEq and PartialOrd
+impl_partial_cmp!(['a] ByteString, &'a ByteStr);
+impl_partial_cmp!([] ByteString, [u8]);
+impl_partial_cmp!(['a] ByteString, &'a [u8]);
+impl_partial_cmp!([] ByteString, str);
+impl_partial_cmp!(['a] ByteString, &'a str);
+impl_partial_cmp!([] ByteString, Vec<u8>);
+impl_partial_cmp!([] ByteString, String);
+impl_partial_cmp!(['a] ByteString, std::borrow::Cow<'a, ByteStr>);
+
 // Deref
 impl std::ops::Deref for ByteString {
     type Target = ByteStr;
@@ -0,0 +1,710 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Matchers built from other matchers.
+//!
+//! The combinators only see the matches that their inputs report, they do
+//! not backtrack into other possible matches of the inputs. For example,
+//! `then` uses the match that the first matcher reports at a position, even
+//! if a shorter one would let the second matcher match. This is exact for
+//! matchers whose matches have a fixed length, such as strings and bytes.
+
+use ByteStr;
+use Matcher;
+use IntoMatcher;
+use PrefixMatcher;
+use SufixMatcher;
+use FullMatcher;
+use ForwardSearcher;
+use ReverseSearcher;
+use IntoSearcher;
+use IntoRSearcher;
+use Searcher;
+use RSearcher;
+use SearchStep;
+use FindSearcher;
+use RFindSearcher;
+use ByteSetMatcher;
+use search::prefix_len;
+use search::sufix_start;
+
+// MatcherExt
+/// Combinators available on everything that can be converted into a matcher.
+pub trait MatcherExt: IntoMatcher + Sized {
+    /// Returns a matcher that matches either `self` or `other`. Searches
+    /// report the match that starts first (or ends last, when searching
+    /// backwards), preferring `self` on ties.
+    #[inline]
+    fn or<N: IntoMatcher>(self, other: N) -> Or<Self::Matcher, N::Matcher> {
+        Or {
+            first: self.into_matcher(),
+            second: other.into_matcher(),
+        }
+    }
+    
+    /// Returns a matcher that matches `self` immediately followed by `other`.
+    #[inline]
+    fn then<N: IntoMatcher>(self, other: N) -> Then<Self::Matcher, N::Matcher> {
+        Then {
+            first: self.into_matcher(),
+            second: other.into_matcher(),
+        }
+    }
+}
+
+impl<M: IntoMatcher> MatcherExt for M {}
+
+/// Returns a matcher that matches any single byte that is not in `set`.
+pub fn not<M: IntoMatcher<Matcher = ByteSetMatcher>>(set: M) -> ByteSetMatcher {
+    let set = set.into_matcher();
+    let mut bytes = [0; 256];
+    let mut len = 0;
+    for byte in 0 ..= 255 {
+        if !set.contains(byte) {
+            bytes[len] = byte;
+            len += 1;
+        }
+    }
+    ByteSetMatcher::new(&bytes[.. len])
+}
+
+/// Returns a matcher that matches between `min` and `max` consecutive
+/// matches of `m`, as many as possible. Use `usize::MAX` as `max` for no
+/// upper bound.
+///
+/// # Panics
+///
+/// Panics if `min > max`.
+pub fn repeat<M: IntoMatcher>(m: M, min: usize, max: usize) -> Repeat<M::Matcher> {
+    assert!(min <= max, "repeat: min is greater than max");
+    Repeat {
+        matcher: m.into_matcher(),
+        min,
+        max,
+    }
+}
+
+// Or
+/// Matcher returned by `MatcherExt::or`.
+#[derive(Clone, Debug)]
+pub struct Or<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Matcher, B: Matcher> Matcher for Or<A, B> {}
+
+impl<A: Matcher, B: Matcher> IntoMatcher for Or<A, B> {
+    type Matcher = Self;
+    
+    #[inline]
+    fn into_matcher(self) -> Self {
+        self
+    }
+}
+
+impl<A: PrefixMatcher, B: PrefixMatcher> PrefixMatcher for Or<A, B> {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        self.first.is_prefix_of(haystack) || self.second.is_prefix_of(haystack)
+    }
+}
+
+impl<A: SufixMatcher, B: SufixMatcher> SufixMatcher for Or<A, B> {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        self.first.is_sufix_of(haystack) || self.second.is_sufix_of(haystack)
+    }
+}
+
+impl<A: FullMatcher, B: FullMatcher> FullMatcher for Or<A, B> {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        self.first.matches(haystack) || self.second.matches(haystack)
+    }
+}
+
+impl<A: ForwardSearcher, B: ForwardSearcher> ForwardSearcher for Or<A, B> {
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        match (self.first.find(haystack), self.second.find(haystack)) {
+            (Some(first), Some(second)) if second.0 < first.0 => Some(second),
+            (Some(first), _) => Some(first),
+            (None, second) => second,
+        }
+    }
+}
+
+impl<A: ReverseSearcher, B: ReverseSearcher> ReverseSearcher for Or<A, B> {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        match (self.first.rfind(haystack), self.second.rfind(haystack)) {
+            (Some(first), Some(second)) if second.1 > first.1 => Some(second),
+            (Some(first), _) => Some(first),
+            (None, second) => second,
+        }
+    }
+}
+
+impl<'a, A: ForwardSearcher, B: ForwardSearcher> IntoSearcher<'a> for Or<A, B> {
+    type Searcher = OrSearcher<'a, A, B>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+        OrSearcher::new(haystack, self.first, self.second, start)
+    }
+}
+
+impl<'a, A: ReverseSearcher, B: ReverseSearcher> IntoRSearcher<'a> for Or<A, B> {
+    type RSearcher = OrRSearcher<'a, A, B>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+        OrRSearcher::new(haystack, self.first, self.second, end)
+    }
+}
+
+impl<'a, A, B> IntoMatcher for &'a Or<A, B> where Or<A, B>: Matcher {
+    type Matcher = &'a Or<A, B>;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a Or<A, B> {
+        self
+    }
+}
+
+impl<'m, 'a, A: ForwardSearcher, B: ForwardSearcher> IntoSearcher<'a> for &'m Or<A, B> {
+    type Searcher = OrSearcher<'a, &'m A, &'m B>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+        OrSearcher::new(haystack, &self.first, &self.second, start)
+    }
+}
+
+impl<'m, 'a, A: ReverseSearcher, B: ReverseSearcher> IntoRSearcher<'a> for &'m Or<A, B> {
+    type RSearcher = OrRSearcher<'a, &'m A, &'m B>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+        OrRSearcher::new(haystack, &self.first, &self.second, end)
+    }
+}
+
+// OrSearcher
+/// `Searcher` of `Or`.
+///
+/// It keeps the next match of each side, so after a match only the side
+/// that reported it, or whose match overlaps it, searches again.
+#[derive(Clone, Debug)]
+pub struct OrSearcher<'a, A, B> {
+    haystack: &'a ByteStr,
+    first: A,
+    second: B,
+    pos: usize,
+    // Whether an empty match at `pos` has been reported.
+    empty: bool,
+    // Next match of each side, `None` if it has to search again.
+    first_match: Option<Option<(usize, usize)>>,
+    second_match: Option<Option<(usize, usize)>>,
+}
+
+impl<'a, A: ForwardSearcher, B: ForwardSearcher> OrSearcher<'a, A, B> {
+    #[inline]
+    fn new(haystack: &'a ByteStr, first: A, second: B, start: usize) -> Self {
+        Self {
+            haystack,
+            first,
+            second,
+            pos: start,
+            empty: false,
+            first_match: None,
+            second_match: None,
+        }
+    }
+    
+    // Returns the first match that starts at `pos` or later.
+    fn find(&mut self) -> Option<(usize, usize)> {
+        let (haystack, pos) = (self.haystack, self.pos);
+        let first = next_match(&self.first, haystack, pos, &mut self.first_match);
+        let second = next_match(&self.second, haystack, pos, &mut self.second_match);
+        match (first, second) {
+            (Some(first), Some(second)) if second.0 < first.0 => Some(second),
+            (Some(first), _) => Some(first),
+            (None, second) => second,
+        }
+    }
+}
+
+// Returns the first match of `m` that starts at `pos` or later, searching
+// again only if `pending` is not such a match.
+#[inline]
+fn next_match<M>(m: &M, haystack: &ByteStr, pos: usize, pending: &mut Option<Option<(usize, usize)>>) -> Option<(usize, usize)>
+    where M: ForwardSearcher
+{
+    match *pending {
+        Some(Some((a, _))) if a < pos => {}
+        Some(found) => return found,
+        None => {}
+    }
+    let found = m.find(&haystack[pos ..]).map(|(a, b)| (pos + a, pos + b));
+    *pending = Some(found);
+    found
+}
+
+impl<'a, A: ForwardSearcher, B: ForwardSearcher> Searcher<'a> for OrSearcher<'a, A, B> {
+    #[inline]
+    fn haystack(&self) -> &'a ByteStr {
+        self.haystack
+    }
+    
+    #[inline]
+    fn cursor(&self) -> usize {
+        self.pos
+    }
+    
+    fn next(&mut self) -> SearchStep {
+        let pos = self.pos;
+        if self.empty {
+            if pos == self.haystack.len() {
+                return SearchStep::Done;
+            }
+            self.empty = false;
+            self.pos += 1;
+            return SearchStep::Reject(pos, pos + 1);
+        }
+        match self.find() {
+            Some((a, b)) if a == pos => {
+                self.pos = b;
+                self.empty = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((a, _)) => {
+                // The match is found again by the next call.
+                self.pos = a;
+                SearchStep::Reject(pos, a)
+            }
+            None if pos != self.haystack.len() => {
+                self.pos = self.haystack.len();
+                SearchStep::Reject(pos, self.pos)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.empty {
+            if self.pos == self.haystack.len() {
+                return None;
+            }
+            self.empty = false;
+            self.pos += 1;
+        }
+        let (a, b) = self.find()?;
+        self.pos = b;
+        self.empty = a == b;
+        Some((a, b))
+    }
+}
+
+// OrRSearcher
+/// `RSearcher` of `Or`, which works like `OrSearcher` from the back.
+#[derive(Clone, Debug)]
+pub struct OrRSearcher<'a, A, B> {
+    haystack: &'a ByteStr,
+    first: A,
+    second: B,
+    pos: usize,
+    // Whether an empty match at `pos` has been reported.
+    empty: bool,
+    // Next match from the back of each side, `None` if it has to search
+    // again.
+    first_match: Option<Option<(usize, usize)>>,
+    second_match: Option<Option<(usize, usize)>>,
+}
+
+impl<'a, A: ReverseSearcher, B: ReverseSearcher> OrRSearcher<'a, A, B> {
+    #[inline]
+    fn new(haystack: &'a ByteStr, first: A, second: B, end: usize) -> Self {
+        Self {
+            haystack,
+            first,
+            second,
+            pos: end,
+            empty: false,
+            first_match: None,
+            second_match: None,
+        }
+    }
+    
+    // Returns the last match that ends at `pos` or earlier.
+    fn rfind(&mut self) -> Option<(usize, usize)> {
+        let (haystack, pos) = (self.haystack, self.pos);
+        let first = next_match_back(&self.first, haystack, pos, &mut self.first_match);
+        let second = next_match_back(&self.second, haystack, pos, &mut self.second_match);
+        match (first, second) {
+            (Some(first), Some(second)) if second.1 > first.1 => Some(second),
+            (Some(first), _) => Some(first),
+            (None, second) => second,
+        }
+    }
+}
+
+// Like `next_match`, for the matches that end at `pos` or earlier.
+#[inline]
+fn next_match_back<M>(m: &M, haystack: &ByteStr, pos: usize, pending: &mut Option<Option<(usize, usize)>>) -> Option<(usize, usize)>
+    where M: ReverseSearcher
+{
+    match *pending {
+        Some(Some((_, b))) if b > pos => {}
+        Some(found) => return found,
+        None => {}
+    }
+    let found = m.rfind(&haystack[.. pos]);
+    *pending = Some(found);
+    found
+}
+
+impl<'a, A: ReverseSearcher, B: ReverseSearcher> RSearcher<'a> for OrRSearcher<'a, A, B> {
+    #[inline]
+    fn cursor_back(&self) -> usize {
+        self.pos
+    }
+    
+    fn next_back(&mut self) -> SearchStep {
+        let pos = self.pos;
+        if self.empty {
+            if pos == 0 {
+                return SearchStep::Done;
+            }
+            self.empty = false;
+            self.pos -= 1;
+            return SearchStep::Reject(pos - 1, pos);
+        }
+        match self.rfind() {
+            Some((a, b)) if b == pos => {
+                self.pos = a;
+                self.empty = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((_, b)) => {
+                // The match is found again by the next call.
+                self.pos = b;
+                SearchStep::Reject(b, pos)
+            }
+            None if pos != 0 => {
+                self.pos = 0;
+                SearchStep::Reject(0, pos)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        if self.empty {
+            if self.pos == 0 {
+                return None;
+            }
+            self.empty = false;
+            self.pos -= 1;
+        }
+        let (a, b) = self.rfind()?;
+        self.pos = a;
+        self.empty = a == b;
+        Some((a, b))
+    }
+}
+
+// Then
+/// Matcher returned by `MatcherExt::then`.
+#[derive(Clone, Debug)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Matcher, B: Matcher> Matcher for Then<A, B> {}
+
+impl<A: Matcher, B: Matcher> IntoMatcher for Then<A, B> {
+    type Matcher = Self;
+    
+    #[inline]
+    fn into_matcher(self) -> Self {
+        self
+    }
+}
+
+impl<A, B> PrefixMatcher for Then<A, B>
+    where A: PrefixMatcher + ForwardSearcher, B: PrefixMatcher
+{
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        prefix_len(&self.first, haystack).map_or(false, |mid| self.second.is_prefix_of(&haystack[mid ..]))
+    }
+}
+
+impl<A, B> SufixMatcher for Then<A, B>
+    where A: SufixMatcher, B: SufixMatcher + ReverseSearcher
+{
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        sufix_start(&self.second, haystack).map_or(false, |mid| self.first.is_sufix_of(&haystack[.. mid]))
+    }
+}
+
+impl<A, B> FullMatcher for Then<A, B>
+    where A: PrefixMatcher + ForwardSearcher, B: FullMatcher
+{
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        prefix_len(&self.first, haystack).map_or(false, |mid| self.second.matches(&haystack[mid ..]))
+    }
+}
+
+impl<A, B> ForwardSearcher for Then<A, B>
+    where A: ForwardSearcher, B: PrefixMatcher + ForwardSearcher
+{
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        let mut pos = 0;
+        while pos <= haystack.len() {
+            let (start, mid) = self.first.find(&haystack[pos ..])?;
+            let (start, mid) = (pos + start, pos + mid);
+            if let Some(len) = prefix_len(&self.second, &haystack[mid ..]) {
+                return Some((start, mid + len));
+            }
+            pos = start + 1;
+        }
+        None
+    }
+}
+
+impl<A, B> ReverseSearcher for Then<A, B>
+    where A: SufixMatcher + ReverseSearcher, B: ReverseSearcher
+{
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        let mut end = haystack.len();
+        loop {
+            let (mid, stop) = self.second.rfind(&haystack[.. end])?;
+            if let Some(start) = sufix_start(&self.first, &haystack[.. mid]) {
+                return Some((start, stop));
+            }
+            if stop == 0 {
+                return None;
+            }
+            end = stop - 1;
+        }
+    }
+}
+
+impl<'a, A, B> IntoSearcher<'a> for Then<A, B>
+    where A: ForwardSearcher, B: PrefixMatcher + ForwardSearcher
+{
+    type Searcher = FindSearcher<'a, Self>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+impl<'a, A, B> IntoRSearcher<'a> for Then<A, B>
+    where A: SufixMatcher + ReverseSearcher, B: ReverseSearcher
+{
+    type RSearcher = RFindSearcher<'a, Self>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+        RFindSearcher::new_at(haystack, self, end)
+    }
+}
+
+impl<'a, A, B> IntoMatcher for &'a Then<A, B> where Then<A, B>: Matcher {
+    type Matcher = &'a Then<A, B>;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a Then<A, B> {
+        self
+    }
+}
+
+impl<'m, 'a, A, B> IntoSearcher<'a> for &'m Then<A, B> where Then<A, B>: ForwardSearcher {
+    type Searcher = FindSearcher<'a, &'m Then<A, B>>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+impl<'m, 'a, A, B> IntoRSearcher<'a> for &'m Then<A, B> where Then<A, B>: ReverseSearcher {
+    type RSearcher = RFindSearcher<'a, &'m Then<A, B>>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+        RFindSearcher::new_at(haystack, self, end)
+    }
+}
+
+// Repeat
+/// Matcher returned by `repeat`.
+#[derive(Clone, Debug)]
+pub struct Repeat<M> {
+    matcher: M,
+    min: usize,
+    max: usize,
+}
+
+impl<M: Matcher> Matcher for Repeat<M> {}
+
+impl<M: Matcher> IntoMatcher for Repeat<M> {
+    type Matcher = Self;
+    
+    #[inline]
+    fn into_matcher(self) -> Self {
+        self
+    }
+}
+
+impl<M: PrefixMatcher + ForwardSearcher> Repeat<M> {
+    // Matches as many repetitions as possible starting at `start`, returning
+    // whether there are at least `min` and where they end.
+    fn extend(&self, haystack: &ByteStr, start: usize) -> (bool, usize) {
+        let mut count = 0;
+        let mut end = start;
+        while count < self.max {
+            match prefix_len(&self.matcher, &haystack[end ..]) {
+                // An empty match can be repeated as many times as needed.
+                Some(0) => return (true, end),
+                Some(len) => end += len,
+                None => break,
+            }
+            count += 1;
+        }
+        (count >= self.min, end)
+    }
+}
+
+impl<M: SufixMatcher + ReverseSearcher> Repeat<M> {
+    // Matches as many repetitions as possible ending at `end`, returning
+    // whether there are at least `min` and where they start.
+    fn extend_back(&self, haystack: &ByteStr, end: usize) -> (bool, usize) {
+        let mut count = 0;
+        let mut start = end;
+        while count < self.max {
+            match sufix_start(&self.matcher, &haystack[.. start]) {
+                Some(i) if i == start => return (true, start),
+                Some(i) => start = i,
+                None => break,
+            }
+            count += 1;
+        }
+        (count >= self.min, start)
+    }
+}
+
+impl<M: PrefixMatcher + ForwardSearcher> PrefixMatcher for Repeat<M> {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        self.extend(haystack, 0).0
+    }
+}
+
+impl<M: SufixMatcher + ReverseSearcher> SufixMatcher for Repeat<M> {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        self.extend_back(haystack, haystack.len()).0
+    }
+}
+
+impl<M: PrefixMatcher + ForwardSearcher> FullMatcher for Repeat<M> {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        self.extend(haystack, 0) == (true, haystack.len())
+    }
+}
+
+impl<M: PrefixMatcher + ForwardSearcher> ForwardSearcher for Repeat<M> {
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        if self.min == 0 {
+            return Some((0, self.extend(haystack, 0).1));
+        }
+        let mut pos = 0;
+        while pos <= haystack.len() {
+            let (start, _) = self.matcher.find(&haystack[pos ..])?;
+            let start = pos + start;
+            if let (true, end) = self.extend(haystack, start) {
+                return Some((start, end));
+            }
+            pos = start + 1;
+        }
+        None
+    }
+}
+
+impl<M: SufixMatcher + ReverseSearcher> ReverseSearcher for Repeat<M> {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        if self.min == 0 {
+            return Some((self.extend_back(haystack, haystack.len()).1, haystack.len()));
+        }
+        let mut end = haystack.len();
+        loop {
+            let (_, stop) = self.matcher.rfind(&haystack[.. end])?;
+            if let (true, start) = self.extend_back(haystack, stop) {
+                return Some((start, stop));
+            }
+            if stop == 0 {
+                return None;
+            }
+            end = stop - 1;
+        }
+    }
+}
+
+impl<'a, M: PrefixMatcher + ForwardSearcher> IntoSearcher<'a> for Repeat<M> {
+    type Searcher = FindSearcher<'a, Self>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+impl<'a, M: SufixMatcher + ReverseSearcher> IntoRSearcher<'a> for Repeat<M> {
+    type RSearcher = RFindSearcher<'a, Self>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+        RFindSearcher::new_at(haystack, self, end)
+    }
+}
+
+impl<'a, M> IntoMatcher for &'a Repeat<M> where Repeat<M>: Matcher {
+    type Matcher = &'a Repeat<M>;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a Repeat<M> {
+        self
+    }
+}
+
+impl<'m, 'a, M> IntoSearcher<'a> for &'m Repeat<M> where Repeat<M>: ForwardSearcher {
+    type Searcher = FindSearcher<'a, &'m Repeat<M>>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+impl<'m, 'a, M> IntoRSearcher<'a> for &'m Repeat<M> where Repeat<M>: ReverseSearcher {
+    type RSearcher = RFindSearcher<'a, &'m Repeat<M>>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+        RFindSearcher::new_at(haystack, self, end)
+    }
+}
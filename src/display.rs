@@ -0,0 +1,181 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Formatting of byte strings.
+//!
+//! The `Display` implementation of `ByteStr` shows it as UTF-8, and the
+//! adapters of this module choose other representations. None of them
+//! allocate.
+
+use core;
+use core::fmt::Write;
+use ByteStr;
+use utf8;
+
+/// Writes `bytes` as UTF-8 followed by `suffix`, with U+FFFD REPLACEMENT
+/// CHARACTER in place of each maximal invalid subsequence. The width, fill,
+/// alignment and precision of `f` are applied to the whole output, like
+/// `str` does.
+pub fn pad_lossy(bytes: &[u8], suffix: &str, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+    if f.width().is_none() && f.precision().is_none() {
+        write_lossy(bytes, f)?;
+        return f.write_str(suffix);
+    }
+    if suffix.is_empty() && utf8::validate(bytes).is_ok() {
+        return f.pad(unsafe { core::str::from_utf8_unchecked(bytes) });
+    }
+    
+    let chars = || ByteStr::from_slice(bytes).chars().chain(suffix.chars());
+    let len = chars().count();
+    let shown = f.precision().map_or(len, |precision| core::cmp::min(precision, len));
+    let padding = f.width().map_or(0, |width| width.saturating_sub(shown));
+    let (before, after) = match f.align() {
+        Some(core::fmt::Alignment::Right) => (padding, 0),
+        Some(core::fmt::Alignment::Center) => (padding / 2, (padding + 1) / 2),
+        _ => (0, padding),
+    };
+    let fill = f.fill();
+    for _ in 0 .. before {
+        f.write_char(fill)?;
+    }
+    for c in chars().take(shown) {
+        f.write_char(c)?;
+    }
+    for _ in 0 .. after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+fn write_lossy(bytes: &[u8], f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+    let mut rest = bytes;
+    loop {
+        match utf8::validate(rest) {
+            Ok(()) => return f.write_str(unsafe { core::str::from_utf8_unchecked(rest) }),
+            Err(error) => {
+                let (valid, invalid) = rest.split_at(error.valid_up_to());
+                f.write_str(unsafe { core::str::from_utf8_unchecked(valid) })?;
+                f.write_char('\u{FFFD}')?;
+                rest = &invalid[error.error_len().unwrap_or(invalid.len()) ..];
+            }
+        }
+    }
+}
+
+/// Writes `bytes` with the escapes of a byte string literal: printable
+/// ASCII is written as is, and other bytes as `\t`, `\r`, `\n` or `\xNN`.
+/// Backslashes are escaped, and so are double quotes if `quote` is set.
+pub fn write_escaped(bytes: &[u8], quote: bool, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+    for &byte in bytes {
+        match byte {
+            b'\t' => f.write_str("\\t")?,
+            b'\r' => f.write_str("\\r")?,
+            b'\n' => f.write_str("\\n")?,
+            b'\\' => f.write_str("\\\\")?,
+            b'"' if quote => f.write_str("\\\"")?,
+            0x20 ..= 0x7E => f.write_char(byte as char)?,
+            _ => {
+                f.write_str("\\x")?;
+                write_hex(byte, f)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+fn write_hex(byte: u8, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    f.write_char(DIGITS[(byte >> 4) as usize] as char)?;
+    f.write_char(DIGITS[(byte & 0xF) as usize] as char)
+}
+
+// DisplayEscaped
+/// Shows a string with the escapes of a byte string literal, but without
+/// the quotes. Returned by `ByteStr::display_escaped`.
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayEscaped<'a> {
+    string: &'a ByteStr,
+}
+
+impl<'a> DisplayEscaped<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr) -> Self {
+        Self { string }
+    }
+}
+
+impl<'a> core::fmt::Display for DisplayEscaped<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        write_escaped(self.string.as_slice(), false, f)
+    }
+}
+
+// DisplayHex
+/// Shows a string as two lowercase hexadecimal digits per byte. Returned by
+/// `ByteStr::display_hex`.
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayHex<'a> {
+    string: &'a ByteStr,
+}
+
+impl<'a> DisplayHex<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr) -> Self {
+        Self { string }
+    }
+}
+
+impl<'a> core::fmt::Display for DisplayHex<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        for &byte in self.string.iter() {
+            write_hex(byte, f)?;
+        }
+        Ok(())
+    }
+}
+
+// DisplayTruncated
+/// Shows at most `max` bytes of a string like its `Display` implementation,
+/// followed by `...` if the string is longer. Width and precision apply to
+/// the whole output, including the `...`. The string is cut before a
+/// UTF-8 sequence that does not fit. Returned by
+/// `ByteStr::display_truncated`.
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayTruncated<'a> {
+    string: &'a ByteStr,
+    max: usize,
+}
+
+impl<'a> DisplayTruncated<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, max: usize) -> Self {
+        Self { string, max }
+    }
+}
+
+impl<'a> core::fmt::Display for DisplayTruncated<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        let bytes = self.string.as_slice();
+        if bytes.len() <= self.max {
+            return pad_lossy(bytes, "", f);
+        }
+        
+        // Only a sequence that is valid in the whole string moves the cut,
+        // an invalid one is shown as U+FFFD either way.
+        let mut cut = self.max;
+        for start in (cut.saturating_sub(3) .. cut).rev() {
+            if !utf8::is_continuation(bytes[start]) {
+                if utf8::decode(&bytes[start ..]).map_or(false, |(_, len)| start + len > cut) {
+                    cut = start;
+                }
+                break;
+            }
+        }
+        pad_lossy(&bytes[.. cut], "...", f)
+    }
+}
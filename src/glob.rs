@@ -0,0 +1,284 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Shell-style wildcard patterns.
+//!
+//! Globs are translated into a `Regex`, so matching takes linear time in the
+//! length of the haystack regardless of the number of wildcards.
+
+use std;
+use std::fmt::Write;
+
+use ByteStr;
+use ByteString;
+use Matcher;
+use IntoMatcher;
+use FullMatcher;
+use ForwardSearcher;
+use IntoSearcher;
+use FindSearcher;
+use regex::Regex;
+
+/// Selects how a `GlobMatcher` treats `/`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlobMode {
+    /// `/` is an ordinary byte, and `**` is the same as `*`.
+    Plain,
+    /// `*`, `?` and classes do not match `/`. `**` matches across
+    /// separators when it is a whole path component: `**/a` matches `a`
+    /// in any directory, `a/**` matches everything inside `a`, and
+    /// `a/**/b` matches `a/b`, `a/x/b`, `a/x/y/b` and so on.
+    Path,
+}
+
+/// Error returned when a glob pattern is not valid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GlobError {
+    message: &'static str,
+    position: usize,
+}
+
+impl GlobError {
+    fn new(message: &'static str, position: usize) -> Self {
+        Self { message, position }
+    }
+    
+    /// Returns the position in the pattern where the error was found.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl std::fmt::Display for GlobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "glob parse error at position {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for GlobError {}
+
+/// Matcher for shell-style wildcard patterns.
+///
+/// The pattern syntax is:
+///
+/// * `?` matches any byte.
+/// * `*` matches any sequence of bytes, including the empty one.
+/// * `[abc]`, `[a-z]` match a byte of the set, `[!abc]` (or `[^abc]`) a
+///   byte that is not in the set. A `]` right after the opening bracket is
+///   part of the set.
+/// * `\` makes the following byte, including a wildcard, match literally.
+///
+/// Patterns and haystacks are arbitrary bytes, they do not need to be valid
+/// UTF-8. See `GlobMode` for the path-aware semantics.
+///
+/// As a `FullMatcher`, the pattern must match the whole string. As a
+/// `ForwardSearcher`, it finds the leftmost substring that matches, with
+/// `*` matching as much as possible.
+#[derive(Clone, Debug)]
+pub struct GlobMatcher {
+    pattern: ByteString,
+    mode: GlobMode,
+    regex: Regex,
+}
+
+impl GlobMatcher {
+    /// Compiles `pattern` with `GlobMode::Plain`.
+    pub fn new<P: AsRef<[u8]>>(pattern: P) -> Result<Self, GlobError> {
+        Self::with_mode(GlobMode::Plain, pattern)
+    }
+    
+    /// Compiles `pattern` with the given mode.
+    pub fn with_mode<P: AsRef<[u8]>>(mode: GlobMode, pattern: P) -> Result<Self, GlobError> {
+        let pattern = pattern.as_ref();
+        let regex = Regex::new(&translate(mode, pattern)?).expect("invalid translated glob");
+        Ok(Self {
+            pattern: ByteString::from_slice(pattern),
+            mode,
+            regex,
+        })
+    }
+    
+    /// Returns the pattern of the matcher.
+    #[inline]
+    pub fn pattern(&self) -> &ByteStr {
+        &self.pattern
+    }
+    
+    /// Returns the mode of the matcher.
+    #[inline]
+    pub fn mode(&self) -> GlobMode {
+        self.mode
+    }
+}
+
+impl Matcher for GlobMatcher {}
+
+impl<'a> IntoMatcher for &'a GlobMatcher {
+    type Matcher = &'a GlobMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a GlobMatcher {
+        self
+    }
+}
+
+impl<'m, 'a> IntoSearcher<'a> for &'m GlobMatcher {
+    type Searcher = FindSearcher<'a, &'m GlobMatcher>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> FindSearcher<'a, &'m GlobMatcher> {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+impl FullMatcher for GlobMatcher {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        self.regex.matches(haystack)
+    }
+}
+
+impl ForwardSearcher for GlobMatcher {
+    #[inline]
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        self.regex.find(haystack)
+    }
+}
+
+const ANY: &str = "(?s:.)";
+const ANY_NOT_SEPARATOR: &str = "[^/]";
+
+// Translates a glob into the regex syntax. Every literal byte is written
+// as `\xNN`, so the result is ASCII.
+fn translate(mode: GlobMode, pattern: &[u8]) -> Result<String, GlobError> {
+    let any = if mode == GlobMode::Path { ANY_NOT_SEPARATOR } else { ANY };
+    let mut regex = String::new();
+    // `true` at the start of a path component.
+    let mut after_separator = true;
+    let mut i = 0;
+    while i < pattern.len() {
+        let at_component_start = after_separator;
+        after_separator = false;
+        match pattern[i] {
+            b'*' => {
+                let stars = pattern[i ..].iter().take_while(|&&byte| byte == b'*').count();
+                i += stars;
+                if mode == GlobMode::Path && stars == 2 && at_component_start {
+                    if i == pattern.len() {
+                        write!(regex, "{}*", ANY).unwrap();
+                        continue;
+                    } else if pattern[i] == b'/' {
+                        write!(regex, "(?:{}*/)?", ANY).unwrap();
+                        i += 1;
+                        after_separator = true;
+                        continue;
+                    }
+                }
+                write!(regex, "{}*", any).unwrap();
+            }
+            b'?' => {
+                regex.push_str(any);
+                i += 1;
+            }
+            b'[' => {
+                let (class, len) = parse_class(mode, &pattern[i ..]).map_err(|message| GlobError::new(message, i))?;
+                write_class(&mut regex, &class);
+                i += len;
+            }
+            b'\\' => {
+                let byte = match pattern.get(i + 1) {
+                    Some(&byte) => byte,
+                    None => return Err(GlobError::new("dangling escape", i)),
+                };
+                write!(regex, "\\x{:02X}", byte).unwrap();
+                after_separator = byte == b'/';
+                i += 2;
+            }
+            byte => {
+                write!(regex, "\\x{:02X}", byte).unwrap();
+                after_separator = byte == b'/';
+                i += 1;
+            }
+        }
+    }
+    Ok(regex)
+}
+
+// Parses the class at the start of `pattern`, returning the set of bytes
+// it matches and its length in the pattern.
+fn parse_class(mode: GlobMode, pattern: &[u8]) -> Result<([bool; 256], usize), &'static str> {
+    let mut set = [false; 256];
+    let mut i = 1;
+    let negated = match pattern.get(i) {
+        Some(&b'!') | Some(&b'^') => {
+            i += 1;
+            true
+        }
+        _ => false,
+    };
+    let items_start = i;
+    loop {
+        let mut low = match pattern.get(i) {
+            Some(&b']') if i != items_start => break,
+            Some(&byte) => byte,
+            None => return Err("unclosed character class"),
+        };
+        if low == b'\\' {
+            i += 1;
+            low = *pattern.get(i).ok_or("unclosed character class")?;
+        }
+        i += 1;
+        
+        let mut high = low;
+        if pattern.get(i) == Some(&b'-') && pattern.get(i + 1).map_or(false, |&byte| byte != b']') {
+            high = pattern[i + 1];
+            i += 2;
+            if high == b'\\' {
+                high = *pattern.get(i).ok_or("unclosed character class")?;
+                i += 1;
+            }
+            if high < low {
+                return Err("invalid range in character class");
+            }
+        }
+        for byte in low ..= high {
+            set[byte as usize] = true;
+        }
+    }
+    
+    if negated {
+        for member in set.iter_mut() {
+            *member = !*member;
+        }
+    }
+    if mode == GlobMode::Path {
+        set[b'/' as usize] = false;
+    }
+    Ok((set, i + 1))
+}
+
+fn write_class(regex: &mut String, set: &[bool; 256]) {
+    if set.iter().all(|&member| !member) {
+        // Empty class, which never matches.
+        regex.push_str("[^\\x00-\\xFF]");
+        return;
+    }
+    regex.push('[');
+    let mut byte = 0;
+    while byte < 256 {
+        if set[byte] {
+            let start = byte;
+            while byte + 1 < 256 && set[byte + 1] {
+                byte += 1;
+            }
+            write!(regex, "\\x{:02X}-\\x{:02X}", start, byte).unwrap();
+        }
+        byte += 1;
+    }
+    regex.push(']');
+}
@@ -7,10 +7,19 @@
 
 use core;
 use ByteStr;
-use Matcher;
 use ForwardSearcher;
 use ReverseSearcher;
-use DoubleEndedSearcher;
+use Searcher;
+use RSearcher;
+use IntoSearcher;
+use IntoRSearcher;
+use memchr;
+use utf8;
+use ByteSetMatcher;
+#[cfg(not(feature="no_std"))]
+use AhoCorasickMatcher;
+#[cfg(not(feature="no_std"))]
+use AhoCorasickSearcher;
 
 // Windows
 #[derive(Clone)]
@@ -36,7 +45,7 @@ impl<'a> Iterator for Windows<'a> {
     
     #[inline]
     fn next(&mut self) -> Option<&'a ByteStr> {
-        self.inner.next().map(|s| ByteStr::from_slice(s))
+        self.inner.next().map(ByteStr::from_slice)
     }
     
     #[inline]
@@ -51,19 +60,19 @@ impl<'a> Iterator for Windows<'a> {
     
     #[inline]
     fn nth(&mut self, n: usize) -> Option<&'a ByteStr> {
-        self.inner.nth(n).map(|s| ByteStr::from_slice(s))
+        self.inner.nth(n).map(ByteStr::from_slice)
     }
     
     #[inline]
     fn last(self) -> Option<&'a ByteStr> {
-        self.inner.last().map(|s| ByteStr::from_slice(s))
+        self.inner.last().map(ByteStr::from_slice)
     }
 }
 
 impl<'a> DoubleEndedIterator for Windows<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<&'a ByteStr> {
-        self.inner.next_back().map(|s| ByteStr::from_slice(s))
+        self.inner.next_back().map(ByteStr::from_slice)
     }
 }
 
@@ -100,7 +109,7 @@ impl<'a> Iterator for Chunks<'a> {
     
     #[inline]
     fn next(&mut self) -> Option<&'a ByteStr> {
-        self.inner.next().map(|s| ByteStr::from_slice(s))
+        self.inner.next().map(ByteStr::from_slice)
     }
     
     #[inline]
@@ -115,19 +124,19 @@ impl<'a> Iterator for Chunks<'a> {
     
     #[inline]
     fn nth(&mut self, n: usize) -> Option<&'a ByteStr> {
-        self.inner.nth(n).map(|s| ByteStr::from_slice(s))
+        self.inner.nth(n).map(ByteStr::from_slice)
     }
     
     #[inline]
     fn last(self) -> Option<&'a ByteStr> {
-        self.inner.last().map(|s| ByteStr::from_slice(s))
+        self.inner.last().map(ByteStr::from_slice)
     }
 }
 
 impl<'a> DoubleEndedIterator for Chunks<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<&'a ByteStr> {
-        self.inner.next_back().map(|s| ByteStr::from_slice(s))
+        self.inner.next_back().map(ByteStr::from_slice)
     }
 }
 
@@ -203,157 +212,615 @@ impl<'a> ExactSizeIterator for ChunksMut<'a> {
 
 //impl<'a> FusedIterator for ChunksMut<'a> {}
 
+// CharsBase
+#[derive(Clone, Debug)]
+struct CharsBase<'a> {
+    string: &'a ByteStr,
+    // Bounds of the part of the string that has not been decoded.
+    start: usize,
+    end: usize,
+}
+
+impl<'a> CharsBase<'a> {
+    #[inline]
+    fn new(string: &'a ByteStr) -> Self {
+        Self { string, start: 0, end: string.len() }
+    }
+    
+    // Returns the next char with its offset, U+FFFD for an invalid sequence.
+    #[inline]
+    fn next_forwards(&mut self) -> Option<(usize, char)> {
+        if self.start == self.end {
+            return None;
+        }
+        let (c, len) = utf8::decode_lossy(&self.string.as_slice()[self.start .. self.end]);
+        let offset = self.start;
+        self.start += len;
+        Some((offset, c.unwrap_or('\u{FFFD}')))
+    }
+    
+    #[inline]
+    fn next_backwards(&mut self) -> Option<(usize, char)> {
+        if self.start == self.end {
+            return None;
+        }
+        let (c, len) = utf8::decode_last_lossy(&self.string.as_slice()[self.start .. self.end]);
+        self.end -= len;
+        Some((self.end, c.unwrap_or('\u{FFFD}')))
+    }
+    
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        ((len + 3) / 4, Some(len))
+    }
+}
+
+// Chars
+#[derive(Clone, Debug)]
+pub struct Chars<'a> {
+    base: CharsBase<'a>,
+}
+
+impl<'a> Chars<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr) -> Self {
+        Self { base: CharsBase::new(string) }
+    }
+}
+
+impl<'a> Iterator for Chars<'a> {
+    type Item = char;
+    
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        self.base.next_forwards().map(|(_, c)| c)
+    }
+    
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Chars<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<char> {
+        self.base.next_backwards().map(|(_, c)| c)
+    }
+}
+
+// CharIndices
+#[derive(Clone, Debug)]
+pub struct CharIndices<'a> {
+    base: CharsBase<'a>,
+}
+
+impl<'a> CharIndices<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr) -> Self {
+        Self { base: CharsBase::new(string) }
+    }
+}
+
+impl<'a> Iterator for CharIndices<'a> {
+    type Item = (usize, char);
+    
+    #[inline]
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.base.next_forwards()
+    }
+    
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.base.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for CharIndices<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(usize, char)> {
+        self.base.next_backwards()
+    }
+}
+
+// Utf8Chunks
+#[derive(Clone, Debug)]
+pub struct Utf8Chunks<'a> {
+    string: &'a ByteStr,
+    // Bounds of the part of the string that has not been returned.
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Utf8Chunks<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr) -> Self {
+        Self { string, start: 0, end: string.len() }
+    }
+}
+
+impl<'a> Iterator for Utf8Chunks<'a> {
+    type Item = (&'a str, &'a ByteStr);
+    
+    fn next(&mut self) -> Option<(&'a str, &'a ByteStr)> {
+        if self.start == self.end {
+            return None;
+        }
+        let rest = &self.string[self.start .. self.end];
+        let (valid, invalid) = match utf8::validate(rest.as_slice()) {
+            Ok(()) => (rest.len(), 0),
+            Err(error) => {
+                let valid = error.valid_up_to();
+                (valid, error.error_len().unwrap_or(rest.len() - valid))
+            }
+        };
+        self.start += valid + invalid;
+        let (valid, rest) = rest.split_at(valid);
+        Some((unsafe { core::str::from_utf8_unchecked(valid.as_slice()) }, &rest[.. invalid]))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Utf8Chunks<'a> {
+    fn next_back(&mut self) -> Option<(&'a str, &'a ByteStr)> {
+        if self.start == self.end {
+            return None;
+        }
+        let rest = &self.string[self.start .. self.end];
+        let bytes = rest.as_slice();
+        let mut valid_end = bytes.len();
+        if let (None, len) = utf8::decode_last_lossy(bytes) {
+            valid_end -= len;
+        }
+        let mut valid_start = valid_end;
+        while valid_start != 0 {
+            match utf8::decode_last_lossy(&bytes[.. valid_start]) {
+                (Some(_), len) => valid_start -= len,
+                (None, _) => break,
+            }
+        }
+        self.end = self.start + valid_start;
+        let valid = unsafe { core::str::from_utf8_unchecked(&bytes[valid_start .. valid_end]) };
+        Some((valid, &rest[valid_end ..]))
+    }
+}
+
 // SplitBase
 #[derive(Clone, Debug)]
-struct SplitBase<'a, M: Matcher> {
+struct SplitBase<'a, S> {
     string: &'a ByteStr,
-    matcher: M,
+    searcher: S,
+    // Bounds of the part of the string that has not been returned.
+    start: usize,
+    end: usize,
     finished: bool,
+    // Whether an empty last item is returned, and whether items keep the
+    // match that ends them.
+    allow_trailing_empty: bool,
+    inclusive: bool,
 }
 
-impl<'a, M: Matcher> SplitBase<'a, M> {
+impl<'a, S> SplitBase<'a, S> {
     #[inline]
-    fn new(string: &'a ByteStr, matcher: M) -> Self {
+    fn new(string: &'a ByteStr, searcher: S) -> Self {
         Self {
-            string: string,
-            matcher: matcher,
+            string,
+            searcher,
+            start: 0,
+            end: string.len(),
             finished: false,
+            allow_trailing_empty: true,
+            inclusive: false,
         }
     }
     
+    // Skips the empty last item, like `split_terminator`.
+    #[inline]
+    fn terminator(mut self) -> Self {
+        self.allow_trailing_empty = false;
+        self
+    }
+    
+    // Keeps the matches at the end of the items, like `split_inclusive`.
     #[inline]
-    fn remaining(&mut self) -> &'a ByteStr {
-        core::mem::replace(&mut self.string, ByteStr::empty())
+    fn inclusive(mut self) -> Self {
+        self.allow_trailing_empty = false;
+        self.inclusive = true;
+        self
+    }
+    
+    #[inline]
+    fn remaining(&mut self) -> Option<&'a ByteStr> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        if self.allow_trailing_empty || self.start != self.end {
+            Some(&self.string[self.start .. self.end])
+        } else {
+            None
+        }
     }
 }
 
-impl<'a, M: ForwardSearcher> SplitBase<'a, M> {
+impl<'a, S: Searcher<'a>> SplitBase<'a, S> {
     #[inline]
     pub fn next_forwards(&mut self) -> Option<&'a ByteStr> {
-        if !self.finished {
-            match self.matcher.find(self.string) {
-                Some((a, b)) => {
-                    let (ret, rest) = self.string.split_at(b);
-                    self.string = rest;
-                    Some(&ret[.. a])
-                }
-                None => {
-                    let ret = core::mem::replace(&mut self.string, ByteStr::empty());
-                    self.finished = true;
-                    Some(ret)
-                }
+        if self.finished {
+            return None;
+        }
+        match self.searcher.next_match() {
+            Some((a, b)) => {
+                let ret = &self.string[self.start .. if self.inclusive { b } else { a }];
+                self.start = b;
+                Some(ret)
             }
-        } else {
-            None
+            None => self.remaining(),
         }
     }
 }
 
-impl<'a, M: ReverseSearcher> SplitBase<'a, M> {
-    #[inline]
+impl<'a, S: RSearcher<'a>> SplitBase<'a, S> {
     pub fn next_backwards(&mut self) -> Option<&'a ByteStr> {
-        if !self.finished {
-            match self.matcher.rfind(self.string) {
-                Some((a, b)) => {
-                    let (rest, ret) = self.string.split_at(b);
-                    self.string = &rest[.. a];
-                    Some(ret)
-                }
-                None => {
-                    let ret = core::mem::replace(&mut self.string, ByteStr::empty());
-                    self.finished = true;
-                    Some(ret)
-                }
+        if self.finished {
+            return None;
+        }
+        if !self.allow_trailing_empty {
+            // The first item from the back is the last one, which is
+            // skipped if it is empty.
+            self.allow_trailing_empty = true;
+            match self.next_backwards_step() {
+                Some(ret) if !ret.is_empty() => return Some(ret),
+                _ if self.finished => return None,
+                _ => {}
             }
-        } else {
-            None
+        }
+        self.next_backwards_step()
+    }
+    
+    #[inline]
+    fn next_backwards_step(&mut self) -> Option<&'a ByteStr> {
+        match self.searcher.next_match_back() {
+            Some((a, b)) => {
+                let ret = &self.string[b .. self.end];
+                self.end = if self.inclusive { b } else { a };
+                Some(ret)
+            }
+            None => self.remaining(),
         }
     }
 }
 
+// MutMatches
+// The matches that an iterator over a mutable string splits it at. No part
+// of the string can be returned while a searcher borrows it, so all the
+// matches are found first, by a searcher over the whole string. Without an
+// allocator, the matcher is cloned instead to search the part that has not
+// been returned each time, which does not see the bytes around that part.
+#[derive(Debug)]
+struct MutMatches<M> {
+    #[cfg(not(feature="no_std"))]
+    matches: std::collections::VecDeque<(usize, usize)>,
+    #[cfg(not(feature="no_std"))]
+    matcher: core::marker::PhantomData<M>,
+    #[cfg(feature="no_std")]
+    matcher: M,
+    // Whether an empty match at the start or at the end of the searched
+    // part has been used.
+    #[cfg(feature="no_std")]
+    empty_front: bool,
+    #[cfg(feature="no_std")]
+    empty_back: bool,
+}
+
+#[cfg(not(feature="no_std"))]
+impl<M> MutMatches<M> {
+    fn forward(string: &ByteStr, matcher: M) -> Self where M: for<'s> IntoSearcher<'s> {
+        let mut searcher = matcher.into_searcher(string);
+        Self {
+            matches: core::iter::from_fn(|| searcher.next_match()).collect(),
+            matcher: core::marker::PhantomData,
+        }
+    }
+    
+    fn backward(string: &ByteStr, matcher: M) -> Self where M: for<'s> IntoRSearcher<'s> {
+        let mut searcher = matcher.into_rsearcher(string);
+        let mut matches = std::collections::VecDeque::new();
+        while let Some(found) = searcher.next_match_back() {
+            matches.push_front(found);
+        }
+        Self { matches, matcher: core::marker::PhantomData }
+    }
+    
+    // The matches have already been found, so the part of the string that
+    // would be searched is ignored.
+    #[inline]
+    fn next_forwards(&mut self, _: &ByteStr, _: usize) -> Option<(usize, usize)> {
+        self.matches.pop_front()
+    }
+    
+    #[inline]
+    fn next_backwards(&mut self, _: &ByteStr, _: usize) -> Option<(usize, usize)> {
+        self.matches.pop_back()
+    }
+    
+    #[inline]
+    fn next_back_forwards(&mut self, _: &ByteStr, _: usize) -> Option<(usize, usize)> {
+        self.matches.pop_back()
+    }
+    
+    #[inline]
+    fn next_back_backwards(&mut self, _: &ByteStr, _: usize) -> Option<(usize, usize)> {
+        self.matches.pop_front()
+    }
+}
+
+#[cfg(feature="no_std")]
+impl<M> MutMatches<M> {
+    #[inline]
+    fn forward(_: &ByteStr, matcher: M) -> Self {
+        Self { matcher, empty_front: false, empty_back: false }
+    }
+    
+    #[inline]
+    fn backward(string: &ByteStr, matcher: M) -> Self {
+        Self::forward(string, matcher)
+    }
+    
+    // Returns the first match of `searcher`, which searches `len` bytes at
+    // `offset`, skipping the empty matches that have been used.
+    fn first<'s, S: Searcher<'s>>(&mut self, mut searcher: S, len: usize, offset: usize) -> Option<(usize, usize)> {
+        let mut found = searcher.next_match();
+        if self.empty_front && found == Some((0, 0)) {
+            found = searcher.next_match();
+        }
+        match found {
+            Some((a, b)) if a == b && a == len && self.empty_back => None,
+            Some((a, b)) => {
+                self.empty_front = a == b;
+                Some((offset + a, offset + b))
+            }
+            None => None,
+        }
+    }
+    
+    // Like `first`, for the searches from the back.
+    fn last<'s, S: RSearcher<'s>>(&mut self, mut searcher: S, len: usize, offset: usize) -> Option<(usize, usize)> {
+        let mut found = searcher.next_match_back();
+        if self.empty_back && found == Some((len, len)) {
+            found = searcher.next_match_back();
+        }
+        match found {
+            Some((a, b)) if a == b && a == 0 && self.empty_front => None,
+            Some((a, b)) => {
+                self.empty_back = a == b;
+                Some((offset + a, offset + b))
+            }
+            None => None,
+        }
+    }
+}
+
+#[cfg(feature="no_std")]
+impl<M: Clone + for<'s> IntoSearcher<'s>> MutMatches<M> {
+    #[inline]
+    fn next_forwards(&mut self, searched: &ByteStr, offset: usize) -> Option<(usize, usize)> {
+        let searcher = self.matcher.clone().into_searcher(searched);
+        self.first(searcher, searched.len(), offset)
+    }
+}
+
+#[cfg(feature="no_std")]
+impl<M: Clone + for<'s> IntoRSearcher<'s>> MutMatches<M> {
+    #[inline]
+    fn next_backwards(&mut self, searched: &ByteStr, offset: usize) -> Option<(usize, usize)> {
+        let searcher = self.matcher.clone().into_rsearcher(searched);
+        self.last(searcher, searched.len(), offset)
+    }
+}
+
+#[cfg(feature="no_std")]
+impl<M> MutMatches<M>
+    where M: Clone + for<'s> IntoSearcher<'s>, for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
+    #[inline]
+    fn next_back_forwards(&mut self, searched: &ByteStr, offset: usize) -> Option<(usize, usize)> {
+        let searcher = self.matcher.clone().into_searcher(searched);
+        self.last(searcher, searched.len(), offset)
+    }
+}
+
+#[cfg(feature="no_std")]
+impl<M> MutMatches<M>
+    where M: Clone + for<'s> IntoRSearcher<'s>, for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
+    #[inline]
+    fn next_back_backwards(&mut self, searched: &ByteStr, offset: usize) -> Option<(usize, usize)> {
+        let searcher = self.matcher.clone().into_rsearcher(searched);
+        self.first(searcher, searched.len(), offset)
+    }
+}
+
 // SplitBaseMut
 #[derive(Debug)]
-struct SplitBaseMut<'a, M: Matcher> {
+struct SplitBaseMut<'a, M> {
     string: &'a mut ByteStr,
-    matcher: M,
+    matches: MutMatches<M>,
+    // Position of `string` in the whole string.
+    start: usize,
     finished: bool,
+    allow_trailing_empty: bool,
+    inclusive: bool,
+    // Length of the match at the end of `string` that an inclusive item
+    // from the back has left in it, which is not searched again.
+    kept_back: usize,
 }
 
-impl<'a, M: Matcher> SplitBaseMut<'a, M> {
+impl<'a, M> SplitBaseMut<'a, M> {
     #[inline]
-    fn new(string: &'a mut ByteStr, matcher: M) -> Self {
+    fn new(string: &'a mut ByteStr, matches: MutMatches<M>) -> Self {
         Self {
-            string: string,
-            matcher: matcher,
+            string,
+            matches,
+            start: 0,
             finished: false,
+            allow_trailing_empty: true,
+            inclusive: false,
+            kept_back: 0,
         }
     }
     
     #[inline]
-    fn remaining(&mut self) -> &'a mut ByteStr {
-        core::mem::replace(&mut self.string, ByteStr::empty_mut())
+    fn terminator(mut self) -> Self {
+        self.allow_trailing_empty = false;
+        self
     }
-}
-
-impl<'a, M: ForwardSearcher> SplitBaseMut<'a, M> {
-    fn next_forwards(&mut self) -> Option<&'a mut ByteStr> {
-        if !self.finished {
-            match self.matcher.find(self.string) {
-                Some((a, b)) => {
-                    let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
-                    let (ret, rest) = string.split_at_mut(b);
-                    self.string = rest;
-                    Some(&mut ret[.. a])
-                }
-                None => {
-                    let ret = core::mem::replace(&mut self.string, ByteStr::empty_mut());
-                    self.finished = true;
-                    Some(ret)
-                }
-            }
+    
+    #[inline]
+    fn inclusive(mut self) -> Self {
+        self.allow_trailing_empty = false;
+        self.inclusive = true;
+        self
+    }
+    
+    #[inline]
+    fn remaining(&mut self) -> Option<&'a mut ByteStr> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
+        if self.allow_trailing_empty || !string.is_empty() {
+            Some(string)
         } else {
             None
         }
     }
-}
-
-impl<'a, M: ReverseSearcher> SplitBaseMut<'a, M> {
-    fn next_backwards(&mut self) -> Option<&'a mut ByteStr> {
-        if !self.finished {
-            match self.matcher.rfind(self.string) {
-                Some((a, b)) => {
-                    let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
-                    let (rest, ret) = string.split_at_mut(b);
+    
+    // Takes the next match from the front, which `find` returns from the
+    // part of the string that has not been searched.
+    fn next_front<F>(&mut self, find: F) -> Option<&'a mut ByteStr>
+        where F: FnOnce(&mut MutMatches<M>, &ByteStr, usize) -> Option<(usize, usize)>
+    {
+        if self.finished {
+            return None;
+        }
+        let searched = &self.string[.. self.string.len() - self.kept_back];
+        match find(&mut self.matches, searched, self.start) {
+            Some((a, b)) => {
+                let (a, b) = (a - self.start, b - self.start);
+                let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
+                let (ret, rest) = string.split_at_mut(b);
+                self.string = rest;
+                self.start += b;
+                Some(if self.inclusive { ret } else { &mut ret[.. a] })
+            }
+            None => self.remaining(),
+        }
+    }
+    
+    fn next_back<F>(&mut self, mut find: F) -> Option<&'a mut ByteStr>
+        where F: FnMut(&mut MutMatches<M>, &ByteStr, usize) -> Option<(usize, usize)>
+    {
+        if self.finished {
+            return None;
+        }
+        if !self.allow_trailing_empty {
+            // The first item from the back is the last one, which is
+            // skipped if it is empty.
+            self.allow_trailing_empty = true;
+            match self.next_back_step(&mut find) {
+                Some(ret) if !ret.is_empty() => return Some(ret),
+                _ if self.finished => return None,
+                _ => {}
+            }
+        }
+        self.next_back_step(&mut find)
+    }
+    
+    fn next_back_step<F>(&mut self, find: &mut F) -> Option<&'a mut ByteStr>
+        where F: FnMut(&mut MutMatches<M>, &ByteStr, usize) -> Option<(usize, usize)>
+    {
+        let searched = &self.string[.. self.string.len() - self.kept_back];
+        match find(&mut self.matches, searched, self.start) {
+            Some((a, b)) => {
+                let (a, b) = (a - self.start, b - self.start);
+                let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
+                let (rest, ret) = string.split_at_mut(b);
+                if self.inclusive {
+                    self.string = rest;
+                    self.kept_back = b - a;
+                } else {
                     self.string = &mut rest[.. a];
-                    Some(ret)
-                }
-                None => {
-                    let ret = core::mem::replace(&mut self.string, ByteStr::empty_mut());
-                    self.finished = true;
-                    Some(ret)
+                    self.kept_back = 0;
                 }
+                Some(ret)
             }
-        } else {
-            None
+            None => self.remaining(),
         }
     }
 }
 
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> SplitBaseMut<'a, M> {
+    #[inline]
+    fn forward(string: &'a mut ByteStr, matcher: M) -> Self {
+        let matches = MutMatches::forward(string, matcher);
+        Self::new(string, matches)
+    }
+    
+    #[inline]
+    fn next_forwards(&mut self) -> Option<&'a mut ByteStr> {
+        self.next_front(MutMatches::next_forwards)
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> SplitBaseMut<'a, M> {
+    #[inline]
+    fn backward(string: &'a mut ByteStr, matcher: M) -> Self {
+        let matches = MutMatches::backward(string, matcher);
+        Self::new(string, matches)
+    }
+    
+    #[inline]
+    fn next_backwards(&mut self) -> Option<&'a mut ByteStr> {
+        self.next_back(MutMatches::next_backwards)
+    }
+}
+
+impl<'a, M> SplitBaseMut<'a, M>
+    where M: Clone + for<'s> IntoSearcher<'s>, for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
+    // Returns the last item that `next_forwards` would return.
+    #[inline]
+    fn next_back_forwards(&mut self) -> Option<&'a mut ByteStr> {
+        self.next_back(MutMatches::next_back_forwards)
+    }
+}
+
+impl<'a, M> SplitBaseMut<'a, M>
+    where M: Clone + for<'s> IntoRSearcher<'s>, for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
+    // Returns the last item that `next_backwards` would return.
+    #[inline]
+    fn next_back_backwards(&mut self) -> Option<&'a mut ByteStr> {
+        self.next_front(MutMatches::next_back_backwards)
+    }
+}
 
 // Split
 #[derive(Clone, Debug)]
-pub struct Split<'a, M: ForwardSearcher> {
-    base: SplitBase<'a, M>,
+pub struct Split<'a, M: IntoSearcher<'a>> {
+    base: SplitBase<'a, M::Searcher>,
 }
 
-impl<'a, M: ForwardSearcher> Split<'a, M> {
+impl<'a, M: IntoSearcher<'a>> Split<'a, M> {
     #[inline]
     pub fn new(string: &'a ByteStr, matcher: M) -> Self {
-        Self { base: SplitBase::new(string, matcher) }
+        Self { base: SplitBase::new(string, matcher.into_searcher(string)) }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for Split<'a, M> {
+impl<'a, M: IntoSearcher<'a>> Iterator for Split<'a, M> {
     type Item = &'a ByteStr;
     
     #[inline]
@@ -362,7 +829,7 @@ impl<'a, M: ForwardSearcher> Iterator for Split<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for Split<'a, M> {
+impl<'a, M: IntoSearcher<'a>> DoubleEndedIterator for Split<'a, M> where M::Searcher: RSearcher<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<&'a ByteStr> {
         self.base.next_backwards()
@@ -371,18 +838,18 @@ impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for Split<'a, M> {
 
 // SplitMut
 #[derive(Debug)]
-pub struct SplitMut<'a, M: ForwardSearcher> {
+pub struct SplitMut<'a, M: Clone + for<'s> IntoSearcher<'s>> {
     base: SplitBaseMut<'a, M>,
 }
 
-impl<'a, M: ForwardSearcher> SplitMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> SplitMut<'a, M> {
     #[inline]
     pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
-        Self { base: SplitBaseMut::new(string, matcher) }
+        Self { base: SplitBaseMut::forward(string, matcher) }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for SplitMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> Iterator for SplitMut<'a, M> {
     type Item = &'a mut ByteStr;
     
     #[inline]
@@ -391,27 +858,29 @@ impl<'a, M: ForwardSearcher> Iterator for SplitMut<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for SplitMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> DoubleEndedIterator for SplitMut<'a, M>
+    where for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
     #[inline]
     fn next_back(&mut self) -> Option<&'a mut ByteStr> {
-        self.base.next_backwards()
+        self.base.next_back_forwards()
     }
 }
 
 // RSplit
 #[derive(Clone, Debug)]
-pub struct RSplit<'a, M: ReverseSearcher> {
-    base: SplitBase<'a, M>,
+pub struct RSplit<'a, M: IntoRSearcher<'a>> {
+    base: SplitBase<'a, M::RSearcher>,
 }
 
-impl<'a, M: ReverseSearcher> RSplit<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> RSplit<'a, M> {
     #[inline]
     pub fn new(string: &'a ByteStr, matcher: M) -> Self {
-        Self { base: SplitBase::new(string, matcher) }
+        Self { base: SplitBase::new(string, matcher.into_rsearcher(string)) }
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RSplit<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> Iterator for RSplit<'a, M> {
     type Item = &'a ByteStr;
     
     #[inline]
@@ -420,7 +889,7 @@ impl<'a, M: ReverseSearcher> Iterator for RSplit<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RSplit<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> DoubleEndedIterator for RSplit<'a, M> where M::RSearcher: Searcher<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<&'a ByteStr> {
         self.base.next_forwards()
@@ -429,18 +898,18 @@ impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RSplit<'a, M> {
 
 // RSplitMut
 #[derive(Debug)]
-pub struct RSplitMut<'a, M: ReverseSearcher> {
+pub struct RSplitMut<'a, M: Clone + for<'s> IntoRSearcher<'s>> {
     base: SplitBaseMut<'a, M>,
 }
 
-impl<'a, M: ReverseSearcher> RSplitMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> RSplitMut<'a, M> {
     #[inline]
     pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
-        Self { base: SplitBaseMut::new(string, matcher) }
+        Self { base: SplitBaseMut::backward(string, matcher) }
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RSplitMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> Iterator for RSplitMut<'a, M> {
     type Item = &'a mut ByteStr;
     
     #[inline]
@@ -449,31 +918,273 @@ impl<'a, M: ReverseSearcher> Iterator for RSplitMut<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RSplitMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> DoubleEndedIterator for RSplitMut<'a, M>
+    where for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
     #[inline]
     fn next_back(&mut self) -> Option<&'a mut ByteStr> {
-        self.base.next_forwards()
+        self.base.next_back_backwards()
     }
 }
 
-// SplitN
+// SplitTerminator
 #[derive(Clone, Debug)]
-pub struct SplitN<'a, M: ForwardSearcher> {
-    base: SplitBase<'a, M>,
-    remaining: usize,
+pub struct SplitTerminator<'a, M: IntoSearcher<'a>> {
+    base: SplitBase<'a, M::Searcher>,
 }
 
-impl<'a, M: ForwardSearcher> SplitN<'a, M> {
+impl<'a, M: IntoSearcher<'a>> SplitTerminator<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: SplitBase::new(string, matcher.into_searcher(string)).terminator() }
+    }
+}
+
+impl<'a, M: IntoSearcher<'a>> Iterator for SplitTerminator<'a, M> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_forwards()
+    }
+}
+
+impl<'a, M: IntoSearcher<'a>> DoubleEndedIterator for SplitTerminator<'a, M> where M::Searcher: RSearcher<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards()
+    }
+}
+
+// SplitTerminatorMut
+#[derive(Debug)]
+pub struct SplitTerminatorMut<'a, M: Clone + for<'s> IntoSearcher<'s>> {
+    base: SplitBaseMut<'a, M>,
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> SplitTerminatorMut<'a, M> {
+    #[inline]
+    pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
+        Self { base: SplitBaseMut::forward(string, matcher).terminator() }
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> Iterator for SplitTerminatorMut<'a, M> {
+    type Item = &'a mut ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_forwards()
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> DoubleEndedIterator for SplitTerminatorMut<'a, M>
+    where for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_back_forwards()
+    }
+}
+
+// RSplitTerminator
+#[derive(Clone, Debug)]
+pub struct RSplitTerminator<'a, M: IntoRSearcher<'a>> {
+    base: SplitBase<'a, M::RSearcher>,
+}
+
+impl<'a, M: IntoRSearcher<'a>> RSplitTerminator<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: SplitBase::new(string, matcher.into_rsearcher(string)).terminator() }
+    }
+}
+
+impl<'a, M: IntoRSearcher<'a>> Iterator for RSplitTerminator<'a, M> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards()
+    }
+}
+
+impl<'a, M: IntoRSearcher<'a>> DoubleEndedIterator for RSplitTerminator<'a, M> where M::RSearcher: Searcher<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_forwards()
+    }
+}
+
+// RSplitTerminatorMut
+#[derive(Debug)]
+pub struct RSplitTerminatorMut<'a, M: Clone + for<'s> IntoRSearcher<'s>> {
+    base: SplitBaseMut<'a, M>,
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> RSplitTerminatorMut<'a, M> {
+    #[inline]
+    pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
+        Self { base: SplitBaseMut::backward(string, matcher).terminator() }
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> Iterator for RSplitTerminatorMut<'a, M> {
+    type Item = &'a mut ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_backwards()
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> DoubleEndedIterator for RSplitTerminatorMut<'a, M>
+    where for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_back_backwards()
+    }
+}
+
+// SplitInclusive
+#[derive(Clone, Debug)]
+pub struct SplitInclusive<'a, M: IntoSearcher<'a>> {
+    base: SplitBase<'a, M::Searcher>,
+}
+
+impl<'a, M: IntoSearcher<'a>> SplitInclusive<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: SplitBase::new(string, matcher.into_searcher(string)).inclusive() }
+    }
+}
+
+impl<'a, M: IntoSearcher<'a>> Iterator for SplitInclusive<'a, M> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_forwards()
+    }
+}
+
+impl<'a, M: IntoSearcher<'a>> DoubleEndedIterator for SplitInclusive<'a, M> where M::Searcher: RSearcher<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards()
+    }
+}
+
+// SplitInclusiveMut
+#[derive(Debug)]
+pub struct SplitInclusiveMut<'a, M: Clone + for<'s> IntoSearcher<'s>> {
+    base: SplitBaseMut<'a, M>,
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> SplitInclusiveMut<'a, M> {
+    #[inline]
+    pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
+        Self { base: SplitBaseMut::forward(string, matcher).inclusive() }
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> Iterator for SplitInclusiveMut<'a, M> {
+    type Item = &'a mut ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_forwards()
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> DoubleEndedIterator for SplitInclusiveMut<'a, M>
+    where for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_back_forwards()
+    }
+}
+
+// RSplitInclusive
+#[derive(Clone, Debug)]
+pub struct RSplitInclusive<'a, M: IntoRSearcher<'a>> {
+    base: SplitBase<'a, M::RSearcher>,
+}
+
+impl<'a, M: IntoRSearcher<'a>> RSplitInclusive<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: SplitBase::new(string, matcher.into_rsearcher(string)).inclusive() }
+    }
+}
+
+impl<'a, M: IntoRSearcher<'a>> Iterator for RSplitInclusive<'a, M> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards()
+    }
+}
+
+impl<'a, M: IntoRSearcher<'a>> DoubleEndedIterator for RSplitInclusive<'a, M> where M::RSearcher: Searcher<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_forwards()
+    }
+}
+
+// RSplitInclusiveMut
+#[derive(Debug)]
+pub struct RSplitInclusiveMut<'a, M: Clone + for<'s> IntoRSearcher<'s>> {
+    base: SplitBaseMut<'a, M>,
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> RSplitInclusiveMut<'a, M> {
+    #[inline]
+    pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
+        Self { base: SplitBaseMut::backward(string, matcher).inclusive() }
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> Iterator for RSplitInclusiveMut<'a, M> {
+    type Item = &'a mut ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_backwards()
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> DoubleEndedIterator for RSplitInclusiveMut<'a, M>
+    where for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut ByteStr> {
+        self.base.next_back_backwards()
+    }
+}
+
+// SplitN
+#[derive(Clone, Debug)]
+pub struct SplitN<'a, M: IntoSearcher<'a>> {
+    base: SplitBase<'a, M::Searcher>,
+    remaining: usize,
+}
+
+impl<'a, M: IntoSearcher<'a>> SplitN<'a, M> {
     #[inline]
     pub fn new(string: &'a ByteStr, n: usize, matcher: M) -> Self {
         Self {
-            base: SplitBase::new(string, matcher),
+            base: SplitBase::new(string, matcher.into_searcher(string)),
             remaining: n,
         }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for SplitN<'a, M> {
+impl<'a, M: IntoSearcher<'a>> Iterator for SplitN<'a, M> {
     type Item = &'a ByteStr;
     
     #[inline]
@@ -482,7 +1193,41 @@ impl<'a, M: ForwardSearcher> Iterator for SplitN<'a, M> {
             None
         } else if self.remaining == 1 {
             self.remaining = 0;
-            Some(self.base.remaining())
+            self.base.remaining()
+        } else {
+            self.remaining -= 1;
+            self.base.next_forwards()
+        }
+    }
+}
+
+// SplitNMut
+#[derive(Debug)]
+pub struct SplitNMut<'a, M: Clone + for<'s> IntoSearcher<'s>> {
+    base: SplitBaseMut<'a, M>,
+    remaining: usize,
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> SplitNMut<'a, M> {
+    #[inline]
+    pub fn new(string: &'a mut ByteStr, n: usize, matcher: M) -> Self {
+        Self {
+            base: SplitBaseMut::forward(string, matcher),
+            remaining: n,
+        }
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> Iterator for SplitNMut<'a, M> {
+    type Item = &'a mut ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut ByteStr> {
+        if self.remaining == 0 {
+            None
+        } else if self.remaining == 1 {
+            self.remaining = 0;
+            self.base.remaining()
         } else {
             self.remaining -= 1;
             self.base.next_forwards()
@@ -490,230 +1235,610 @@ impl<'a, M: ForwardSearcher> Iterator for SplitN<'a, M> {
     }
 }
 
-// SplitNMut
-#[derive(Debug)]
-pub struct SplitNMut<'a, M: ForwardSearcher> {
-    base: SplitBaseMut<'a, M>,
-    remaining: usize,
+// RSplitN
+#[derive(Clone, Debug)]
+pub struct RSplitN<'a, M: IntoRSearcher<'a>> {
+    base: SplitBase<'a, M::RSearcher>,
+    remaining: usize,
+}
+
+impl<'a, M: IntoRSearcher<'a>> RSplitN<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, n: usize, matcher: M) -> Self {
+        Self {
+            base: SplitBase::new(string, matcher.into_rsearcher(string)),
+            remaining: n,
+        }
+    }
+}
+
+impl<'a, M: IntoRSearcher<'a>> Iterator for RSplitN<'a, M> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        if self.remaining == 0 {
+            None
+        } else if self.remaining == 1 {
+            self.remaining = 0;
+            self.base.remaining()
+        } else {
+            self.remaining -= 1;
+            self.base.next_backwards()
+        }
+    }
+}
+
+// RSplitNMut
+#[derive(Debug)]
+pub struct RSplitNMut<'a, M: Clone + for<'s> IntoRSearcher<'s>> {
+    base: SplitBaseMut<'a, M>,
+    remaining: usize,
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> RSplitNMut<'a, M> {
+    #[inline]
+    pub fn new(string: &'a mut ByteStr, n: usize, matcher: M) -> Self {
+        Self {
+            base: SplitBaseMut::backward(string, matcher),
+            remaining: n,
+        }
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> Iterator for RSplitNMut<'a, M> {
+    type Item = &'a mut ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut ByteStr> {
+        if self.remaining == 0 {
+            None
+        } else if self.remaining == 1 {
+            self.remaining = 0;
+            self.base.remaining()
+        } else {
+            self.remaining -= 1;
+            self.base.next_backwards()
+        }
+    }
+}
+
+// LineBreaks
+/// The line breaks recognized by the line iterators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineBreaks {
+    /// `\n` and `\r\n`.
+    Newline,
+    /// `\n`, `\r\n` and a bare `\r`, as well as vertical tab (`\x0b`), form
+    /// feed (`\x0c`) and the UTF-8 encoding of NEL (`\xc2\x85`).
+    Any,
+}
+
+// Returns the bounds of the first line break in `string`.
+fn find_line_break(string: &[u8], breaks: LineBreaks) -> Option<(usize, usize)> {
+    match breaks {
+        LineBreaks::Newline => memchr::memchr(b'\n', string).map(|i| {
+            if i > 0 && string[i - 1] == b'\r' { (i - 1, i + 1) } else { (i, i + 1) }
+        }),
+        LineBreaks::Any => {
+            for (i, &byte) in string.iter().enumerate() {
+                match byte {
+                    b'\n' | b'\x0b' | b'\x0c' => return Some((i, i + 1)),
+                    b'\r' if string.get(i + 1) == Some(&b'\n') => return Some((i, i + 2)),
+                    b'\r' => return Some((i, i + 1)),
+                    b'\xc2' if string.get(i + 1) == Some(&b'\x85') => return Some((i, i + 2)),
+                    _ => {}
+                }
+            }
+            None
+        }
+    }
+}
+
+// Returns the bounds of the last line break in `string`.
+fn rfind_line_break(string: &[u8], breaks: LineBreaks) -> Option<(usize, usize)> {
+    let preceded_by = |i: usize, byte: u8| i > 0 && string[i - 1] == byte;
+    match breaks {
+        LineBreaks::Newline => memchr::memrchr(b'\n', string).map(|i| {
+            if preceded_by(i, b'\r') { (i - 1, i + 1) } else { (i, i + 1) }
+        }),
+        LineBreaks::Any => {
+            // A `\r` found here is never followed by `\n`, which would
+            // have been found first.
+            for (i, &byte) in string.iter().enumerate().rev() {
+                match byte {
+                    b'\n' if preceded_by(i, b'\r') => return Some((i - 1, i + 1)),
+                    b'\n' | b'\r' | b'\x0b' | b'\x0c' => return Some((i, i + 1)),
+                    b'\x85' if preceded_by(i, b'\xc2') => return Some((i - 1, i + 1)),
+                    _ => {}
+                }
+            }
+            None
+        }
+    }
+}
+
+// Returns the bounds of the last line of `string`, which is not empty,
+// without its terminator.
+fn last_line(string: &[u8], breaks: LineBreaks) -> (usize, usize) {
+    match rfind_line_break(string, breaks) {
+        Some((a, b)) if b == string.len() => {
+            (rfind_line_break(&string[.. a], breaks).map_or(0, |(_, b)| b), a)
+        }
+        Some((_, b)) => (b, string.len()),
+        None => (0, string.len()),
+    }
+}
+
+// LinesBase
+#[derive(Clone, Debug)]
+struct LinesBase<'a> {
+    string: &'a ByteStr,
+    breaks: LineBreaks,
+    keep_terminator: bool,
+    // Bounds of the part of the string that has not been returned.
+    start: usize,
+    end: usize,
+}
+
+impl<'a> LinesBase<'a> {
+    #[inline]
+    fn new(string: &'a ByteStr, breaks: LineBreaks, keep_terminator: bool) -> Self {
+        Self {
+            string,
+            breaks,
+            keep_terminator,
+            start: 0,
+            end: string.len(),
+        }
+    }
+    
+    // Returns the next line with its offset.
+    fn next_forwards(&mut self) -> Option<(usize, &'a ByteStr)> {
+        if self.start == self.end {
+            return None;
+        }
+        let rest = &self.string[self.start .. self.end];
+        let (a, b) = find_line_break(rest.as_slice(), self.breaks).unwrap_or((rest.len(), rest.len()));
+        let offset = self.start;
+        self.start += b;
+        Some((offset, &rest[.. if self.keep_terminator { b } else { a }]))
+    }
+    
+    fn next_backwards(&mut self) -> Option<(usize, &'a ByteStr)> {
+        if self.start == self.end {
+            return None;
+        }
+        let rest = &self.string[self.start .. self.end];
+        let (a, b) = last_line(rest.as_slice(), self.breaks);
+        let offset = self.start + a;
+        self.end = offset;
+        Some((offset, &rest[a .. if self.keep_terminator { rest.len() } else { b }]))
+    }
+}
+
+// Lines
+#[derive(Clone, Debug)]
+pub struct Lines<'a> {
+    base: LinesBase<'a>,
+}
+
+impl<'a> Lines<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, breaks: LineBreaks, keep_terminator: bool) -> Self {
+        Self { base: LinesBase::new(string, breaks, keep_terminator) }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_forwards().map(|(_, line)| line)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Lines<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards().map(|(_, line)| line)
+    }
+}
+
+// LinesMut
+#[derive(Debug)]
+pub struct LinesMut<'a> {
+    string: &'a mut ByteStr,
+    breaks: LineBreaks,
+    keep_terminator: bool,
+}
+
+impl<'a> LinesMut<'a> {
+    #[inline]
+    pub fn new(string: &'a mut ByteStr, breaks: LineBreaks, keep_terminator: bool) -> Self {
+        Self { string, breaks, keep_terminator }
+    }
+}
+
+impl<'a> Iterator for LinesMut<'a> {
+    type Item = &'a mut ByteStr;
+    
+    fn next(&mut self) -> Option<&'a mut ByteStr> {
+        if self.string.is_empty() {
+            return None;
+        }
+        let len = self.string.len();
+        let (a, b) = find_line_break(self.string.as_slice(), self.breaks).unwrap_or((len, len));
+        let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
+        let (line, rest) = string.split_at_mut(b);
+        self.string = rest;
+        Some(if self.keep_terminator { line } else { &mut line[.. a] })
+    }
+}
+
+impl<'a> DoubleEndedIterator for LinesMut<'a> {
+    fn next_back(&mut self) -> Option<&'a mut ByteStr> {
+        if self.string.is_empty() {
+            return None;
+        }
+        let (a, b) = last_line(self.string.as_slice(), self.breaks);
+        let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
+        let (rest, line) = string.split_at_mut(a);
+        self.string = rest;
+        Some(if self.keep_terminator { line } else { &mut line[.. b - a] })
+    }
+}
+
+// LineIndices
+#[derive(Clone, Debug)]
+pub struct LineIndices<'a> {
+    base: LinesBase<'a>,
+}
+
+impl<'a> LineIndices<'a> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, breaks: LineBreaks) -> Self {
+        Self { base: LinesBase::new(string, breaks, false) }
+    }
+}
+
+impl<'a> Iterator for LineIndices<'a> {
+    type Item = (usize, &'a ByteStr);
+    
+    #[inline]
+    fn next(&mut self) -> Option<(usize, &'a ByteStr)> {
+        self.base.next_forwards()
+    }
+}
+
+impl<'a> DoubleEndedIterator for LineIndices<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(usize, &'a ByteStr)> {
+        self.base.next_backwards()
+    }
+}
+
+// WhitespaceBase
+#[derive(Clone, Debug)]
+struct WhitespaceBase<'a> {
+    string: &'a ByteStr,
+    // The bytes that can start whitespace, which include the leading bytes
+    // of multi-byte UTF-8 whitespace when it is recognized.
+    starts: ByteSetMatcher,
+    // Bounds of the part of the string that has not been returned.
+    start: usize,
+    end: usize,
+}
+
+impl<'a> WhitespaceBase<'a> {
+    #[inline]
+    fn new(string: &'a ByteStr, unicode: bool) -> Self {
+        let starts = if unicode {
+            ByteSetMatcher::new(b"\t\n\x0b\x0c\r \xc2\xe1\xe2\xe3")
+        } else {
+            ByteSetMatcher::new(b"\t\n\x0c\r ")
+        };
+        Self {
+            string,
+            starts,
+            start: 0,
+            end: string.len(),
+        }
+    }
+    
+    // Returns the length of the whitespace at the start of `string`, or 0
+    // if it does not start with whitespace. The first byte is in `starts`.
+    #[inline]
+    fn whitespace_len(&self, string: &ByteStr) -> usize {
+        if string[0] < 0x80 {
+            1
+        } else {
+            match utf8::decode(string.as_slice()) {
+                Some((c, len)) if c.is_whitespace() => len,
+                _ => 0,
+            }
+        }
+    }
+    
+    // Returns the bounds of the first whitespace in `string`.
+    fn find_whitespace(&self, string: &ByteStr) -> Option<(usize, usize)> {
+        let mut from = 0;
+        while let Some((i, _)) = self.starts.find(&string[from ..]) {
+            let start = from + i;
+            let len = self.whitespace_len(&string[start ..]);
+            if len != 0 {
+                return Some((start, start + len));
+            }
+            from = start + 1;
+        }
+        None
+    }
+    
+    // Returns the bounds of the last whitespace in `string`. Whitespace
+    // never starts with a continuation byte, so none is skipped by looking
+    // at the starts from the back.
+    fn rfind_whitespace(&self, string: &ByteStr) -> Option<(usize, usize)> {
+        let mut to = string.len();
+        while let Some((start, _)) = self.starts.rfind(&string[.. to]) {
+            let len = self.whitespace_len(&string[start ..]);
+            if len != 0 {
+                return Some((start, start + len));
+            }
+            to = start;
+        }
+        None
+    }
+    
+    fn next_forwards(&mut self) -> Option<&'a ByteStr> {
+        while self.start != self.end {
+            let rest = &self.string[self.start .. self.end];
+            match self.find_whitespace(rest) {
+                Some((0, b)) => self.start += b,
+                Some((a, b)) => {
+                    self.start += b;
+                    return Some(&rest[.. a]);
+                }
+                None => {
+                    self.start = self.end;
+                    return Some(rest);
+                }
+            }
+        }
+        None
+    }
+    
+    fn next_backwards(&mut self) -> Option<&'a ByteStr> {
+        while self.start != self.end {
+            let rest = &self.string[self.start .. self.end];
+            match self.rfind_whitespace(rest) {
+                Some((a, b)) if b == rest.len() => self.end = self.start + a,
+                Some((a, b)) => {
+                    self.end = self.start + a;
+                    return Some(&rest[b ..]);
+                }
+                None => {
+                    self.end = self.start;
+                    return Some(rest);
+                }
+            }
+        }
+        None
+    }
+}
+
+// SplitAsciiWhitespace
+#[derive(Clone, Debug)]
+pub struct SplitAsciiWhitespace<'a> {
+    base: WhitespaceBase<'a>,
 }
 
-impl<'a, M: ForwardSearcher> SplitNMut<'a, M> {
+impl<'a> SplitAsciiWhitespace<'a> {
     #[inline]
-    pub fn new(string: &'a mut ByteStr, n: usize, matcher: M) -> Self {
-        Self {
-            base: SplitBaseMut::new(string, matcher),
-            remaining: n,
-        }
+    pub fn new(string: &'a ByteStr) -> Self {
+        Self { base: WhitespaceBase::new(string, false) }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for SplitNMut<'a, M> {
-    type Item = &'a mut ByteStr;
+impl<'a> Iterator for SplitAsciiWhitespace<'a> {
+    type Item = &'a ByteStr;
     
     #[inline]
-    fn next(&mut self) -> Option<&'a mut ByteStr> {
-        if self.remaining == 0 {
-            None
-        } else if self.remaining == 1 {
-            self.remaining = 0;
-            Some(self.base.remaining())
-        } else {
-            self.remaining -= 1;
-            self.base.next_forwards()
-        }
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_forwards()
     }
 }
 
-// RSplitN
+impl<'a> DoubleEndedIterator for SplitAsciiWhitespace<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards()
+    }
+}
+
+// SplitWhitespace
 #[derive(Clone, Debug)]
-pub struct RSplitN<'a, M: ReverseSearcher> {
-    base: SplitBase<'a, M>,
-    remaining: usize,
+pub struct SplitWhitespace<'a> {
+    base: WhitespaceBase<'a>,
 }
 
-impl<'a, M: ReverseSearcher> RSplitN<'a, M> {
+impl<'a> SplitWhitespace<'a> {
     #[inline]
-    pub fn new(string: &'a ByteStr, n: usize, matcher: M) -> Self {
-        Self {
-            base: SplitBase::new(string, matcher),
-            remaining: n,
-        }
+    pub fn new(string: &'a ByteStr) -> Self {
+        Self { base: WhitespaceBase::new(string, true) }
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RSplitN<'a, M> {
+impl<'a> Iterator for SplitWhitespace<'a> {
     type Item = &'a ByteStr;
     
     #[inline]
     fn next(&mut self) -> Option<&'a ByteStr> {
-        if self.remaining == 0 {
-            None
-        } else if self.remaining == 1 {
-            self.remaining = 0;
-            Some(self.base.remaining())
-        } else {
-            self.remaining -= 1;
-            self.base.next_backwards()
-        }
-    }
-}
-
-// RSplitNMut
-#[derive(Debug)]
-pub struct RSplitNMut<'a, M: ReverseSearcher> {
-    base: SplitBaseMut<'a, M>,
-    remaining: usize,
-}
-
-impl<'a, M: ReverseSearcher> RSplitNMut<'a, M> {
-    #[inline]
-    pub fn new(string: &'a mut ByteStr, n: usize, matcher: M) -> Self {
-        Self {
-            base: SplitBaseMut::new(string, matcher),
-            remaining: n,
-        }
+        self.base.next_forwards()
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RSplitNMut<'a, M> {
-    type Item = &'a mut ByteStr;
-    
+impl<'a> DoubleEndedIterator for SplitWhitespace<'a> {
     #[inline]
-    fn next(&mut self) -> Option<&'a mut ByteStr> {
-        if self.remaining == 0 {
-            None
-        } else if self.remaining == 1 {
-            self.remaining = 0;
-            Some(self.base.remaining())
-        } else {
-            self.remaining -= 1;
-            self.base.next_backwards()
-        }
+    fn next_back(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards()
     }
 }
 
 // MatchesBase
 #[derive(Clone, Debug)]
-struct MatchesBase<'a, M: Matcher> {
+struct MatchesBase<'a, S> {
     string: &'a ByteStr,
-    matcher: M,
-    pos: usize,
+    searcher: S,
 }
 
-impl<'a, M: Matcher> MatchesBase<'a, M> {
+impl<'a, S> MatchesBase<'a, S> {
     #[inline]
-    fn new(string: &'a ByteStr, matcher: M) -> Self {
-        Self {
-            string: string,
-            matcher: matcher,
-            pos: 0,
-        }
+    fn new(string: &'a ByteStr, searcher: S) -> Self {
+        Self { string, searcher }
     }
 }
 
-impl<'a, M: ForwardSearcher> MatchesBase<'a, M> {
+impl<'a, S: Searcher<'a>> MatchesBase<'a, S> {
+    #[inline]
     fn next_forwards(&mut self) -> Option<(usize, &'a ByteStr)> {
-        match self.matcher.find(self.string) {
-            Some((a, b)) => {
-                let (ret, rest) = self.string.split_at(b);
-                let ret_pos = self.pos + a;
-                self.string = rest;
-                self.pos += b;
-                Some((ret_pos, &ret[a ..]))
-            }
-            None => {
-                self.string = ByteStr::empty();
-                None
-            }
-        }
+        self.searcher.next_match().map(|(a, b)| (a, &self.string[a .. b]))
     }
 }
 
-impl<'a, M: ReverseSearcher> MatchesBase<'a, M> {
+impl<'a, S: RSearcher<'a>> MatchesBase<'a, S> {
+    #[inline]
     fn next_backwards(&mut self) -> Option<(usize, &'a ByteStr)> {
-        match self.matcher.rfind(self.string) {
-            Some((a, b)) => {
-                let (rest, ret) = self.string[.. b].split_at(a);
-                self.string = rest;
-                Some((a, ret))
-            }
-            None => {
-                self.string = ByteStr::empty();
-                None
-            }
-        }
+        self.searcher.next_match_back().map(|(a, b)| (a, &self.string[a .. b]))
     }
 }
 
 // MatchesBaseMut
 #[derive(Debug)]
-struct MatchesBaseMut<'a, M: Matcher> {
+struct MatchesBaseMut<'a, M> {
     string: &'a mut ByteStr,
-    matcher: M,
-    pos: usize,
+    matches: MutMatches<M>,
+    // Position of `string` in the whole string.
+    start: usize,
+    finished: bool,
 }
 
-impl<'a, M: Matcher> MatchesBaseMut<'a, M> {
+impl<'a, M> MatchesBaseMut<'a, M> {
     #[inline]
-    fn new(string: &'a mut ByteStr, matcher: M) -> Self {
+    fn new(string: &'a mut ByteStr, matches: MutMatches<M>) -> Self {
         Self {
-            string: string,
-            matcher: matcher,
-            pos: 0,
+            string,
+            matches,
+            start: 0,
+            finished: false,
         }
     }
-}
-
-impl<'a, M: ForwardSearcher> MatchesBaseMut<'a, M> {
-    fn next_forwards(&mut self) -> Option<(usize, &'a mut ByteStr)> {
-        match self.matcher.find(self.string) {
+    
+    fn next_front<F>(&mut self, find: F) -> Option<(usize, &'a mut ByteStr)>
+        where F: FnOnce(&mut MutMatches<M>, &ByteStr, usize) -> Option<(usize, usize)>
+    {
+        if self.finished {
+            return None;
+        }
+        match find(&mut self.matches, &*self.string, self.start) {
             Some((a, b)) => {
+                let (a, b) = (a - self.start, b - self.start);
                 let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
                 let (ret, rest) = string.split_at_mut(b);
-                let ret_pos = self.pos + a;
+                let ret_pos = self.start + a;
                 self.string = rest;
-                self.pos += b;
+                self.start += b;
                 Some((ret_pos, &mut ret[a ..]))
             }
             None => {
-                self.string = ByteStr::empty_mut();
+                self.finished = true;
                 None
             }
         }
     }
-}
-
-impl<'a, M: ReverseSearcher> MatchesBaseMut<'a, M> {
-    fn next_backwards(&mut self) -> Option<(usize, &'a mut ByteStr)> {
-        match self.matcher.rfind(self.string) {
+    
+    fn next_back<F>(&mut self, find: F) -> Option<(usize, &'a mut ByteStr)>
+        where F: FnOnce(&mut MutMatches<M>, &ByteStr, usize) -> Option<(usize, usize)>
+    {
+        if self.finished {
+            return None;
+        }
+        match find(&mut self.matches, &*self.string, self.start) {
             Some((a, b)) => {
+                let (a, b) = (a - self.start, b - self.start);
                 let string = core::mem::replace(&mut self.string, ByteStr::empty_mut());
                 let (rest, ret) = string[.. b].split_at_mut(a);
                 self.string = rest;
-                Some((a, ret))
+                Some((self.start + a, ret))
             }
             None => {
-                self.string = ByteStr::empty_mut();
+                self.finished = true;
                 None
             }
         }
     }
 }
 
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> MatchesBaseMut<'a, M> {
+    #[inline]
+    fn forward(string: &'a mut ByteStr, matcher: M) -> Self {
+        let matches = MutMatches::forward(string, matcher);
+        Self::new(string, matches)
+    }
+    
+    #[inline]
+    fn next_forwards(&mut self) -> Option<(usize, &'a mut ByteStr)> {
+        self.next_front(MutMatches::next_forwards)
+    }
+}
+
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> MatchesBaseMut<'a, M> {
+    #[inline]
+    fn backward(string: &'a mut ByteStr, matcher: M) -> Self {
+        let matches = MutMatches::backward(string, matcher);
+        Self::new(string, matches)
+    }
+    
+    #[inline]
+    fn next_backwards(&mut self) -> Option<(usize, &'a mut ByteStr)> {
+        self.next_back(MutMatches::next_backwards)
+    }
+}
+
+impl<'a, M> MatchesBaseMut<'a, M>
+    where M: Clone + for<'s> IntoSearcher<'s>, for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
+    // Returns the last item that `next_forwards` would return.
+    #[inline]
+    fn next_back_forwards(&mut self) -> Option<(usize, &'a mut ByteStr)> {
+        self.next_back(MutMatches::next_back_forwards)
+    }
+}
+
+impl<'a, M> MatchesBaseMut<'a, M>
+    where M: Clone + for<'s> IntoRSearcher<'s>, for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
+    // Returns the last item that `next_backwards` would return.
+    #[inline]
+    fn next_back_backwards(&mut self) -> Option<(usize, &'a mut ByteStr)> {
+        self.next_front(MutMatches::next_back_backwards)
+    }
+}
+
 // Matches
 #[derive(Clone, Debug)]
-pub struct Matches<'a, M: ForwardSearcher> {
-    base: MatchesBase<'a, M>,
+pub struct Matches<'a, M: IntoSearcher<'a>> {
+    base: MatchesBase<'a, M::Searcher>,
 }
 
-impl<'a, M: ForwardSearcher> Matches<'a, M> {
+impl<'a, M: IntoSearcher<'a>> Matches<'a, M> {
     #[inline]
     pub fn new(string: &'a ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBase::new(string, matcher) }
+        Self { base: MatchesBase::new(string, matcher.into_searcher(string)) }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for Matches<'a, M> {
+impl<'a, M: IntoSearcher<'a>> Iterator for Matches<'a, M> {
     type Item = &'a ByteStr;
     
     #[inline]
@@ -722,7 +1847,7 @@ impl<'a, M: ForwardSearcher> Iterator for Matches<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for Matches<'a, M> {
+impl<'a, M: IntoSearcher<'a>> DoubleEndedIterator for Matches<'a, M> where M::Searcher: RSearcher<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<&'a ByteStr> {
         self.base.next_backwards().map(|(_, s)| s)
@@ -731,18 +1856,18 @@ impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for Matches<'a, M> {
 
 // MatchesMut
 #[derive(Debug)]
-pub struct MatchesMut<'a, M: ForwardSearcher> {
+pub struct MatchesMut<'a, M: Clone + for<'s> IntoSearcher<'s>> {
     base: MatchesBaseMut<'a, M>,
 }
 
-impl<'a, M: ForwardSearcher> MatchesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> MatchesMut<'a, M> {
     #[inline]
     pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBaseMut::new(string, matcher) }
+        Self { base: MatchesBaseMut::forward(string, matcher) }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for MatchesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> Iterator for MatchesMut<'a, M> {
     type Item = &'a mut ByteStr;
     
     #[inline]
@@ -751,27 +1876,29 @@ impl<'a, M: ForwardSearcher> Iterator for MatchesMut<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for MatchesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> DoubleEndedIterator for MatchesMut<'a, M>
+    where for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
     #[inline]
     fn next_back(&mut self) -> Option<&'a mut ByteStr> {
-        self.base.next_backwards().map(|(_, s)| s)
+        self.base.next_back_forwards().map(|(_, s)| s)
     }
 }
 
 // RMatches
 #[derive(Clone, Debug)]
-pub struct RMatches<'a, M: ReverseSearcher> {
-    base: MatchesBase<'a, M>,
+pub struct RMatches<'a, M: IntoRSearcher<'a>> {
+    base: MatchesBase<'a, M::RSearcher>,
 }
 
-impl<'a, M: ReverseSearcher> RMatches<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> RMatches<'a, M> {
     #[inline]
     pub fn new(string: &'a ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBase::new(string, matcher) }
+        Self { base: MatchesBase::new(string, matcher.into_rsearcher(string)) }
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RMatches<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> Iterator for RMatches<'a, M> {
     type Item = &'a ByteStr;
     
     #[inline]
@@ -780,7 +1907,7 @@ impl<'a, M: ReverseSearcher> Iterator for RMatches<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RMatches<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> DoubleEndedIterator for RMatches<'a, M> where M::RSearcher: Searcher<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<&'a ByteStr> {
         self.base.next_forwards().map(|(_, s)| s)
@@ -789,18 +1916,18 @@ impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RMatches<'a, M> {
 
 // RMatchesMut
 #[derive(Debug)]
-pub struct RMatchesMut<'a, M: ReverseSearcher> {
+pub struct RMatchesMut<'a, M: Clone + for<'s> IntoRSearcher<'s>> {
     base: MatchesBaseMut<'a, M>,
 }
 
-impl<'a, M: ReverseSearcher> RMatchesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> RMatchesMut<'a, M> {
     #[inline]
     pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBaseMut::new(string, matcher) }
+        Self { base: MatchesBaseMut::backward(string, matcher) }
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RMatchesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> Iterator for RMatchesMut<'a, M> {
     type Item = &'a mut ByteStr;
     
     #[inline]
@@ -809,27 +1936,29 @@ impl<'a, M: ReverseSearcher> Iterator for RMatchesMut<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RMatchesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> DoubleEndedIterator for RMatchesMut<'a, M>
+    where for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
     #[inline]
     fn next_back(&mut self) -> Option<&'a mut ByteStr> {
-        self.base.next_forwards().map(|(_, s)| s)
+        self.base.next_back_backwards().map(|(_, s)| s)
     }
 }
 
 // MatchIndices
 #[derive(Clone, Debug)]
-pub struct MatchIndices<'a, M: ForwardSearcher> {
-    base: MatchesBase<'a, M>,
+pub struct MatchIndices<'a, M: IntoSearcher<'a>> {
+    base: MatchesBase<'a, M::Searcher>,
 }
 
-impl<'a, M: ForwardSearcher> MatchIndices<'a, M> {
+impl<'a, M: IntoSearcher<'a>> MatchIndices<'a, M> {
     #[inline]
     pub fn new(string: &'a ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBase::new(string, matcher) }
+        Self { base: MatchesBase::new(string, matcher.into_searcher(string)) }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for MatchIndices<'a, M> {
+impl<'a, M: IntoSearcher<'a>> Iterator for MatchIndices<'a, M> {
     type Item = (usize, &'a ByteStr);
     
     #[inline]
@@ -838,7 +1967,7 @@ impl<'a, M: ForwardSearcher> Iterator for MatchIndices<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for MatchIndices<'a, M> {
+impl<'a, M: IntoSearcher<'a>> DoubleEndedIterator for MatchIndices<'a, M> where M::Searcher: RSearcher<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<(usize, &'a ByteStr)> {
         self.base.next_backwards()
@@ -847,18 +1976,18 @@ impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for MatchIndices<'a, M> {
 
 // MatchIndicesMut
 #[derive(Debug)]
-pub struct MatchIndicesMut<'a, M: ForwardSearcher> {
+pub struct MatchIndicesMut<'a, M: Clone + for<'s> IntoSearcher<'s>> {
     base: MatchesBaseMut<'a, M>,
 }
 
-impl<'a, M: ForwardSearcher> MatchIndicesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> MatchIndicesMut<'a, M> {
     #[inline]
     pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBaseMut::new(string, matcher) }
+        Self { base: MatchesBaseMut::forward(string, matcher) }
     }
 }
 
-impl<'a, M: ForwardSearcher> Iterator for MatchIndicesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> Iterator for MatchIndicesMut<'a, M> {
     type Item = (usize, &'a mut ByteStr);
     
     #[inline]
@@ -867,27 +1996,29 @@ impl<'a, M: ForwardSearcher> Iterator for MatchIndicesMut<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for MatchIndicesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoSearcher<'s>> DoubleEndedIterator for MatchIndicesMut<'a, M>
+    where for<'s> <M as IntoSearcher<'s>>::Searcher: RSearcher<'s>
+{
     #[inline]
     fn next_back(&mut self) -> Option<(usize, &'a mut ByteStr)> {
-        self.base.next_backwards()
+        self.base.next_back_forwards()
     }
 }
 
 // RMatchIndices
 #[derive(Clone, Debug)]
-pub struct RMatchIndices<'a, M: ReverseSearcher> {
-    base: MatchesBase<'a, M>,
+pub struct RMatchIndices<'a, M: IntoRSearcher<'a>> {
+    base: MatchesBase<'a, M::RSearcher>,
 }
 
-impl<'a, M: ReverseSearcher> RMatchIndices<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> RMatchIndices<'a, M> {
     #[inline]
     pub fn new(string: &'a ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBase::new(string, matcher) }
+        Self { base: MatchesBase::new(string, matcher.into_rsearcher(string)) }
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RMatchIndices<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> Iterator for RMatchIndices<'a, M> {
     type Item = (usize, &'a ByteStr);
     
     #[inline]
@@ -896,7 +2027,7 @@ impl<'a, M: ReverseSearcher> Iterator for RMatchIndices<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RMatchIndices<'a, M> {
+impl<'a, M: IntoRSearcher<'a>> DoubleEndedIterator for RMatchIndices<'a, M> where M::RSearcher: Searcher<'a> {
     #[inline]
     fn next_back(&mut self) -> Option<(usize, &'a ByteStr)> {
         self.base.next_forwards()
@@ -905,18 +2036,18 @@ impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RMatchIndices<'a, M> {
 
 // RMatchIndicesMut
 #[derive(Debug)]
-pub struct RMatchIndicesMut<'a, M: ReverseSearcher> {
+pub struct RMatchIndicesMut<'a, M: Clone + for<'s> IntoRSearcher<'s>> {
     base: MatchesBaseMut<'a, M>,
 }
 
-impl<'a, M: ReverseSearcher> RMatchIndicesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> RMatchIndicesMut<'a, M> {
     #[inline]
     pub fn new(string: &'a mut ByteStr, matcher: M) -> Self {
-        Self { base: MatchesBaseMut::new(string, matcher) }
+        Self { base: MatchesBaseMut::backward(string, matcher) }
     }
 }
 
-impl<'a, M: ReverseSearcher> Iterator for RMatchIndicesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> Iterator for RMatchIndicesMut<'a, M> {
     type Item = (usize, &'a mut ByteStr);
     
     #[inline]
@@ -925,9 +2056,196 @@ impl<'a, M: ReverseSearcher> Iterator for RMatchIndicesMut<'a, M> {
     }
 }
 
-impl<'a, M: DoubleEndedSearcher> DoubleEndedIterator for RMatchIndicesMut<'a, M> {
+impl<'a, M: Clone + for<'s> IntoRSearcher<'s>> DoubleEndedIterator for RMatchIndicesMut<'a, M>
+    where for<'s> <M as IntoRSearcher<'s>>::RSearcher: Searcher<'s>
+{
     #[inline]
     fn next_back(&mut self) -> Option<(usize, &'a mut ByteStr)> {
+        self.base.next_back_backwards()
+    }
+}
+
+// OverlappingBase
+#[derive(Clone, Debug)]
+struct OverlappingBase<'a, M> {
+    string: &'a ByteStr,
+    matcher: M,
+    // Matches start at or after `start` and end at or before `end`. Each
+    // search uses a new searcher from there, which sees the whole string.
+    start: usize,
+    end: usize,
+    finished: bool,
+}
+
+impl<'a, M> OverlappingBase<'a, M> {
+    #[inline]
+    fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self {
+            string,
+            matcher,
+            start: 0,
+            end: string.len(),
+            finished: false,
+        }
+    }
+}
+
+impl<'a, M: Clone + IntoSearcher<'a>> OverlappingBase<'a, M> {
+    fn next_forwards(&mut self) -> Option<(usize, &'a ByteStr)> {
+        if self.finished {
+            return None;
+        }
+        match self.matcher.clone().into_searcher_at(self.string, self.start).next_match() {
+            Some((a, b)) => {
+                // The next match can start right after the start of this one.
+                if a == self.string.len() {
+                    self.finished = true;
+                } else {
+                    self.start = a + 1;
+                }
+                Some((a, &self.string[a .. b]))
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+impl<'a, M: Clone + IntoRSearcher<'a>> OverlappingBase<'a, M> {
+    fn next_backwards(&mut self) -> Option<(usize, &'a ByteStr)> {
+        if self.finished {
+            return None;
+        }
+        match self.matcher.clone().into_rsearcher_at(self.string, self.end).next_match_back() {
+            Some((a, b)) => {
+                // The next match can end right before the end of this one.
+                if b == 0 {
+                    self.finished = true;
+                } else {
+                    self.end = b - 1;
+                }
+                Some((a, &self.string[a .. b]))
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+// MatchesOverlapping
+#[derive(Clone, Debug)]
+pub struct MatchesOverlapping<'a, M: Clone + IntoSearcher<'a>> {
+    base: OverlappingBase<'a, M>,
+}
+
+impl<'a, M: Clone + IntoSearcher<'a>> MatchesOverlapping<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: OverlappingBase::new(string, matcher) }
+    }
+}
+
+impl<'a, M: Clone + IntoSearcher<'a>> Iterator for MatchesOverlapping<'a, M> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_forwards().map(|(_, s)| s)
+    }
+}
+
+// RMatchesOverlapping
+#[derive(Clone, Debug)]
+pub struct RMatchesOverlapping<'a, M: Clone + IntoRSearcher<'a>> {
+    base: OverlappingBase<'a, M>,
+}
+
+impl<'a, M: Clone + IntoRSearcher<'a>> RMatchesOverlapping<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: OverlappingBase::new(string, matcher) }
+    }
+}
+
+impl<'a, M: Clone + IntoRSearcher<'a>> Iterator for RMatchesOverlapping<'a, M> {
+    type Item = &'a ByteStr;
+    
+    #[inline]
+    fn next(&mut self) -> Option<&'a ByteStr> {
+        self.base.next_backwards().map(|(_, s)| s)
+    }
+}
+
+// MatchIndicesOverlapping
+#[derive(Clone, Debug)]
+pub struct MatchIndicesOverlapping<'a, M: Clone + IntoSearcher<'a>> {
+    base: OverlappingBase<'a, M>,
+}
+
+impl<'a, M: Clone + IntoSearcher<'a>> MatchIndicesOverlapping<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: OverlappingBase::new(string, matcher) }
+    }
+}
+
+impl<'a, M: Clone + IntoSearcher<'a>> Iterator for MatchIndicesOverlapping<'a, M> {
+    type Item = (usize, &'a ByteStr);
+    
+    #[inline]
+    fn next(&mut self) -> Option<(usize, &'a ByteStr)> {
         self.base.next_forwards()
     }
 }
+
+// RMatchIndicesOverlapping
+#[derive(Clone, Debug)]
+pub struct RMatchIndicesOverlapping<'a, M: Clone + IntoRSearcher<'a>> {
+    base: OverlappingBase<'a, M>,
+}
+
+impl<'a, M: Clone + IntoRSearcher<'a>> RMatchIndicesOverlapping<'a, M> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: M) -> Self {
+        Self { base: OverlappingBase::new(string, matcher) }
+    }
+}
+
+impl<'a, M: Clone + IntoRSearcher<'a>> Iterator for RMatchIndicesOverlapping<'a, M> {
+    type Item = (usize, &'a ByteStr);
+    
+    #[inline]
+    fn next(&mut self) -> Option<(usize, &'a ByteStr)> {
+        self.base.next_backwards()
+    }
+}
+
+// PatternMatches
+#[cfg(not(feature="no_std"))]
+#[derive(Clone, Debug)]
+pub struct PatternMatches<'a, 'b> {
+    searcher: AhoCorasickSearcher<'b, 'a>,
+}
+
+#[cfg(not(feature="no_std"))]
+impl<'a, 'b> PatternMatches<'a, 'b> {
+    #[inline]
+    pub fn new(string: &'a ByteStr, matcher: &'b AhoCorasickMatcher) -> Self {
+        Self { searcher: matcher.into_searcher(string) }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl<'a, 'b> Iterator for PatternMatches<'a, 'b> {
+    type Item = (usize, usize, usize);
+    
+    #[inline]
+    fn next(&mut self) -> Option<(usize, usize, usize)> {
+        let (start, end) = self.searcher.next_match()?;
+        Some((self.searcher.last_pattern().unwrap(), start, end))
+    }
+}
@@ -10,6 +10,7 @@
 #[cfg(not(feature="no_std"))]
 use std as core;
 
+#[macro_use]
 mod byte_str;
 pub use byte_str::ByteStr;
 
@@ -19,8 +20,12 @@ mod byte_string;
 pub use byte_string::ByteString;
 
 pub mod iterators;
+pub use iterators::LineBreaks;
 
+mod memchr;
 mod search;
+mod two_way;
+mod utf8;
 
 pub use search::Matcher;
 pub use search::IntoMatcher;
@@ -31,9 +36,73 @@ pub use search::FullMatcher;
 pub use search::ForwardSearcher;
 pub use search::ReverseSearcher;
 pub use search::DoubleEndedSearcher;
+pub use search::SearchStep;
+pub use search::Searcher;
+pub use search::RSearcher;
+pub use search::IntoSearcher;
+pub use search::IntoRSearcher;
+pub use search::FindSearcher;
+pub use search::RFindSearcher;
+pub use search::StrSearcher;
+pub use search::StrRSearcher;
 
 pub use search::StrMatcher;
+#[cfg(not(feature="no_std"))]
+pub use search::Finder;
+#[cfg(not(feature="no_std"))]
+pub use search::FinderRev;
 pub use search::ByteMatcher;
+pub use search::ByteSetMatcher;
+pub use search::ByteSet;
+pub use search::PredicateMatcher;
+pub use search::CharMatcher;
+pub use search::CharPredicateMatcher;
+pub use search::AsciiCaseInsensitive;
+pub use search::AsciiCaseInsensitiveMatcher;
+
+pub use utf8::Utf8Error;
+#[cfg(not(feature="no_std"))]
+pub use utf8::FromUtf8Error;
+
+mod display;
+pub use display::DisplayEscaped;
+pub use display::DisplayHex;
+pub use display::DisplayTruncated;
+
+mod combinators;
+pub use combinators::MatcherExt;
+pub use combinators::Or;
+pub use combinators::OrSearcher;
+pub use combinators::OrRSearcher;
+pub use combinators::Then;
+pub use combinators::Repeat;
+pub use combinators::not;
+pub use combinators::repeat;
+
+#[cfg(not(feature="no_std"))]
+pub mod regex;
+#[cfg(not(feature="no_std"))]
+mod aho_corasick;
+#[cfg(not(feature="no_std"))]
+pub use aho_corasick::AhoCorasickMatcher;
+#[cfg(not(feature="no_std"))]
+pub use aho_corasick::AhoCorasickSearcher;
+#[cfg(not(feature="no_std"))]
+pub use aho_corasick::MatchKind;
+#[cfg(not(feature="no_std"))]
+mod glob;
+#[cfg(not(feature="no_std"))]
+pub use glob::GlobMatcher;
+#[cfg(not(feature="no_std"))]
+pub use glob::GlobMode;
+#[cfg(not(feature="no_std"))]
+pub use glob::GlobError;
+#[cfg(not(feature="no_std"))]
+mod approx;
+#[cfg(not(feature="no_std"))]
+pub use approx::HammingMatcher;
+#[cfg(not(feature="no_std"))]
+pub use approx::EditDistanceMatcher;
 
 #[cfg(test)]
 mod tests;
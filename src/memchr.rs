@@ -0,0 +1,394 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Fast search of one, two or three different bytes.
+//!
+//! A portable implementation that tests a `usize` word at a time is always
+//! available. On x86-64, SSE2 is used as baseline and AVX2 is used when it
+//! is detected at runtime (or, with `no_std`, when it is enabled at compile
+//! time). On AArch64, NEON is used, which requires Rust 1.59.
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+#[inline]
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    imp::find(One(needle), haystack)
+}
+
+/// Returns the index of the last occurrence of `needle` in `haystack`.
+#[inline]
+pub fn memrchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    imp::rfind(One(needle), haystack)
+}
+
+/// Returns the index of the first occurrence of `a` or `b` in `haystack`.
+#[inline]
+pub fn memchr2(a: u8, b: u8, haystack: &[u8]) -> Option<usize> {
+    imp::find(Two(a, b), haystack)
+}
+
+/// Returns the index of the last occurrence of `a` or `b` in `haystack`.
+#[inline]
+pub fn memrchr2(a: u8, b: u8, haystack: &[u8]) -> Option<usize> {
+    imp::rfind(Two(a, b), haystack)
+}
+
+/// Returns the index of the first occurrence of `a`, `b` or `c` in `haystack`.
+#[inline]
+pub fn memchr3(a: u8, b: u8, c: u8, haystack: &[u8]) -> Option<usize> {
+    imp::find(Three(a, b, c), haystack)
+}
+
+/// Returns the index of the last occurrence of `a`, `b` or `c` in `haystack`.
+#[inline]
+pub fn memrchr3(a: u8, b: u8, c: u8, haystack: &[u8]) -> Option<usize> {
+    imp::rfind(Three(a, b, c), haystack)
+}
+
+// Needles
+#[derive(Clone, Copy)]
+pub struct One(pub u8);
+
+#[derive(Clone, Copy)]
+pub struct Two(pub u8, pub u8);
+
+#[derive(Clone, Copy)]
+pub struct Three(pub u8, pub u8, pub u8);
+
+// A set of up to three bytes that can be compared a word or a vector
+// at a time.
+pub trait Needles: Copy {
+    fn is_match(self, byte: u8) -> bool;
+    
+    // Returns `true` if any byte of `word` is in the set.
+    fn word_has_match(self, word: usize) -> bool;
+    
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn eq_sse2(self, chunk: ::core::arch::x86_64::__m128i) -> ::core::arch::x86_64::__m128i;
+    
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn eq_avx2(self, chunk: ::core::arch::x86_64::__m256i) -> ::core::arch::x86_64::__m256i;
+    
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    unsafe fn eq_neon(self, chunk: ::core::arch::aarch64::uint8x16_t) -> ::core::arch::aarch64::uint8x16_t;
+}
+
+const LO: usize = usize::MAX / 255;
+const HI: usize = LO << 7;
+
+// Returns `true` if any byte of `word` is equal to `byte`.
+#[inline(always)]
+fn word_has_byte(word: usize, byte: u8) -> bool {
+    let x = word ^ (LO * byte as usize);
+    x.wrapping_sub(LO) & !x & HI != 0
+}
+
+macro_rules! impl_needles {
+    ($ty:ident, $($field:tt),+) => {
+        impl Needles for $ty {
+            #[inline(always)]
+            fn is_match(self, byte: u8) -> bool {
+                false $(|| byte == self.$field)+
+            }
+            
+            #[inline(always)]
+            fn word_has_match(self, word: usize) -> bool {
+                false $(|| word_has_byte(word, self.$field))+
+            }
+            
+            #[cfg(target_arch = "x86_64")]
+            #[inline(always)]
+            unsafe fn eq_sse2(self, chunk: ::core::arch::x86_64::__m128i) -> ::core::arch::x86_64::__m128i {
+                use core::arch::x86_64::*;
+                let mut r = _mm_setzero_si128();
+                $(r = _mm_or_si128(r, _mm_cmpeq_epi8(chunk, _mm_set1_epi8(self.$field as i8)));)+
+                r
+            }
+            
+            #[cfg(target_arch = "x86_64")]
+            #[inline(always)]
+            unsafe fn eq_avx2(self, chunk: ::core::arch::x86_64::__m256i) -> ::core::arch::x86_64::__m256i {
+                use core::arch::x86_64::*;
+                let mut r = _mm256_setzero_si256();
+                $(r = _mm256_or_si256(r, _mm256_cmpeq_epi8(chunk, _mm256_set1_epi8(self.$field as i8)));)+
+                r
+            }
+            
+            #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+            #[inline(always)]
+            unsafe fn eq_neon(self, chunk: ::core::arch::aarch64::uint8x16_t) -> ::core::arch::aarch64::uint8x16_t {
+                use core::arch::aarch64::*;
+                let mut r = vdupq_n_u8(0);
+                $(r = vorrq_u8(r, vceqq_u8(chunk, vdupq_n_u8(self.$field)));)+
+                r
+            }
+        }
+    }
+}
+
+impl_needles!(One, 0);
+impl_needles!(Two, 0, 1);
+impl_needles!(Three, 0, 1, 2);
+
+// Word-at-a-time implementation.
+#[allow(dead_code)]
+pub mod swar {
+    use core;
+    use super::Needles;
+    
+    const WORD_SIZE: usize = core::mem::size_of::<usize>();
+    
+    #[inline]
+    unsafe fn read_word(ptr: *const u8) -> usize {
+        core::ptr::read_unaligned(ptr as *const usize)
+    }
+    
+    pub fn find<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let len = haystack.len();
+        let ptr = haystack.as_ptr();
+        
+        let mut i = 0;
+        while i + WORD_SIZE <= len {
+            let word = unsafe { read_word(ptr.add(i)) };
+            if needles.word_has_match(word) {
+                break;
+            }
+            i += WORD_SIZE;
+        }
+        haystack[i ..].iter().position(|&byte| needles.is_match(byte)).map(|j| i + j)
+    }
+    
+    pub fn rfind<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let ptr = haystack.as_ptr();
+        
+        let mut end = haystack.len();
+        while end >= WORD_SIZE {
+            let word = unsafe { read_word(ptr.add(end - WORD_SIZE)) };
+            if needles.word_has_match(word) {
+                break;
+            }
+            end -= WORD_SIZE;
+        }
+        haystack[.. end].iter().rposition(|&byte| needles.is_match(byte))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod imp {
+    use core::arch::x86_64::*;
+    use super::Needles;
+    
+    #[inline]
+    pub fn find<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        if haystack.len() < 16 {
+            return haystack.iter().position(|&byte| needles.is_match(byte));
+        }
+        if has_avx2() && haystack.len() >= 32 {
+            unsafe { find_avx2(needles, haystack) }
+        } else {
+            unsafe { find_sse2(needles, haystack) }
+        }
+    }
+    
+    #[inline]
+    pub fn rfind<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        if haystack.len() < 16 {
+            return haystack.iter().rposition(|&byte| needles.is_match(byte));
+        }
+        if has_avx2() && haystack.len() >= 32 {
+            unsafe { rfind_avx2(needles, haystack) }
+        } else {
+            unsafe { rfind_sse2(needles, haystack) }
+        }
+    }
+    
+    #[cfg(not(feature="no_std"))]
+    #[inline]
+    fn has_avx2() -> bool {
+        is_x86_feature_detected!("avx2")
+    }
+    
+    #[cfg(feature="no_std")]
+    #[inline]
+    fn has_avx2() -> bool {
+        cfg!(target_feature = "avx2")
+    }
+    
+    // Returns a bit mask of the bytes among the 16 bytes at `ptr` that
+    // are in `needles`.
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    unsafe fn mask_sse2<N: Needles>(needles: N, ptr: *const u8) -> u32 {
+        let chunk = _mm_loadu_si128(ptr as *const __m128i);
+        _mm_movemask_epi8(needles.eq_sse2(chunk)) as u32
+    }
+    
+    // `haystack.len()` must be at least 16.
+    #[target_feature(enable = "sse2")]
+    unsafe fn find_sse2<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let len = haystack.len();
+        let ptr = haystack.as_ptr();
+        
+        let mut i = 0;
+        while i + 16 <= len {
+            let mask = mask_sse2(needles, ptr.add(i));
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += 16;
+        }
+        if i < len {
+            // The overlapping part has already been checked.
+            let mask = mask_sse2(needles, ptr.add(len - 16));
+            if mask != 0 {
+                return Some(len - 16 + mask.trailing_zeros() as usize);
+            }
+        }
+        None
+    }
+    
+    // `haystack.len()` must be at least 16.
+    #[target_feature(enable = "sse2")]
+    unsafe fn rfind_sse2<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let ptr = haystack.as_ptr();
+        
+        let mut end = haystack.len();
+        while end >= 16 {
+            end -= 16;
+            let mask = mask_sse2(needles, ptr.add(end));
+            if mask != 0 {
+                return Some(end + 31 - mask.leading_zeros() as usize);
+            }
+        }
+        if end > 0 {
+            // The overlapping part has already been checked.
+            let mask = mask_sse2(needles, ptr);
+            if mask != 0 {
+                return Some(31 - mask.leading_zeros() as usize);
+            }
+        }
+        None
+    }
+    
+    // Returns a bit mask of the bytes among the 32 bytes at `ptr` that
+    // are in `needles`.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mask_avx2<N: Needles>(needles: N, ptr: *const u8) -> u32 {
+        let chunk = _mm256_loadu_si256(ptr as *const __m256i);
+        _mm256_movemask_epi8(needles.eq_avx2(chunk)) as u32
+    }
+    
+    // `haystack.len()` must be at least 32.
+    #[target_feature(enable = "avx2")]
+    unsafe fn find_avx2<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let len = haystack.len();
+        let ptr = haystack.as_ptr();
+        
+        let mut i = 0;
+        while i + 32 <= len {
+            let mask = mask_avx2(needles, ptr.add(i));
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize);
+            }
+            i += 32;
+        }
+        if i < len {
+            // The overlapping part has already been checked.
+            let mask = mask_avx2(needles, ptr.add(len - 32));
+            if mask != 0 {
+                return Some(len - 32 + mask.trailing_zeros() as usize);
+            }
+        }
+        None
+    }
+    
+    // `haystack.len()` must be at least 32.
+    #[target_feature(enable = "avx2")]
+    unsafe fn rfind_avx2<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let ptr = haystack.as_ptr();
+        
+        let mut end = haystack.len();
+        while end >= 32 {
+            end -= 32;
+            let mask = mask_avx2(needles, ptr.add(end));
+            if mask != 0 {
+                return Some(end + 31 - mask.leading_zeros() as usize);
+            }
+        }
+        if end > 0 {
+            // The overlapping part has already been checked.
+            let mask = mask_avx2(needles, ptr);
+            if mask != 0 {
+                return Some(31 - mask.leading_zeros() as usize);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod imp {
+    use core::arch::aarch64::*;
+    use super::Needles;
+    
+    #[inline]
+    pub fn find<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        if haystack.len() < 16 {
+            return haystack.iter().position(|&byte| needles.is_match(byte));
+        }
+        unsafe { find_neon(needles, haystack) }
+    }
+    
+    #[inline]
+    pub fn rfind<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        if haystack.len() < 16 {
+            return haystack.iter().rposition(|&byte| needles.is_match(byte));
+        }
+        unsafe { rfind_neon(needles, haystack) }
+    }
+    
+    // Returns `true` if any of the 16 bytes at `ptr` is in `needles`.
+    #[inline]
+    unsafe fn any_neon<N: Needles>(needles: N, ptr: *const u8) -> bool {
+        vmaxvq_u8(needles.eq_neon(vld1q_u8(ptr))) != 0
+    }
+    
+    // `haystack.len()` must be at least 16.
+    unsafe fn find_neon<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let len = haystack.len();
+        let ptr = haystack.as_ptr();
+        
+        let mut i = 0;
+        while i + 16 <= len {
+            if any_neon(needles, ptr.add(i)) {
+                break;
+            }
+            i += 16;
+        }
+        haystack[i ..].iter().position(|&byte| needles.is_match(byte)).map(|j| i + j)
+    }
+    
+    // `haystack.len()` must be at least 16.
+    unsafe fn rfind_neon<N: Needles>(needles: N, haystack: &[u8]) -> Option<usize> {
+        let ptr = haystack.as_ptr();
+        
+        let mut end = haystack.len();
+        while end >= 16 {
+            if any_neon(needles, ptr.add(end - 16)) {
+                break;
+            }
+            end -= 16;
+        }
+        haystack[.. end].iter().rposition(|&byte| needles.is_match(byte))
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", all(target_arch = "aarch64", target_feature = "neon"))))]
+mod imp {
+    pub use super::swar::find;
+    pub use super::swar::rfind;
+}
@@ -0,0 +1,184 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Compiler from `Node` trees to Thompson NFA programs.
+//!
+//! The program is built from the end: each node is compiled with the
+//! instruction that follows it already known, so no patching is needed
+//! except for the loops of unbounded repetitions.
+
+use regex::parse::Assertion;
+use regex::parse::ByteClass;
+use regex::parse::Node;
+
+#[derive(Clone, Debug)]
+pub enum Inst {
+    Match,
+    // Consumes a byte of the class.
+    Bytes(ByteClass, usize),
+    // Continues with both, the first one having priority.
+    Split(usize, usize),
+    Save(usize, usize),
+    Assertion(Assertion, usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub insts: Vec<Inst>,
+    pub start_anchored: usize,
+    // Start with a lazy `(?s:.)*?` prefix.
+    pub start_unanchored: usize,
+    pub num_slots: usize,
+    // Byte equivalence classes: bytes in the same class are not
+    // distinguished by any instruction.
+    pub classes: [u8; 256],
+    pub num_classes: usize,
+}
+
+impl Program {
+    // Compiles `node`. Capture groups are only saved if `captures` is `true`.
+    pub fn new(node: &Node, num_groups: usize, captures: bool) -> Self {
+        let mut compiler = Compiler {
+            insts: Vec::new(),
+            captures,
+        };
+        let match_pc = compiler.push(Inst::Match);
+        let mut end = match_pc;
+        if captures {
+            end = compiler.push(Inst::Save(1, end));
+        }
+        let mut start_anchored = compiler.compile(node, end);
+        if captures {
+            start_anchored = compiler.push(Inst::Save(0, start_anchored));
+        }
+        let start_unanchored = compiler.push(Inst::Split(start_anchored, 0));
+        let any = compiler.push(Inst::Bytes(ByteClass::full(), start_unanchored));
+        compiler.insts[start_unanchored] = Inst::Split(start_anchored, any);
+        
+        let insts = compiler.insts;
+        let (classes, num_classes) = byte_classes(&insts);
+        Self {
+            insts,
+            start_anchored,
+            start_unanchored,
+            num_slots: if captures { num_groups * 2 } else { 0 },
+            classes,
+            num_classes,
+        }
+    }
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+    captures: bool,
+}
+
+impl Compiler {
+    fn push(&mut self, inst: Inst) -> usize {
+        self.insts.push(inst);
+        self.insts.len() - 1
+    }
+    
+    // Compiles `node` followed by `next`, returning the entry instruction.
+    fn compile(&mut self, node: &Node, next: usize) -> usize {
+        match *node {
+            Node::Empty => next,
+            Node::Class(class) => self.push(Inst::Bytes(class, next)),
+            Node::Assertion(assertion) => self.push(Inst::Assertion(assertion, next)),
+            Node::Capture(ref node, index) => {
+                if self.captures {
+                    let end = self.push(Inst::Save(index * 2 + 1, next));
+                    let entry = self.compile(node, end);
+                    self.push(Inst::Save(index * 2, entry))
+                } else {
+                    self.compile(node, next)
+                }
+            }
+            Node::Concat(ref nodes) => {
+                nodes.iter().rev().fold(next, |next, node| self.compile(node, next))
+            }
+            Node::Alternate(ref nodes) => {
+                let entries: Vec<usize> = nodes.iter().map(|node| self.compile(node, next)).collect();
+                let last = entries[entries.len() - 1];
+                entries[.. entries.len() - 1].iter().rev()
+                    .fold(last, |rest, &entry| self.push(Inst::Split(entry, rest)))
+            }
+            Node::Repeat { ref node, min, max, greedy } => {
+                let mut min = min;
+                let mut rest = match max {
+                    None if min == 0 && !node.can_be_empty() => {
+                        let split = self.push(Inst::Split(0, 0));
+                        let body = self.compile(node, split);
+                        self.insts[split] = self.split(body, next, greedy);
+                        split
+                    }
+                    None => {
+                        // `x+` is a loop entered through its body. `x*` is
+                        // compiled as `(x+)?` when `x` can match the empty
+                        // string, since a loop entered through its split
+                        // would prefer the wrong thread.
+                        let split = self.push(Inst::Split(0, 0));
+                        let body = self.compile(node, split);
+                        self.insts[split] = self.split(body, next, greedy);
+                        if min == 0 {
+                            let question = self.split(body, next, greedy);
+                            self.push(question)
+                        } else {
+                            min -= 1;
+                            body
+                        }
+                    }
+                    Some(max) => {
+                        let mut rest = next;
+                        for _ in min .. max {
+                            let body = self.compile(node, rest);
+                            let split = self.split(body, rest, greedy);
+                            rest = self.push(split);
+                        }
+                        rest
+                    }
+                };
+                for _ in 0 .. min {
+                    rest = self.compile(node, rest);
+                }
+                rest
+            }
+        }
+    }
+    
+    #[inline]
+    fn split(&self, body: usize, skip: usize, greedy: bool) -> Inst {
+        if greedy {
+            Inst::Split(body, skip)
+        } else {
+            Inst::Split(skip, body)
+        }
+    }
+}
+
+fn byte_classes(insts: &[Inst]) -> ([u8; 256], usize) {
+    let mut boundaries = [false; 256];
+    for inst in insts {
+        if let Inst::Bytes(ref class, _) = *inst {
+            for byte in 1 ..= 255u8 {
+                if class.contains(byte) != class.contains(byte - 1) {
+                    boundaries[byte as usize] = true;
+                }
+            }
+        }
+    }
+    
+    let mut classes = [0; 256];
+    let mut class = 0;
+    for byte in 1 .. 256 {
+        if boundaries[byte] {
+            class += 1;
+        }
+        classes[byte] = class as u8;
+    }
+    (classes, class + 1)
+}
@@ -0,0 +1,316 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Lazy DFA built from a `Program` while searching.
+//!
+//! Each DFA state is the ordered list of the NFA instructions that consume
+//! a byte or end a match (`Bytes`, `Match` and `$` assertions) reachable
+//! after the input seen so far, ordered by priority. With leftmost-first
+//! semantics the instructions after a `Match` are dropped, so the search
+//! stops once no thread can find a preferred match.
+//!
+//! States and transitions are computed on demand and cached. When the cache
+//! grows too large it is cleared and filled again.
+
+use std;
+
+use regex::SparseSet;
+use regex::compile::Inst;
+use regex::compile::Program;
+use regex::parse::Assertion;
+
+const DEAD: usize = 0;
+const UNKNOWN: usize = !0;
+const MAX_STATES: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Semantics {
+    // Stop at the match preferred by the order of the alternatives and the
+    // greediness of repetitions.
+    LeftmostFirst,
+    // Keep every thread, to report all the match ends.
+    All,
+}
+
+#[derive(Clone, Debug)]
+pub struct Dfa {
+    semantics: Semantics,
+    states: Vec<Box<[usize]>>,
+    is_match: Vec<bool>,
+    map: std::collections::HashMap<Box<[usize]>, usize>,
+    // `trans[state * num_classes + class]` is the next state, or `UNKNOWN`.
+    trans: Vec<usize>,
+    // Indexed by `anchored as usize * 2 + at_start as usize`.
+    starts: [usize; 4],
+    seen: SparseSet,
+    stack: Vec<usize>,
+}
+
+impl Dfa {
+    pub fn new(prog: &Program, semantics: Semantics) -> Self {
+        let mut dfa = Self {
+            semantics,
+            states: Vec::new(),
+            is_match: Vec::new(),
+            map: std::collections::HashMap::new(),
+            trans: Vec::new(),
+            starts: [UNKNOWN; 4],
+            seen: SparseSet::new(prog.insts.len()),
+            stack: Vec::new(),
+        };
+        dfa.clear(prog);
+        dfa
+    }
+    
+    fn clear(&mut self, prog: &Program) {
+        self.states.clear();
+        self.is_match.clear();
+        self.map.clear();
+        self.trans.clear();
+        self.starts = [UNKNOWN; 4];
+        let dead = self.add_state(prog, Vec::new());
+        for next in self.trans[dead * prog.num_classes ..].iter_mut() {
+            *next = DEAD;
+        }
+    }
+    
+    fn add_state(&mut self, prog: &Program, insts: Vec<usize>) -> usize {
+        let id = self.states.len();
+        let insts = insts.into_boxed_slice();
+        self.is_match.push(insts.iter().any(|&pc| matches!(prog.insts[pc], Inst::Match)));
+        self.map.insert(insts.clone(), id);
+        self.states.push(insts);
+        self.trans.extend((0 .. prog.num_classes).map(|_| UNKNOWN));
+        id
+    }
+    
+    // Returns the state for `insts`. The cache may be cleared, which
+    // invalidates every other state.
+    fn state(&mut self, prog: &Program, insts: Vec<usize>) -> (usize, bool) {
+        if let Some(&id) = self.map.get(&insts[..]) {
+            return (id, false);
+        }
+        let cleared = self.states.len() >= MAX_STATES;
+        if cleared {
+            self.clear(prog);
+            if insts.is_empty() {
+                return (DEAD, true);
+            }
+        }
+        (self.add_state(prog, insts), cleared)
+    }
+    
+    // Adds the instructions reachable from `pc` without consuming input to
+    // `out`, in priority order. Returns `true` if a `Match` was added and
+    // lower priority threads must be dropped.
+    fn closure(&mut self, prog: &Program, pc: usize, at_start: bool, out: &mut Vec<usize>) -> bool {
+        self.stack.push(pc);
+        while let Some(pc) = self.stack.pop() {
+            if !self.seen.insert(pc) {
+                continue;
+            }
+            match prog.insts[pc] {
+                Inst::Match => {
+                    out.push(pc);
+                    if self.semantics == Semantics::LeftmostFirst {
+                        self.stack.clear();
+                        return true;
+                    }
+                }
+                Inst::Bytes(..) | Inst::Assertion(Assertion::EndText, _) => out.push(pc),
+                Inst::Split(first, second) => {
+                    self.stack.push(second);
+                    self.stack.push(first);
+                }
+                Inst::Save(_, next) => self.stack.push(next),
+                Inst::Assertion(Assertion::StartText, next) => {
+                    if at_start {
+                        self.stack.push(next);
+                    }
+                }
+            }
+        }
+        false
+    }
+    
+    fn start_state(&mut self, prog: &Program, anchored: bool, at_start: bool) -> usize {
+        let index = anchored as usize * 2 + at_start as usize;
+        if self.starts[index] == UNKNOWN {
+            let pc = if anchored { prog.start_anchored } else { prog.start_unanchored };
+            let mut insts = Vec::new();
+            self.seen.clear();
+            self.closure(prog, pc, at_start, &mut insts);
+            let (id, _) = self.state(prog, insts);
+            self.starts[index] = id;
+        }
+        self.starts[index]
+    }
+    
+    #[inline]
+    fn next_state(&mut self, prog: &Program, state: usize, byte: u8) -> usize {
+        let index = state * prog.num_classes + prog.classes[byte as usize] as usize;
+        match self.trans[index] {
+            UNKNOWN => self.compute_next_state(prog, state, byte),
+            next => next,
+        }
+    }
+    
+    fn compute_next_state(&mut self, prog: &Program, state: usize, byte: u8) -> usize {
+        let mut insts = Vec::new();
+        self.seen.clear();
+        for i in 0 .. self.states[state].len() {
+            if let Inst::Bytes(ref class, next) = prog.insts[self.states[state][i]] {
+                if class.contains(byte) && self.closure(prog, next, false, &mut insts) {
+                    break;
+                }
+            }
+        }
+        let (next, cleared) = self.state(prog, insts);
+        if !cleared {
+            self.trans[state * prog.num_classes + prog.classes[byte as usize] as usize] = next;
+        }
+        next
+    }
+    
+    // Returns `true` if `state` matches at the end of the input.
+    fn eof_is_match(&mut self, prog: &Program, state: usize, at_start: bool) -> bool {
+        self.seen.clear();
+        self.stack.extend(self.states[state].iter().cloned());
+        while let Some(pc) = self.stack.pop() {
+            if !self.seen.insert(pc) {
+                continue;
+            }
+            match prog.insts[pc] {
+                Inst::Match => {
+                    self.stack.clear();
+                    return true;
+                }
+                Inst::Bytes(..) => {}
+                Inst::Split(first, second) => {
+                    self.stack.push(second);
+                    self.stack.push(first);
+                }
+                Inst::Save(_, next) | Inst::Assertion(Assertion::EndText, next) => self.stack.push(next),
+                Inst::Assertion(Assertion::StartText, next) => {
+                    if at_start {
+                        self.stack.push(next);
+                    }
+                }
+            }
+        }
+        false
+    }
+    
+    /// Searches forwards from `start` to `end`, returning the end of the last
+    /// match found before the search stops, or of the first one if
+    /// `earliest`. The bytes before `start` and after `end` are only used as
+    /// context.
+    pub fn find_forward(&mut self,
+                        prog: &Program,
+                        haystack: &[u8],
+                        start: usize,
+                        end: usize,
+                        anchored: bool,
+                        earliest: bool)
+        -> Option<usize>
+    {
+        let mut state = self.start_state(prog, anchored, start == 0);
+        let mut last = None;
+        if self.is_match[state] {
+            last = Some(start);
+            if earliest {
+                return last;
+            }
+        }
+        for (i, &byte) in haystack[start .. end].iter().enumerate() {
+            state = self.next_state(prog, state, byte);
+            if state == DEAD {
+                return last;
+            }
+            if self.is_match[state] {
+                last = Some(start + i + 1);
+                if earliest {
+                    return last;
+                }
+            }
+        }
+        if end == haystack.len() && self.eof_is_match(prog, state, haystack.is_empty()) {
+            last = Some(haystack.len());
+        }
+        last
+    }
+    
+    /// Searches forwards from `start` to the end of `haystack`, passing the
+    /// end of every match found before the search stops to `report`, in
+    /// increasing order.
+    pub fn find_forward_ends<R: FnMut(usize)>(&mut self,
+                                              prog: &Program,
+                                              haystack: &[u8],
+                                              start: usize,
+                                              anchored: bool,
+                                              mut report: R)
+    {
+        let mut state = self.start_state(prog, anchored, start == 0);
+        let mut last = None;
+        if self.is_match[state] {
+            report(start);
+            last = Some(start);
+        }
+        for (i, &byte) in haystack[start ..].iter().enumerate() {
+            state = self.next_state(prog, state, byte);
+            if state == DEAD {
+                return;
+            }
+            if self.is_match[state] {
+                report(start + i + 1);
+                last = Some(start + i + 1);
+            }
+        }
+        if last != Some(haystack.len()) && self.eof_is_match(prog, state, haystack.is_empty()) {
+            report(haystack.len());
+        }
+    }
+    
+    /// Searches backwards from `end` to `start` with a reversed program,
+    /// returning the start of the last match found before the search stops,
+    /// or of the first one if `earliest`. The bytes before `start` are only
+    /// used as context.
+    pub fn find_reverse(&mut self,
+                        prog: &Program,
+                        haystack: &[u8],
+                        start: usize,
+                        end: usize,
+                        anchored: bool,
+                        earliest: bool)
+        -> Option<usize>
+    {
+        let mut state = self.start_state(prog, anchored, end == haystack.len());
+        let mut last = None;
+        if self.is_match[state] {
+            last = Some(end);
+            if earliest {
+                return last;
+            }
+        }
+        for (i, &byte) in haystack[start .. end].iter().enumerate().rev() {
+            state = self.next_state(prog, state, byte);
+            if state == DEAD {
+                return last;
+            }
+            if self.is_match[state] {
+                last = Some(start + i);
+                if earliest {
+                    return last;
+                }
+            }
+        }
+        if start == 0 && self.eof_is_match(prog, state, haystack.is_empty()) {
+            last = Some(0);
+        }
+        last
+    }
+}
@@ -0,0 +1,665 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Regular expressions that match bytes.
+//!
+//! A `Regex` can be used as a matcher with the methods of `ByteStr`, such as
+//! `find`, `split` or `match_indices`, and provides the capture groups of a
+//! match with `captures`.
+//!
+//! # Syntax
+//!
+//! | Syntax                     | Matches                                              |
+//! |----------------------------|------------------------------------------------------|
+//! | `x`                        | the UTF-8 encoding of the character `x`              |
+//! | `.`                        | any byte except `\n` (any byte with the `s` flag)    |
+//! | `[abc]`, `[a-z]`, `[^a-z]` | a byte of the class; classes only accept ASCII       |
+//! | `\d`, `\w`, `\s`           | ASCII digit, word byte and whitespace                |
+//! | `\D`, `\W`, `\S`           | negation of the above                                |
+//! | `\xNN`, `\x{N}`            | the byte `0xNN`, which may be non-ASCII              |
+//! | `\n`, `\r`, `\t`, `\f`, `\v`, `\a`, `\0` | control bytes                          |
+//! | `\A` or `^`, `\z` or `$`   | the start and the end of the haystack                |
+//! | `xy`, `x\|y`               | concatenation and alternation                        |
+//! | `x*`, `x+`, `x?`           | repetitions                                          |
+//! | `x{n}`, `x{n,}`, `x{n,m}`  | counted repetitions                                  |
+//! | `x*?`, `x+?`, `x??`, ...   | lazy repetitions                                     |
+//! | `(x)`, `(?P<name>x)`, `(?<name>x)` | capture groups                               |
+//! | `(?:x)`, `(?flags:x)`, `(?flags)` | groups without capture, setting flags         |
+//!
+//! The flags are `i` (ASCII case insensitive) and `s` (`.` matches `\n`), and
+//! can be cleared with `-`, as in `(?-i)`.
+//!
+//! When several matches start at the same position, the alternatives and
+//! repetitions select one like in Perl: earlier alternatives are preferred,
+//! and greedy repetitions match as much as possible. Searches from the end,
+//! such as `rfind` or `rsplit`, report the match that ends last and, among
+//! those, the longest one.
+//!
+//! Methods that search a string once, such as `find`, `rfind` or
+//! `starts_with`, only see the string they are given, so `^` and `$` match
+//! at its start and end. Iterators such as `split`, `rsplit` or
+//! `match_indices` search the whole string in both directions, so `^` only
+//! matches at its start and `$` at its end. For example, both splitting
+//! `b"aaa"` with `^a` and splitting it from the end with `a$` give an empty
+//! string and `b"aa"`.
+//!
+//! # Implementation
+//!
+//! The pattern is compiled into a Thompson NFA, which is searched with a
+//! lazy DFA. The DFA finds where a match ends, a DFA of the reversed NFA
+//! finds where it starts, and only then the NFA is simulated to resolve the
+//! capture groups. All of them take linear time in the length of the
+//! haystack.
+
+use std;
+
+use ByteStr;
+use Matcher;
+use IntoMatcher;
+use PrefixMatcher;
+use SufixMatcher;
+use FullMatcher;
+use ForwardSearcher;
+use ReverseSearcher;
+use SearchStep;
+use Searcher;
+use IntoSearcher;
+use IntoRSearcher;
+use RSearcher;
+
+mod compile;
+mod dfa;
+mod parse;
+mod pike_vm;
+
+use self::compile::Program;
+use self::dfa::Dfa;
+use self::dfa::Semantics;
+use self::pike_vm::PikeVm;
+
+/// Error returned when a pattern is not valid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    message: &'static str,
+    position: usize,
+}
+
+impl Error {
+    fn new(message: &'static str, position: usize) -> Self {
+        Self { message, position }
+    }
+    
+    /// Returns the position in the pattern, in bytes, where the error was
+    /// found.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "regex parse error at position {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Compiled regular expression.
+///
+/// See the [module documentation](index.html) for the syntax.
+///
+/// A `Regex` can be shared between threads. The lazily built automata are
+/// kept behind a lock; a search that finds it taken, from another thread or
+/// from a nested search, uses automata of its own instead of waiting.
+pub struct Regex {
+    pattern: String,
+    names: std::sync::Arc<Vec<Option<String>>>,
+    forward: Program,
+    reverse: Program,
+    cache: std::sync::Mutex<Cache>,
+}
+
+#[derive(Clone, Debug)]
+struct Cache {
+    forward_first: Dfa,
+    forward_all: Dfa,
+    reverse_all: Dfa,
+    pike_vm: PikeVm,
+}
+
+impl Cache {
+    fn new(forward: &Program, reverse: &Program) -> Self {
+        Self {
+            forward_first: Dfa::new(forward, Semantics::LeftmostFirst),
+            forward_all: Dfa::new(forward, Semantics::All),
+            reverse_all: Dfa::new(reverse, Semantics::All),
+            pike_vm: PikeVm::new(forward),
+        }
+    }
+}
+
+impl Regex {
+    /// Compiles `pattern`.
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let ast = parse::parse(pattern)?;
+        let forward = Program::new(&ast.root, ast.names.len(), true);
+        let reverse = Program::new(&ast.root.reverse(), ast.names.len(), false);
+        let cache = Cache::new(&forward, &reverse);
+        Ok(Self {
+            pattern: pattern.to_string(),
+            names: std::sync::Arc::new(ast.names),
+            forward,
+            reverse,
+            cache: std::sync::Mutex::new(cache),
+        })
+    }
+    
+    // Calls `f` with the cached automata, or with new ones if they are in
+    // use. A poisoned lock is recovered, since the automata are only a
+    // cache and are cleared whenever they grow too large.
+    fn with_cache<T, F: FnOnce(&mut Cache) -> T>(&self, f: F) -> T {
+        match self.cache.try_lock() {
+            Ok(mut cache) => f(&mut cache),
+            Err(std::sync::TryLockError::Poisoned(error)) => f(&mut error.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => f(&mut Cache::new(&self.forward, &self.reverse)),
+        }
+    }
+    
+    /// Returns the pattern of the regex.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+    
+    /// Returns the number of capture groups, including the group 0 that
+    /// corresponds to the whole match.
+    #[inline]
+    pub fn captures_len(&self) -> usize {
+        self.names.len()
+    }
+    
+    /// Returns the capture groups of the leftmost match in `haystack`.
+    pub fn captures<'a>(&self, haystack: &'a ByteStr) -> Option<Captures<'a>> {
+        let (start, _) = self.find(haystack)?;
+        let slots = self.with_cache(|cache| cache.pike_vm.captures(&self.forward, haystack.as_slice(), start))?;
+        Some(Captures {
+            haystack,
+            slots,
+            names: self.names.clone(),
+        })
+    }
+    
+    // Returns the leftmost match that starts at or after `start`. The bytes
+    // before `start` are used as context for the anchors.
+    fn find_at(&self, haystack: &ByteStr, start: usize) -> Option<(usize, usize)> {
+        let haystack = haystack.as_slice();
+        self.with_cache(|cache| {
+            let end = cache.forward_first.find_forward(&self.forward, haystack, start, haystack.len(), false, false)?;
+            // The leftmost match starts where the longest match of the
+            // reversed regex ending at `end` does.
+            let start = cache.reverse_all.find_reverse(&self.reverse, haystack, start, end, true, false)
+                .expect("reverse search did not find the match");
+            Some((start, end))
+        })
+    }
+    
+    // Returns the start of the longest match that ends at `end`, which
+    // must be the end of a match. The bytes after `end` are used as context
+    // for the anchors.
+    fn longest_match_ending_at(&self, haystack: &ByteStr, end: usize) -> usize {
+        self.with_cache(|cache| cache.reverse_all.find_reverse(&self.reverse, haystack.as_slice(), 0, end, true, false))
+            .expect("reverse search did not find the match")
+    }
+    
+    // Returns the ends of all the matches in `haystack`, in increasing
+    // order.
+    fn match_ends(&self, haystack: &ByteStr) -> Vec<usize> {
+        let mut ends = Vec::new();
+        self.with_cache(|cache| cache.forward_all.find_forward_ends(&self.forward, haystack.as_slice(), 0, false, |end| ends.push(end)));
+        ends
+    }
+}
+
+impl Clone for Regex {
+    fn clone(&self) -> Self {
+        let cache = self.with_cache(|cache| cache.clone());
+        Self {
+            pattern: self.pattern.clone(),
+            names: self.names.clone(),
+            forward: self.forward.clone(),
+            reverse: self.reverse.clone(),
+            cache: std::sync::Mutex::new(cache),
+        }
+    }
+}
+
+impl std::fmt::Debug for Regex {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        f.debug_tuple("Regex").field(&self.pattern).finish()
+    }
+}
+
+impl Matcher for Regex {}
+
+impl<'a> IntoMatcher for &'a Regex {
+    type Matcher = &'a Regex;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a Regex {
+        self
+    }
+}
+
+impl<'r, 'a> IntoSearcher<'a> for &'r Regex {
+    type Searcher = RegexSearcher<'r, 'a>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> RegexSearcher<'r, 'a> {
+        RegexSearcher {
+            regex: self,
+            haystack,
+            pos: start,
+            empty: false,
+            end: haystack.len(),
+            pending: None,
+        }
+    }
+}
+
+impl<'r, 'a> IntoRSearcher<'a> for &'r Regex {
+    type RSearcher = RegexRSearcher<'r, 'a>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> RegexRSearcher<'r, 'a> {
+        RegexRSearcher {
+            regex: self,
+            haystack,
+            end,
+            empty: false,
+            ends: None,
+        }
+    }
+}
+
+impl PrefixMatcher for Regex {
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        self.with_cache(|cache| {
+            cache.forward_first.find_forward(&self.forward, haystack.as_slice(), 0, haystack.len(), true, true).is_some()
+        })
+    }
+}
+
+impl SufixMatcher for Regex {
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        let haystack = haystack.as_slice();
+        self.with_cache(|cache| cache.reverse_all.find_reverse(&self.reverse, haystack, 0, haystack.len(), true, true).is_some())
+    }
+}
+
+impl FullMatcher for Regex {
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        self.with_cache(|cache| {
+            cache.forward_all.find_forward(&self.forward, haystack.as_slice(), 0, haystack.len(), true, false) == Some(haystack.len())
+        })
+    }
+}
+
+impl ForwardSearcher for Regex {
+    #[inline]
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        self.find_at(haystack, 0)
+    }
+}
+
+impl ReverseSearcher for Regex {
+    /// Returns the match that ends last; among the matches that end at the
+    /// same position, the longest one.
+    #[inline]
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        let end = self.with_cache(|cache| {
+            cache.forward_all.find_forward(&self.forward, haystack.as_slice(), 0, haystack.len(), false, false)
+        })?;
+        Some((self.longest_match_ending_at(haystack, end), end))
+    }
+}
+
+/// `Searcher` for a `Regex`.
+///
+/// It searches the whole haystack, so `^` only matches at its start and `$`
+/// at its end, and the automata built for a match are reused by the
+/// following ones. From the back, it reports the same matches as from the
+/// front in reverse order, which makes iterators such as `split`
+/// double-ended. Which matches are found from the front depends on the
+/// previous ones, so the first step from the back finds all the matches
+/// that have not been reported yet and keeps them.
+#[derive(Clone, Debug)]
+pub struct RegexSearcher<'r, 'a> {
+    regex: &'r Regex,
+    haystack: &'a ByteStr,
+    pos: usize,
+    // Whether an empty match at `pos` has been reported.
+    empty: bool,
+    // Where the next step from the back ends.
+    end: usize,
+    // The matches that have been reported from neither end, once a step
+    // has been taken from the back.
+    pending: Option<std::collections::VecDeque<(usize, usize)>>,
+}
+
+impl<'r, 'a> RegexSearcher<'r, 'a> {
+    // Returns the next match from the front after `pos`, skipping a byte
+    // after an empty match.
+    fn find_front(&self, mut pos: usize, empty: bool) -> Option<(usize, usize)> {
+        if empty {
+            if pos == self.haystack.len() {
+                return None;
+            }
+            pos += 1;
+        }
+        self.regex.find_at(self.haystack, pos)
+    }
+    
+    fn pending(&mut self) -> &mut std::collections::VecDeque<(usize, usize)> {
+        if self.pending.is_none() {
+            let (mut pos, mut empty) = (self.pos, self.empty);
+            let mut pending = std::collections::VecDeque::new();
+            while let Some((a, b)) = self.find_front(pos, empty) {
+                pending.push_back((a, b));
+                pos = b;
+                empty = a == b;
+            }
+            self.pending = Some(pending);
+        }
+        self.pending.as_mut().unwrap()
+    }
+}
+
+impl<'r, 'a> Searcher<'a> for RegexSearcher<'r, 'a> {
+    #[inline]
+    fn haystack(&self) -> &'a ByteStr {
+        self.haystack
+    }
+    
+    #[inline]
+    fn cursor(&self) -> usize {
+        self.pos
+    }
+    
+    fn next(&mut self) -> SearchStep {
+        let pos = self.pos;
+        let found = match self.pending {
+            Some(ref pending) => pending.front().cloned(),
+            None if self.empty => {
+                if pos == self.end {
+                    return SearchStep::Done;
+                }
+                self.empty = false;
+                self.pos += 1;
+                return SearchStep::Reject(pos, pos + 1);
+            }
+            None => self.find_front(pos, false),
+        };
+        match found {
+            Some((a, b)) if a == pos => {
+                if let Some(ref mut pending) = self.pending {
+                    pending.pop_front();
+                }
+                self.pos = b;
+                self.empty = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((a, _)) => {
+                // The match is found again by the next call.
+                self.pos = a;
+                SearchStep::Reject(pos, a)
+            }
+            None if pos != self.end => {
+                self.pos = self.end;
+                SearchStep::Reject(pos, self.pos)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        let (a, b) = match self.pending {
+            Some(ref mut pending) => pending.pop_front()?,
+            None => self.find_front(self.pos, self.empty)?,
+        };
+        self.pos = b;
+        self.empty = a == b;
+        Some((a, b))
+    }
+}
+
+impl<'r, 'a> RSearcher<'a> for RegexSearcher<'r, 'a> {
+    #[inline]
+    fn cursor_back(&self) -> usize {
+        self.end
+    }
+    
+    fn next_back(&mut self) -> SearchStep {
+        let end = self.end;
+        match self.pending().back().cloned() {
+            Some((a, b)) if b == end => {
+                self.pending().pop_back();
+                self.end = a;
+                SearchStep::Match(a, b)
+            }
+            Some((_, b)) => {
+                // The match is found again by the next call.
+                self.end = b;
+                SearchStep::Reject(b, end)
+            }
+            None if end > self.pos => {
+                self.end = self.pos;
+                SearchStep::Reject(self.pos, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        let (a, b) = self.pending().pop_back()?;
+        self.end = a;
+        Some((a, b))
+    }
+}
+
+/// `RSearcher` for a `Regex`, used by the iterators that search from the
+/// back, such as `rsplit`.
+///
+/// Each search reports the match that ends last, like `rfind`, but in the
+/// whole haystack, so `^` only matches at its start and `$` at its end. The
+/// first step finds where all the matches end, with a single search from
+/// the front.
+#[derive(Clone, Debug)]
+pub struct RegexRSearcher<'r, 'a> {
+    regex: &'r Regex,
+    haystack: &'a ByteStr,
+    end: usize,
+    // Whether an empty match at `end` has been reported.
+    empty: bool,
+    // The ends of the matches, up to the last one that ends at or before
+    // `end`.
+    ends: Option<Vec<usize>>,
+}
+
+impl<'r, 'a> RegexRSearcher<'r, 'a> {
+    // Returns the match that ends last at or before `end` and, among those,
+    // the longest one.
+    fn rfind_at(&mut self, end: usize) -> Option<(usize, usize)> {
+        let (regex, haystack) = (self.regex, self.haystack);
+        let ends = self.ends.get_or_insert_with(|| regex.match_ends(haystack));
+        while ends.last().map_or(false, |&last| last > end) {
+            ends.pop();
+        }
+        let end = *ends.last()?;
+        Some((regex.longest_match_ending_at(haystack, end), end))
+    }
+}
+
+impl<'r, 'a> RSearcher<'a> for RegexRSearcher<'r, 'a> {
+    #[inline]
+    fn cursor_back(&self) -> usize {
+        self.end
+    }
+    
+    fn next_back(&mut self) -> SearchStep {
+        let end = self.end;
+        if self.empty {
+            if end == 0 {
+                return SearchStep::Done;
+            }
+            self.empty = false;
+            self.end -= 1;
+            return SearchStep::Reject(end - 1, end);
+        }
+        match self.rfind_at(end) {
+            Some((a, b)) if b == end => {
+                self.end = a;
+                self.empty = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((_, b)) => {
+                // The match is found again by the next call.
+                self.end = b;
+                SearchStep::Reject(b, end)
+            }
+            None if end != 0 => {
+                self.end = 0;
+                SearchStep::Reject(0, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        if self.empty {
+            if self.end == 0 {
+                return None;
+            }
+            self.empty = false;
+            self.end -= 1;
+        }
+        let end = self.end;
+        let (a, b) = self.rfind_at(end)?;
+        self.end = a;
+        self.empty = a == b;
+        Some((a, b))
+    }
+}
+
+/// Capture groups of a match of a `Regex`.
+#[derive(Clone, Debug)]
+pub struct Captures<'a> {
+    haystack: &'a ByteStr,
+    slots: Vec<Option<usize>>,
+    names: std::sync::Arc<Vec<Option<String>>>,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<'a> Captures<'a> {
+    /// Returns the string matched by the group `index`, or `None` if the
+    /// group did not participate in the match. The group 0 is the whole
+    /// match.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&'a ByteStr> {
+        self.pos(index).map(|(start, end)| &self.haystack[start .. end])
+    }
+    
+    /// Returns the string matched by the group called `name`.
+    pub fn name(&self, name: &str) -> Option<&'a ByteStr> {
+        let index = self.names.iter().position(|n| n.as_ref().map_or(false, |n| n == name))?;
+        self.get(index)
+    }
+    
+    /// Returns the start and end of the group `index`.
+    #[inline]
+    pub fn pos(&self, index: usize) -> Option<(usize, usize)> {
+        match (self.slots.get(index * 2), self.slots.get(index * 2 + 1)) {
+            (Some(&Some(start)), Some(&Some(end))) => Some((start, end)),
+            _ => None,
+        }
+    }
+    
+    /// Returns the number of groups, including the group 0.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slots.len() / 2
+    }
+}
+
+impl<'a> std::ops::Index<usize> for Captures<'a> {
+    type Output = ByteStr;
+    
+    /// Returns the string matched by the group `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group did not participate in the match.
+    fn index(&self, index: usize) -> &ByteStr {
+        self.get(index).expect("no group at the given index")
+    }
+}
+
+// Set of instruction indices that keeps insertion order and is cleared in
+// constant time.
+#[derive(Clone, Debug)]
+struct SparseSet {
+    dense: Vec<usize>,
+    sparse: Box<[usize]>,
+}
+
+impl SparseSet {
+    fn new(capacity: usize) -> Self {
+        Self {
+            dense: Vec::with_capacity(capacity),
+            sparse: vec![0; capacity].into_boxed_slice(),
+        }
+    }
+    
+    #[inline]
+    fn len(&self) -> usize {
+        self.dense.len()
+    }
+    
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+    
+    #[inline]
+    fn get(&self, i: usize) -> usize {
+        self.dense[i]
+    }
+    
+    #[inline]
+    fn contains(&self, value: usize) -> bool {
+        let i = self.sparse[value];
+        i < self.dense.len() && self.dense[i] == value
+    }
+    
+    // Returns `false` if `value` was already in the set.
+    #[inline]
+    fn insert(&mut self, value: usize) -> bool {
+        if self.contains(value) {
+            return false;
+        }
+        self.sparse[value] = self.dense.len();
+        self.dense.push(value);
+        true
+    }
+    
+    #[inline]
+    fn clear(&mut self) {
+        self.dense.clear();
+    }
+}
@@ -0,0 +1,557 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Parser from the pattern syntax to `Node` trees.
+
+use std;
+
+use regex::Error;
+
+// Limit of the counted repetitions (`{n,m}`), which are expanded when
+// compiling.
+const MAX_REPEAT: u32 = 1000;
+
+// Set of bytes, as a bitmap.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ByteClass([u64; 4]);
+
+impl ByteClass {
+    #[inline]
+    pub fn empty() -> Self {
+        ByteClass([0; 4])
+    }
+    
+    #[inline]
+    pub fn full() -> Self {
+        ByteClass([!0; 4])
+    }
+    
+    #[inline]
+    pub fn single(byte: u8) -> Self {
+        let mut class = Self::empty();
+        class.insert(byte);
+        class
+    }
+    
+    #[inline]
+    pub fn contains(&self, byte: u8) -> bool {
+        self.0[(byte >> 6) as usize] & (1 << (byte & 0x3F)) != 0
+    }
+    
+    #[inline]
+    pub fn insert(&mut self, byte: u8) {
+        self.0[(byte >> 6) as usize] |= 1 << (byte & 0x3F);
+    }
+    
+    pub fn insert_range(&mut self, first: u8, last: u8) {
+        for byte in first ..= last {
+            self.insert(byte);
+        }
+    }
+    
+    pub fn union(&mut self, other: &ByteClass) {
+        for (word, other_word) in self.0.iter_mut().zip(other.0.iter()) {
+            *word |= *other_word;
+        }
+    }
+    
+    pub fn negate(&mut self) {
+        for word in self.0.iter_mut() {
+            *word = !*word;
+        }
+    }
+    
+    // Adds the other case of every ASCII letter of the class.
+    pub fn fold_ascii_case(&mut self) {
+        for byte in b'A' ..= b'Z' {
+            if self.contains(byte) || self.contains(byte.to_ascii_lowercase()) {
+                self.insert(byte);
+                self.insert(byte.to_ascii_lowercase());
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for ByteClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let mut list = f.debug_list();
+        for byte in 0 ..= 255u8 {
+            if self.contains(byte) {
+                list.entry(&byte);
+            }
+        }
+        list.finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Assertion {
+    StartText,
+    EndText,
+}
+
+#[derive(Clone, Debug)]
+pub enum Node {
+    Empty,
+    Class(ByteClass),
+    Assertion(Assertion),
+    // Capture group with its index.
+    Capture(Box<Node>, usize),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+    },
+}
+
+impl Node {
+    pub fn can_be_empty(&self) -> bool {
+        match *self {
+            Node::Empty | Node::Assertion(_) => true,
+            Node::Class(_) => false,
+            Node::Capture(ref node, _) => node.can_be_empty(),
+            Node::Concat(ref nodes) => nodes.iter().all(Node::can_be_empty),
+            Node::Alternate(ref nodes) => nodes.iter().any(Node::can_be_empty),
+            Node::Repeat { ref node, min, .. } => min == 0 || node.can_be_empty(),
+        }
+    }
+    
+    // Returns a node that matches the reversed strings, without captures.
+    pub fn reverse(&self) -> Node {
+        match *self {
+            Node::Empty => Node::Empty,
+            Node::Class(class) => Node::Class(class),
+            Node::Assertion(Assertion::StartText) => Node::Assertion(Assertion::EndText),
+            Node::Assertion(Assertion::EndText) => Node::Assertion(Assertion::StartText),
+            Node::Capture(ref node, _) => node.reverse(),
+            Node::Concat(ref nodes) => Node::Concat(nodes.iter().rev().map(Node::reverse).collect()),
+            Node::Alternate(ref nodes) => Node::Alternate(nodes.iter().map(Node::reverse).collect()),
+            Node::Repeat { ref node, min, max, greedy } => Node::Repeat {
+                node: Box::new(node.reverse()),
+                min,
+                max,
+                greedy,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Flags {
+    case_insensitive: bool,
+    dot_matches_new_line: bool,
+}
+
+// Result of parsing a pattern.
+pub struct Ast {
+    pub root: Node,
+    // Names of the capture groups, including the implicit group 0.
+    pub names: Vec<Option<String>>,
+}
+
+pub fn parse(pattern: &str) -> Result<Ast, Error> {
+    let mut parser = Parser {
+        pattern,
+        pos: 0,
+        names: vec![None],
+    };
+    let flags = Flags {
+        case_insensitive: false,
+        dot_matches_new_line: false,
+    };
+    let root = parser.parse_alternation(flags, 0)?;
+    if parser.pos != pattern.len() {
+        // Only a `)` can stop the top level alternation.
+        return Err(parser.error("unopened group"));
+    }
+    Ok(Ast { root, names: parser.names })
+}
+
+struct Parser<'a> {
+    pattern: &'a str,
+    pos: usize,
+    names: Vec<Option<String>>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &'static str) -> Error {
+        Error::new(message, self.pos)
+    }
+    
+    #[inline]
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.pos ..].chars().next()
+    }
+    
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+    
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+    
+    fn parse_alternation(&mut self, mut flags: Flags, depth: usize) -> Result<Node, Error> {
+        let mut branches = Vec::new();
+        loop {
+            branches.push(self.parse_concat(&mut flags, depth)?);
+            if !self.eat('|') {
+                break;
+            }
+        }
+        if branches.len() == 1 {
+            Ok(branches.pop().unwrap())
+        } else {
+            Ok(Node::Alternate(branches))
+        }
+    }
+    
+    // Flags set with `(?flags)` last until the end of the enclosing group,
+    // so they are shared by the following branches of the alternation.
+    fn parse_concat(&mut self, flags: &mut Flags, depth: usize) -> Result<Node, Error> {
+        let mut nodes = Vec::new();
+        loop {
+            let atom = match self.peek() {
+                None | Some('|') => break,
+                Some(')') => {
+                    if depth == 0 {
+                        return Err(self.error("unopened group"));
+                    }
+                    break;
+                }
+                Some('*') | Some('+') | Some('?') | Some('{') => {
+                    return Err(self.error("repetition operator missing expression"));
+                }
+                Some('(') => {
+                    self.pos += 1;
+                    match self.parse_group(flags, depth)? {
+                        Some(node) => node,
+                        None => continue,
+                    }
+                }
+                Some(_) => self.parse_atom(*flags)?,
+            };
+            let node = self.parse_repetition(atom)?;
+            nodes.push(node);
+        }
+        match nodes.len() {
+            0 => Ok(Node::Empty),
+            1 => Ok(nodes.pop().unwrap()),
+            _ => Ok(Node::Concat(nodes)),
+        }
+    }
+    
+    // Parses after a `(`. Returns `None` for a flag setting group such
+    // as `(?i)`, which changes `flags` instead.
+    fn parse_group(&mut self, flags: &mut Flags, depth: usize) -> Result<Option<Node>, Error> {
+        let group_start = self.pos - 1;
+        let mut inner_flags = *flags;
+        let mut capture = None;
+        if self.eat('?') {
+            let named = if self.eat('P') {
+                if !self.eat('<') {
+                    return Err(self.error("invalid group name"));
+                }
+                true
+            } else {
+                self.eat('<')
+            };
+            if named {
+                let name_start = self.pos;
+                let name_end = match self.pattern[name_start ..].find('>') {
+                    Some(len) => name_start + len,
+                    None => return Err(Error::new("unclosed group name", name_start)),
+                };
+                let name = &self.pattern[name_start .. name_end];
+                if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) ||
+                    !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return Err(Error::new("invalid group name", name_start));
+                }
+                if self.names.iter().any(|n| n.as_ref().map_or(false, |n| n == name)) {
+                    return Err(Error::new("duplicate group name", name_start));
+                }
+                self.pos = name_end + 1;
+                capture = Some(self.names.len());
+                self.names.push(Some(name.to_string()));
+            } else {
+                let mut negate = false;
+                loop {
+                    match self.next_char() {
+                        Some('i') => inner_flags.case_insensitive = !negate,
+                        Some('s') => inner_flags.dot_matches_new_line = !negate,
+                        Some('-') if !negate => negate = true,
+                        Some(':') => break,
+                        Some(')') => {
+                            *flags = inner_flags;
+                            return Ok(None);
+                        }
+                        Some(_) => return Err(Error::new("unrecognized flag", self.pos - 1)),
+                        None => return Err(Error::new("unclosed group", group_start)),
+                    }
+                }
+            }
+        } else {
+            capture = Some(self.names.len());
+            self.names.push(None);
+        }
+        
+        let node = self.parse_alternation(inner_flags, depth + 1)?;
+        if !self.eat(')') {
+            return Err(Error::new("unclosed group", group_start));
+        }
+        Ok(Some(match capture {
+            Some(index) => Node::Capture(Box::new(node), index),
+            None => node,
+        }))
+    }
+    
+    fn parse_repetition(&mut self, node: Node) -> Result<Node, Error> {
+        let (min, max) = match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                (0, None)
+            }
+            Some('+') => {
+                self.pos += 1;
+                (1, None)
+            }
+            Some('?') => {
+                self.pos += 1;
+                (0, Some(1))
+            }
+            Some('{') => {
+                let start = self.pos;
+                self.pos += 1;
+                let min = self.parse_count()?;
+                let max = if self.eat(',') {
+                    if self.peek() == Some('}') {
+                        None
+                    } else {
+                        Some(self.parse_count()?)
+                    }
+                } else {
+                    Some(min)
+                };
+                if !self.eat('}') {
+                    return Err(Error::new("unclosed counted repetition", start));
+                }
+                if max.map_or(false, |max| max < min) {
+                    return Err(Error::new("invalid counted repetition range", start));
+                }
+                (min, max)
+            }
+            _ => return Ok(node),
+        };
+        let greedy = !self.eat('?');
+        match self.peek() {
+            Some('*') | Some('+') | Some('?') | Some('{') => {
+                return Err(self.error("repetition operator missing expression"));
+            }
+            _ => {}
+        }
+        if let Node::Assertion(_) = node {
+            // Repeating an assertion is the same as making it optional.
+            return Ok(if min == 0 { Node::Empty } else { node });
+        }
+        Ok(Node::Repeat { node: Box::new(node), min, max, greedy })
+    }
+    
+    fn parse_count(&mut self) -> Result<u32, Error> {
+        let start = self.pos;
+        let mut count = 0u32;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            self.pos += 1;
+            count = count.saturating_mul(10).saturating_add(digit);
+        }
+        if self.pos == start {
+            return Err(self.error("invalid counted repetition"));
+        }
+        if count > MAX_REPEAT {
+            return Err(Error::new("counted repetition too large", start));
+        }
+        Ok(count)
+    }
+    
+    fn parse_atom(&mut self, flags: Flags) -> Result<Node, Error> {
+        let c = self.next_char().unwrap();
+        let node = match c {
+            '.' => {
+                let mut class = ByteClass::full();
+                if !flags.dot_matches_new_line {
+                    class = ByteClass::empty();
+                    class.insert(b'\n');
+                    class.negate();
+                }
+                Node::Class(class)
+            }
+            '^' => Node::Assertion(Assertion::StartText),
+            '$' => Node::Assertion(Assertion::EndText),
+            '[' => Node::Class(self.parse_class(flags)?),
+            '\\' => match self.parse_escape()? {
+                Escape::Byte(byte) => literal(byte, flags),
+                Escape::Class(class) => Node::Class(class),
+                Escape::Assertion(assertion) => Node::Assertion(assertion),
+            },
+            c if c.is_ascii() => literal(c as u8, flags),
+            c => {
+                // A non-ASCII character matches its UTF-8 encoding.
+                let mut buf = [0; 4];
+                let bytes = c.encode_utf8(&mut buf).as_bytes();
+                Node::Concat(bytes.iter().map(|&byte| Node::Class(ByteClass::single(byte))).collect())
+            }
+        };
+        Ok(node)
+    }
+    
+    // Parses after a `\`.
+    fn parse_escape(&mut self) -> Result<Escape, Error> {
+        let start = self.pos - 1;
+        let c = match self.next_char() {
+            Some(c) => c,
+            None => return Err(Error::new("incomplete escape sequence", start)),
+        };
+        let escape = match c {
+            'x' => Escape::Byte(self.parse_hex(start)?),
+            'n' => Escape::Byte(b'\n'),
+            'r' => Escape::Byte(b'\r'),
+            't' => Escape::Byte(b'\t'),
+            'f' => Escape::Byte(0x0C),
+            'v' => Escape::Byte(0x0B),
+            'a' => Escape::Byte(0x07),
+            '0' => Escape::Byte(0),
+            'A' => Escape::Assertion(Assertion::StartText),
+            'z' => Escape::Assertion(Assertion::EndText),
+            'd' | 'D' | 'w' | 'W' | 's' | 'S' => {
+                let mut class = ByteClass::empty();
+                match c.to_ascii_lowercase() {
+                    'd' => class.insert_range(b'0', b'9'),
+                    'w' => {
+                        class.insert_range(b'0', b'9');
+                        class.insert_range(b'A', b'Z');
+                        class.insert_range(b'a', b'z');
+                        class.insert(b'_');
+                    }
+                    _ => {
+                        class.insert_range(b'\t', b'\r');
+                        class.insert(b' ');
+                    }
+                }
+                if c.is_ascii_uppercase() {
+                    class.negate();
+                }
+                Escape::Class(class)
+            }
+            c if c.is_ascii_punctuation() || c == ' ' => Escape::Byte(c as u8),
+            _ => return Err(Error::new("unrecognized escape sequence", start)),
+        };
+        Ok(escape)
+    }
+    
+    // Parses the digits of `\xNN` or `\x{N}`.
+    fn parse_hex(&mut self, start: usize) -> Result<u8, Error> {
+        let braced = self.eat('{');
+        let mut value = 0u32;
+        let mut digits = 0;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) {
+            if !braced && digits == 2 {
+                break;
+            }
+            self.pos += 1;
+            value = value.saturating_mul(16).saturating_add(digit);
+            digits += 1;
+        }
+        if digits == 0 || (!braced && digits != 2) || (braced && !self.eat('}')) {
+            return Err(Error::new("invalid hexadecimal escape", start));
+        }
+        if value > 0xFF {
+            return Err(Error::new("hexadecimal escape out of the byte range", start));
+        }
+        Ok(value as u8)
+    }
+    
+    // Parses after a `[`.
+    fn parse_class(&mut self, flags: Flags) -> Result<ByteClass, Error> {
+        let start = self.pos - 1;
+        let negated = self.eat('^');
+        let mut class = ByteClass::empty();
+        let mut first = true;
+        loop {
+            let c = match self.next_char() {
+                Some(c) => c,
+                None => return Err(Error::new("unclosed character class", start)),
+            };
+            let item_start = self.pos - c.len_utf8();
+            let low = match c {
+                ']' if !first => break,
+                '\\' => match self.parse_escape()? {
+                    Escape::Byte(byte) => byte,
+                    Escape::Class(other) => {
+                        class.union(&other);
+                        first = false;
+                        continue;
+                    }
+                    Escape::Assertion(_) => {
+                        return Err(Error::new("invalid escape sequence in class", item_start));
+                    }
+                },
+                c if c.is_ascii() => c as u8,
+                _ => return Err(Error::new("non-ASCII character in class", item_start)),
+            };
+            first = false;
+            
+            if self.peek() == Some('-') && !self.pattern[self.pos + 1 ..].starts_with(']') {
+                self.pos += 1;
+                let high = match self.next_char() {
+                    Some('\\') => match self.parse_escape()? {
+                        Escape::Byte(byte) => byte,
+                        _ => return Err(Error::new("invalid class range", item_start)),
+                    },
+                    Some(c) if c.is_ascii() => c as u8,
+                    Some(_) => return Err(Error::new("non-ASCII character in class", self.pos - 1)),
+                    None => return Err(Error::new("unclosed character class", start)),
+                };
+                if high < low {
+                    return Err(Error::new("invalid class range", item_start));
+                }
+                class.insert_range(low, high);
+            } else {
+                class.insert(low);
+            }
+        }
+        if flags.case_insensitive {
+            class.fold_ascii_case();
+        }
+        if negated {
+            class.negate();
+        }
+        Ok(class)
+    }
+}
+
+enum Escape {
+    Byte(u8),
+    Class(ByteClass),
+    Assertion(Assertion),
+}
+
+fn literal(byte: u8, flags: Flags) -> Node {
+    let mut class = ByteClass::single(byte);
+    if flags.case_insensitive {
+        class.fold_ascii_case();
+    }
+    Node::Class(class)
+}
@@ -0,0 +1,145 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Simulation of a `Program` that keeps track of the capture groups
+//! (Pike VM).
+//!
+//! It runs the threads in lockstep in priority order, so it takes linear
+//! time, but it is slower than the DFA. It is only used to resolve the
+//! groups once the DFA has found where the match starts.
+
+use std;
+
+use regex::SparseSet;
+use regex::compile::Inst;
+use regex::compile::Program;
+use regex::parse::Assertion;
+
+#[derive(Clone, Debug)]
+struct Threads {
+    set: SparseSet,
+    // Capture slots of each thread, `num_slots` per instruction.
+    slots: Vec<Option<usize>>,
+}
+
+impl Threads {
+    fn new(prog: &Program) -> Self {
+        Self {
+            set: SparseSet::new(prog.insts.len()),
+            slots: vec![None; prog.insts.len() * prog.num_slots],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Frame {
+    Explore(usize),
+    RestoreSlot(usize, Option<usize>),
+}
+
+#[derive(Clone, Debug)]
+pub struct PikeVm {
+    clist: Threads,
+    nlist: Threads,
+    stack: Vec<Frame>,
+    slots: Vec<Option<usize>>,
+}
+
+impl PikeVm {
+    pub fn new(prog: &Program) -> Self {
+        Self {
+            clist: Threads::new(prog),
+            nlist: Threads::new(prog),
+            stack: Vec::new(),
+            slots: vec![None; prog.num_slots],
+        }
+    }
+    
+    /// Returns the capture slots of the leftmost-first match that starts
+    /// at `start`.
+    pub fn captures(&mut self, prog: &Program, haystack: &[u8], start: usize) -> Option<Vec<Option<usize>>> {
+        let mut matched = None;
+        self.clist.set.clear();
+        for slot in self.slots.iter_mut() {
+            *slot = None;
+        }
+        let mut slots = std::mem::take(&mut self.slots);
+        add_thread(&mut self.clist, &mut self.stack, prog, haystack, prog.start_anchored, start, &mut slots);
+        
+        let mut pos = start;
+        while !self.clist.set.is_empty() {
+            self.nlist.set.clear();
+            for i in 0 .. self.clist.set.len() {
+                let pc = self.clist.set.get(i);
+                let thread_slots = &self.clist.slots[pc * prog.num_slots .. (pc + 1) * prog.num_slots];
+                match prog.insts[pc] {
+                    Inst::Match => {
+                        // Lower priority threads cannot produce a preferred match.
+                        matched = Some(thread_slots.to_vec());
+                        break;
+                    }
+                    Inst::Bytes(ref class, next) if pos < haystack.len() && class.contains(haystack[pos]) => {
+                        slots.copy_from_slice(thread_slots);
+                        add_thread(&mut self.nlist, &mut self.stack, prog, haystack, next, pos + 1, &mut slots);
+                    }
+                    _ => {}
+                }
+            }
+            std::mem::swap(&mut self.clist, &mut self.nlist);
+            pos += 1;
+        }
+        self.slots = slots;
+        matched
+    }
+}
+
+// Adds the thread at `pc` and everything reachable from it without
+// consuming input to `list`, in priority order.
+fn add_thread(list: &mut Threads,
+              stack: &mut Vec<Frame>,
+              prog: &Program,
+              haystack: &[u8],
+              pc: usize,
+              pos: usize,
+              slots: &mut [Option<usize>]) {
+    stack.push(Frame::Explore(pc));
+    while let Some(frame) = stack.pop() {
+        let pc = match frame {
+            Frame::Explore(pc) => pc,
+            Frame::RestoreSlot(slot, value) => {
+                slots[slot] = value;
+                continue;
+            }
+        };
+        if !list.set.insert(pc) {
+            continue;
+        }
+        match prog.insts[pc] {
+            Inst::Match | Inst::Bytes(..) => {
+                list.slots[pc * prog.num_slots .. (pc + 1) * prog.num_slots].copy_from_slice(slots);
+            }
+            Inst::Split(first, second) => {
+                stack.push(Frame::Explore(second));
+                stack.push(Frame::Explore(first));
+            }
+            Inst::Save(slot, next) => {
+                stack.push(Frame::RestoreSlot(slot, slots[slot]));
+                slots[slot] = Some(pos);
+                stack.push(Frame::Explore(next));
+            }
+            Inst::Assertion(assertion, next) => {
+                let holds = match assertion {
+                    Assertion::StartText => pos == 0,
+                    Assertion::EndText => pos == haystack.len(),
+                };
+                if holds {
+                    stack.push(Frame::Explore(next));
+                }
+            }
+        }
+    }
+}
@@ -5,7 +5,13 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use core;
 use ByteStr;
+#[cfg(not(feature="no_std"))]
+use ByteString;
+use memchr;
+use utf8;
+use two_way::TwoWay;
 
 // Matcher
 pub trait Matcher {}
@@ -35,144 +41,1926 @@ pub trait FullMatcher: Matcher {
 // ForwardSearcher
 pub trait ForwardSearcher: Matcher {
     fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)>;
+    
+    /// Returns the number of matches in `haystack`, including the ones that
+    /// overlap, as `ByteStr::count_overlapping` does.
+    ///
+    /// The default implementation calls `find` again one byte after the
+    /// start of each match. Matchers that can skip ahead after a match
+    /// override it.
+    fn count_overlapping(&self, haystack: &ByteStr) -> usize {
+        let mut count = 0;
+        let mut start = 0;
+        while let Some((a, _)) = self.find(&haystack[start ..]) {
+            count += 1;
+            start += a + 1;
+            if start > haystack.len() {
+                break;
+            }
+        }
+        count
+    }
+}
+
+// ReverseSearcher
+pub trait ReverseSearcher: Matcher {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)>;
+}
+
+// DoubleEndedSearcher
+/// A searcher that can be used from both ends of a string with consistent
+/// results.
+///
+/// When matches can overlap, searching from the front and from the back may
+/// select different matches (for example, `b"aaa"` in `b"aaaa"`). Double-ended
+/// iterators use `rfind_forward` and `find_reverse` to consume the other end
+/// of the sequence produced by `find` and `rfind` respectively.
+pub trait DoubleEndedSearcher: ForwardSearcher + ReverseSearcher {
+    /// Returns the last match that repeatedly calling `find` (each time on the
+    /// remaining string after the previous match) would report.
+    ///
+    /// The default implementation returns `rfind()`, which is only correct
+    /// for matchers whose matches cannot overlap.
+    #[inline]
+    fn rfind_forward(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        self.rfind(haystack)
+    }
+    
+    /// Returns the last match that repeatedly calling `rfind` (each time on the
+    /// remaining string before the previous match) would report.
+    ///
+    /// The default implementation returns `find()`, which is only correct
+    /// for matchers whose matches cannot overlap.
+    #[inline]
+    fn find_reverse(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        self.find(haystack)
+    }
+}
+
+// SearchStep
+/// Result of a step of a `Searcher` or `RSearcher`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchStep {
+    /// `haystack[a .. b]` is a match.
+    Match(usize, usize),
+    /// `haystack[a .. b]` contains no match.
+    Reject(usize, usize),
+    /// The whole haystack has been visited.
+    Done,
+}
+
+// Searcher
+/// State of a search through a string, from the front.
+///
+/// Unlike `ForwardSearcher::find`, which only sees the rest of the string,
+/// a searcher is created once for the whole haystack, so it can keep
+/// automaton state and the context around the cursor between matches.
+///
+/// The steps returned by `next` cover the haystack without gaps: every
+/// step starts where the previous one ended.
+///
+/// An empty match is never reported twice: after an empty match at `p`,
+/// the next step is `Reject(p, p + 1)`. So an empty needle matches at every
+/// position of the haystack, including its end, like it does with `str`.
+pub trait Searcher<'a> {
+    /// Returns the string being searched.
+    fn haystack(&self) -> &'a ByteStr;
+    
+    /// Returns the position where the next step starts.
+    fn cursor(&self) -> usize;
+    
+    /// Performs the next step of the search.
+    fn next(&mut self) -> SearchStep;
+    
+    /// Returns the next match, skipping rejected parts.
+    #[inline]
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next() {
+                SearchStep::Match(a, b) => return Some((a, b)),
+                SearchStep::Reject(..) => {}
+                SearchStep::Done => return None,
+            }
+        }
+    }
+    
+    /// Returns the next rejected part, skipping matches.
+    #[inline]
+    fn next_reject(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next() {
+                SearchStep::Match(..) => {}
+                SearchStep::Reject(a, b) => return Some((a, b)),
+                SearchStep::Done => return None,
+            }
+        }
+    }
+}
+
+// RSearcher
+/// State of a search through a string, from the back.
+///
+/// The haystack is given when the searcher is created. When a type
+/// implements both `Searcher` and `RSearcher`, both ends consume the same
+/// sequence of matches, so they can be mixed to iterate from both ends.
+/// Empty matches are handled like in `Searcher`, stepping back one byte.
+pub trait RSearcher<'a> {
+    /// Returns the position where the next step from the back ends.
+    fn cursor_back(&self) -> usize;
+    
+    /// Performs the next step of the search from the back.
+    fn next_back(&mut self) -> SearchStep;
+    
+    /// Returns the next match from the back, skipping rejected parts.
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next_back() {
+                SearchStep::Match(a, b) => return Some((a, b)),
+                SearchStep::Reject(..) => {}
+                SearchStep::Done => return None,
+            }
+        }
+    }
+    
+    /// Returns the next rejected part from the back, skipping matches.
+    #[inline]
+    fn next_reject_back(&mut self) -> Option<(usize, usize)> {
+        loop {
+            match self.next_back() {
+                SearchStep::Match(..) => {}
+                SearchStep::Reject(a, b) => return Some((a, b)),
+                SearchStep::Done => return None,
+            }
+        }
+    }
+}
+
+// IntoSearcher
+/// Matchers that can create a `Searcher`, used by the iterators that search
+/// from the front.
+///
+/// Stateless matchers use `FindSearcher`, which calls `find` on the rest of
+/// the string. Matchers that benefit from keeping state between matches
+/// provide their own searcher.
+pub trait IntoSearcher<'a>: Matcher + Sized {
+    type Searcher: Searcher<'a>;
+    
+    #[inline]
+    fn into_searcher(self, haystack: &'a ByteStr) -> Self::Searcher {
+        self.into_searcher_at(haystack, 0)
+    }
+    
+    /// Creates a searcher whose first step starts at `start`. The part of
+    /// `haystack` before `start` is not searched, but it can decide whether
+    /// the matches after it are found, like the start of the string for `^`
+    /// in a `Regex`.
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher;
+}
+
+// IntoRSearcher
+/// Matchers that can create a `RSearcher`, used by the iterators that search
+/// from the back.
+pub trait IntoRSearcher<'a>: Matcher + Sized {
+    type RSearcher: RSearcher<'a>;
+    
+    #[inline]
+    fn into_rsearcher(self, haystack: &'a ByteStr) -> Self::RSearcher {
+        self.into_rsearcher_at(haystack, haystack.len())
+    }
+    
+    /// Creates a searcher whose first step from the back ends at `end`, like
+    /// `IntoSearcher::into_searcher_at`.
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher;
+}
+
+// FindSearcher
+/// `Searcher` that calls `ForwardSearcher::find` on the part of the haystack
+/// that has not been searched.
+///
+/// If the matcher is a `DoubleEndedSearcher`, it is also a `RSearcher` that
+/// returns the same matches in reverse order.
+#[derive(Clone, Debug)]
+pub struct FindSearcher<'a, M> {
+    haystack: &'a ByteStr,
+    matcher: M,
+    start: usize,
+    end: usize,
+    // Whether an empty match at `start` or `end` has been reported.
+    empty_front: bool,
+    empty_back: bool,
+}
+
+impl<'a, M: Matcher> FindSearcher<'a, M> {
+    #[inline]
+    pub fn new(haystack: &'a ByteStr, matcher: M) -> Self {
+        Self::new_at(haystack, matcher, 0)
+    }
+    
+    /// Creates a searcher over the part of `haystack` from `start`.
+    #[inline]
+    pub fn new_at(haystack: &'a ByteStr, matcher: M, start: usize) -> Self {
+        Self {
+            haystack,
+            matcher,
+            start,
+            end: haystack.len(),
+            empty_front: false,
+            empty_back: false,
+        }
+    }
+    
+    // Searches the part that has not been searched yet with `find`,
+    // returning absolute positions. An empty match at the end of that part
+    // is skipped if the searches from the back have already reported it.
+    #[inline]
+    fn find_front<F>(&self, find: F) -> Option<(usize, usize)>
+        where F: FnOnce(&M, &ByteStr) -> Option<(usize, usize)>
+    {
+        let (start, end) = (self.start, self.end);
+        match find(&self.matcher, &self.haystack[start .. end]) {
+            Some((a, b)) if a == b && start + a == end && self.empty_back => None,
+            Some((a, b)) => Some((start + a, start + b)),
+            None => None,
+        }
+    }
+    
+    // Like `find_front`, for the searches from the back.
+    #[inline]
+    fn find_back<F>(&self, find: F) -> Option<(usize, usize)>
+        where F: FnOnce(&M, &ByteStr) -> Option<(usize, usize)>
+    {
+        let (start, end) = (self.start, self.end);
+        match find(&self.matcher, &self.haystack[start .. end]) {
+            Some((a, b)) if a == b && a == 0 && self.empty_front => None,
+            Some((a, b)) => Some((start + a, start + b)),
+            None => None,
+        }
+    }
+}
+
+impl<'a, M: ForwardSearcher> Searcher<'a> for FindSearcher<'a, M> {
+    #[inline]
+    fn haystack(&self) -> &'a ByteStr {
+        self.haystack
+    }
+    
+    #[inline]
+    fn cursor(&self) -> usize {
+        self.start
+    }
+    
+    fn next(&mut self) -> SearchStep {
+        let (start, end) = (self.start, self.end);
+        if self.empty_front {
+            if start == end {
+                return SearchStep::Done;
+            }
+            self.empty_front = false;
+            self.start += 1;
+            return SearchStep::Reject(start, start + 1);
+        }
+        match self.find_front(|m, haystack| m.find(haystack)) {
+            Some((a, b)) if a == start => {
+                self.start = b;
+                self.empty_front = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((a, _)) => {
+                // The match is found again by the next call.
+                self.start = a;
+                SearchStep::Reject(start, a)
+            }
+            None if start != end => {
+                self.start = end;
+                SearchStep::Reject(start, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.empty_front {
+            if self.start == self.end {
+                return None;
+            }
+            self.empty_front = false;
+            self.start += 1;
+        }
+        let (a, b) = self.find_front(|m, haystack| m.find(haystack))?;
+        self.start = b;
+        self.empty_front = a == b;
+        Some((a, b))
+    }
+}
+
+impl<'a, M: DoubleEndedSearcher> RSearcher<'a> for FindSearcher<'a, M> {
+    #[inline]
+    fn cursor_back(&self) -> usize {
+        self.end
+    }
+    
+    fn next_back(&mut self) -> SearchStep {
+        let (start, end) = (self.start, self.end);
+        if self.empty_back {
+            if start == end {
+                return SearchStep::Done;
+            }
+            self.empty_back = false;
+            self.end -= 1;
+            return SearchStep::Reject(end - 1, end);
+        }
+        match self.find_back(|m, haystack| m.rfind_forward(haystack)) {
+            Some((a, b)) if b == end => {
+                self.end = a;
+                self.empty_back = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((_, b)) => {
+                // The match is found again by the next call.
+                self.end = b;
+                SearchStep::Reject(b, end)
+            }
+            None if start != end => {
+                self.end = start;
+                SearchStep::Reject(start, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        if self.empty_back {
+            if self.start == self.end {
+                return None;
+            }
+            self.empty_back = false;
+            self.end -= 1;
+        }
+        let (a, b) = self.find_back(|m, haystack| m.rfind_forward(haystack))?;
+        self.end = a;
+        self.empty_back = a == b;
+        Some((a, b))
+    }
+}
+
+// RFindSearcher
+/// `RSearcher` that calls `ReverseSearcher::rfind` on the part of the
+/// haystack that has not been searched.
+///
+/// If the matcher is a `DoubleEndedSearcher`, it is also a `Searcher` that
+/// returns the same matches in reverse order.
+#[derive(Clone, Debug)]
+pub struct RFindSearcher<'a, M> {
+    haystack: &'a ByteStr,
+    matcher: M,
+    start: usize,
+    end: usize,
+    // Whether an empty match at `start` or `end` has been reported.
+    empty_front: bool,
+    empty_back: bool,
+}
+
+impl<'a, M: Matcher> RFindSearcher<'a, M> {
+    #[inline]
+    pub fn new(haystack: &'a ByteStr, matcher: M) -> Self {
+        Self::new_at(haystack, matcher, haystack.len())
+    }
+    
+    /// Creates a searcher over the part of `haystack` before `end`.
+    #[inline]
+    pub fn new_at(haystack: &'a ByteStr, matcher: M, end: usize) -> Self {
+        Self {
+            haystack,
+            matcher,
+            start: 0,
+            end,
+            empty_front: false,
+            empty_back: false,
+        }
+    }
+    
+    // Searches the part that has not been searched yet with `find`,
+    // returning absolute positions. An empty match at the end of that part
+    // is skipped if the searches from the back have already reported it.
+    #[inline]
+    fn find_front<F>(&self, find: F) -> Option<(usize, usize)>
+        where F: FnOnce(&M, &ByteStr) -> Option<(usize, usize)>
+    {
+        let (start, end) = (self.start, self.end);
+        match find(&self.matcher, &self.haystack[start .. end]) {
+            Some((a, b)) if a == b && start + a == end && self.empty_back => None,
+            Some((a, b)) => Some((start + a, start + b)),
+            None => None,
+        }
+    }
+    
+    // Like `find_front`, for the searches from the back.
+    #[inline]
+    fn find_back<F>(&self, find: F) -> Option<(usize, usize)>
+        where F: FnOnce(&M, &ByteStr) -> Option<(usize, usize)>
+    {
+        let (start, end) = (self.start, self.end);
+        match find(&self.matcher, &self.haystack[start .. end]) {
+            Some((a, b)) if a == b && a == 0 && self.empty_front => None,
+            Some((a, b)) => Some((start + a, start + b)),
+            None => None,
+        }
+    }
+}
+
+impl<'a, M: ReverseSearcher> RSearcher<'a> for RFindSearcher<'a, M> {
+    #[inline]
+    fn cursor_back(&self) -> usize {
+        self.end
+    }
+    
+    fn next_back(&mut self) -> SearchStep {
+        let (start, end) = (self.start, self.end);
+        if self.empty_back {
+            if start == end {
+                return SearchStep::Done;
+            }
+            self.empty_back = false;
+            self.end -= 1;
+            return SearchStep::Reject(end - 1, end);
+        }
+        match self.find_back(|m, haystack| m.rfind(haystack)) {
+            Some((a, b)) if b == end => {
+                self.end = a;
+                self.empty_back = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((_, b)) => {
+                // The match is found again by the next call.
+                self.end = b;
+                SearchStep::Reject(b, end)
+            }
+            None if start != end => {
+                self.end = start;
+                SearchStep::Reject(start, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        if self.empty_back {
+            if self.start == self.end {
+                return None;
+            }
+            self.empty_back = false;
+            self.end -= 1;
+        }
+        let (a, b) = self.find_back(|m, haystack| m.rfind(haystack))?;
+        self.end = a;
+        self.empty_back = a == b;
+        Some((a, b))
+    }
+}
+
+impl<'a, M: DoubleEndedSearcher> Searcher<'a> for RFindSearcher<'a, M> {
+    #[inline]
+    fn haystack(&self) -> &'a ByteStr {
+        self.haystack
+    }
+    
+    #[inline]
+    fn cursor(&self) -> usize {
+        self.start
+    }
+    
+    fn next(&mut self) -> SearchStep {
+        let (start, end) = (self.start, self.end);
+        if self.empty_front {
+            if start == end {
+                return SearchStep::Done;
+            }
+            self.empty_front = false;
+            self.start += 1;
+            return SearchStep::Reject(start, start + 1);
+        }
+        match self.find_front(|m, haystack| m.find_reverse(haystack)) {
+            Some((a, b)) if a == start => {
+                self.start = b;
+                self.empty_front = a == b;
+                SearchStep::Match(a, b)
+            }
+            Some((a, _)) => {
+                // The match is found again by the next call.
+                self.start = a;
+                SearchStep::Reject(start, a)
+            }
+            None if start != end => {
+                self.start = end;
+                SearchStep::Reject(start, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.empty_front {
+            if self.start == self.end {
+                return None;
+            }
+            self.empty_front = false;
+            self.start += 1;
+        }
+        let (a, b) = self.find_front(|m, haystack| m.find_reverse(haystack))?;
+        self.start = b;
+        self.empty_front = a == b;
+        Some((a, b))
+    }
+}
+
+// Implements `IntoSearcher` with `FindSearcher` and, if `rev` is given,
+// `IntoRSearcher` with `RFindSearcher`.
+macro_rules! impl_find_searchers {
+    ([$($gen:tt)*] $ty:ty) => {
+        impl<'a, $($gen)*> IntoSearcher<'a> for $ty {
+            type Searcher = FindSearcher<'a, Self>;
+            
+            #[inline]
+            fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+                FindSearcher::new_at(haystack, self, start)
+            }
+        }
+    };
+    ([$($gen:tt)*] $ty:ty, rev) => {
+        impl_find_searchers!([$($gen)*] $ty);
+        
+        impl<'a, $($gen)*> IntoRSearcher<'a> for $ty {
+            type RSearcher = RFindSearcher<'a, Self>;
+            
+            #[inline]
+            fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+                RFindSearcher::new_at(haystack, self, end)
+            }
+        }
+    };
+}
+
+// Returns the end of the match of `m` at the start of `haystack`.
+#[inline]
+pub fn prefix_len<M: PrefixMatcher + ForwardSearcher>(m: &M, haystack: &ByteStr) -> Option<usize> {
+    if m.is_prefix_of(haystack) {
+        m.find(haystack).map(|(_, end)| end)
+    } else {
+        None
+    }
+}
+
+// Returns the start of the match of `m` at the end of `haystack`.
+#[inline]
+pub fn sufix_start<M: SufixMatcher + ReverseSearcher>(m: &M, haystack: &ByteStr) -> Option<usize> {
+    if m.is_sufix_of(haystack) {
+        m.rfind(haystack).map(|(start, _)| start)
+    } else {
+        None
+    }
+}
+
+// References
+impl<M: Matcher + ?Sized> Matcher for &M {}
+
+impl<M: PrefixMatcher + ?Sized> PrefixMatcher for &M {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        (**self).is_prefix_of(haystack)
+    }
+}
+
+impl<M: SufixMatcher + ?Sized> SufixMatcher for &M {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        (**self).is_sufix_of(haystack)
+    }
+}
+
+impl<M: FullMatcher + ?Sized> FullMatcher for &M {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        (**self).matches(haystack)
+    }
+}
+
+impl<M: ForwardSearcher + ?Sized> ForwardSearcher for &M {
+    #[inline]
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        (**self).find(haystack)
+    }
+    
+    #[inline]
+    fn count_overlapping(&self, haystack: &ByteStr) -> usize {
+        (**self).count_overlapping(haystack)
+    }
+}
+
+impl<M: ReverseSearcher + ?Sized> ReverseSearcher for &M {
+    #[inline]
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        (**self).rfind(haystack)
+    }
+}
+
+impl<M: DoubleEndedSearcher + ?Sized> DoubleEndedSearcher for &M {
+    #[inline]
+    fn rfind_forward(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        (**self).rfind_forward(haystack)
+    }
+    
+    #[inline]
+    fn find_reverse(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        (**self).find_reverse(haystack)
+    }
+}
+
+// StrMatcher
+#[derive(Clone, Debug)]
+pub struct StrMatcher<'a> {
+    needle: &'a ByteStr,
+    two_way: Option<TwoWay>,
+}
+
+impl<'a> StrMatcher<'a> {
+    /// Creates a matcher for `needle`, precomputing the critical factorization
+    /// used by the linear time searches.
+    #[inline]
+    pub fn new(needle: &'a ByteStr) -> Self {
+        let two_way = if needle.is_empty() {
+            None
+        } else {
+            Some(TwoWay::new(needle.as_slice()))
+        };
+        Self { needle, two_way }
+    }
+}
+
+impl<'a> Matcher for StrMatcher<'a> {}
+
+impl<'a, T: ?Sized + AsRef<[u8]>> IntoMatcher for &'a T {
+    type Matcher = StrMatcher<'a>;
+    
+    #[inline]
+    fn into_matcher(self) -> StrMatcher<'a> {
+        StrMatcher::new(ByteStr::from_slice(self.as_ref()))
+    }
+}
+
+impl<'a> PrefixMatcher for StrMatcher<'a> {
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.len() >= self.needle.len() && haystack[0 .. self.needle.len()] == self.needle[..]
+    }
+}
+
+impl<'a> SufixMatcher for StrMatcher<'a> {
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.len() >= self.needle.len() &&
+            haystack[haystack.len() - self.needle.len() .. haystack.len()] == self.needle[..]
+    }
+}
+
+impl<'a> FullMatcher for StrMatcher<'a> {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        haystack == self.needle
+    }
+}
+
+impl<'a> ForwardSearcher for StrMatcher<'a> {
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        match self.two_way {
+            Some(ref two_way) => {
+                two_way.find(haystack.as_slice(), self.needle.as_slice(), 0)
+                    .map(|i| (i, i + self.needle.len()))
+            }
+            None => Some((0, 0)),
+        }
+    }
+    
+    fn count_overlapping(&self, haystack: &ByteStr) -> usize {
+        match self.two_way {
+            Some(ref two_way) => two_way.count_overlapping(haystack.as_slice(), self.needle.as_slice()),
+            None => haystack.len() + 1,
+        }
+    }
+}
+
+impl<'a> ReverseSearcher for StrMatcher<'a> {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        match self.two_way {
+            Some(ref two_way) => {
+                two_way.rfind(haystack.as_slice(), self.needle.as_slice(), haystack.len())
+                    .map(|i| (i, i + self.needle.len()))
+            }
+            None => Some((haystack.len(), haystack.len())),
+        }
+    }
+}
+
+impl<'a> DoubleEndedSearcher for StrMatcher<'a> {
+    #[inline]
+    fn rfind_forward(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        fixed_len_rfind_forward(self, self.needle.len(), haystack)
+    }
+    
+    #[inline]
+    fn find_reverse(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        fixed_len_find_reverse(self, self.needle.len(), haystack)
+    }
+}
+
+// `DoubleEndedSearcher` methods for matchers whose matches all have
+// length `len`.
+fn fixed_len_rfind_forward<M>(m: &M, len: usize, haystack: &ByteStr) -> Option<(usize, usize)>
+    where M: ForwardSearcher + ReverseSearcher
+{
+    if len == 0 {
+        return m.rfind(haystack);
+    }
+    
+    let (last, _) = m.rfind(haystack)?;
+    
+    // Walk back to the first occurrence of the chain of overlapping
+    // occurrences that ends at `last`. A forward search always selects
+    // that one, so everything before it is unaffected.
+    let mut first = last;
+    loop {
+        let start = (first + 1).saturating_sub(len);
+        match m.rfind(&haystack[start .. first + len - 1]) {
+            Some((i, _)) => first = start + i,
+            None => break,
+        }
+    }
+    
+    // Replay the forward search over the chain.
+    let mut pos = first;
+    while let Some((i, _)) = m.find(&haystack[pos + len .. last + len]) {
+        pos += len + i;
+    }
+    Some((pos, pos + len))
+}
+
+fn fixed_len_find_reverse<M>(m: &M, len: usize, haystack: &ByteStr) -> Option<(usize, usize)>
+    where M: ForwardSearcher + ReverseSearcher
+{
+    if len == 0 {
+        return m.find(haystack);
+    }
+    
+    let (first, _) = m.find(haystack)?;
+    
+    // Walk forward to the last occurrence of the chain of overlapping
+    // occurrences that starts at `first`. A reverse search always selects
+    // that one, so everything after it is unaffected.
+    let mut last = first;
+    loop {
+        let end = core::cmp::min(last + 2 * len - 1, haystack.len());
+        match m.find(&haystack[last + 1 .. end]) {
+            Some((i, _)) => last += 1 + i,
+            None => break,
+        }
+    }
+    
+    // Replay the reverse search over the chain.
+    let mut pos = last;
+    while let Some((i, _)) = m.rfind(&haystack[first .. pos]) {
+        pos = first + i;
+    }
+    Some((pos, pos + len))
+}
+
+// StrSearcher
+/// `Searcher` of `StrMatcher` and `AsciiCaseInsensitiveMatcher`, which
+/// works like `FindSearcher`.
+///
+/// Occurrences of the needle can overlap, so the match that the search from
+/// the front selects at the back of the string depends on the whole chain of
+/// overlapping occurrences that ends there. The first step from the back into
+/// a chain finds all its matches, and the next steps take them from there.
+#[derive(Clone, Debug)]
+pub struct StrSearcher<'a, M> {
+    inner: FindSearcher<'a, M>,
+    // Length of the needle.
+    len: usize,
+    // Starts of the matches of the last chain, in increasing order.
+    #[cfg(not(feature="no_std"))]
+    chain: Vec<usize>,
+}
+
+impl<'a, M: DoubleEndedSearcher> StrSearcher<'a, M> {
+    #[inline]
+    fn new(inner: FindSearcher<'a, M>, len: usize) -> Self {
+        Self {
+            inner,
+            len,
+            #[cfg(not(feature="no_std"))]
+            chain: Vec::new(),
+        }
+    }
+    
+    // Returns the last match that the search from the front would report in
+    // the part that has not been searched, for a non-empty needle.
+    #[cfg(not(feature="no_std"))]
+    fn last_match(&mut self) -> Option<(usize, usize)> {
+        let (start, end, len) = (self.inner.start, self.inner.end, self.len);
+        while self.chain.last().map_or(false, |&pos| pos + len > end) {
+            self.chain.pop();
+        }
+        match self.chain.last() {
+            Some(&pos) if pos >= start => return Some((pos, pos + len)),
+            // The search from the front has reached the matches that are
+            // left, which are the same ones.
+            Some(_) => {
+                self.chain.clear();
+                return None;
+            }
+            None => {}
+        }
+        
+        let (m, haystack) = (&self.inner.matcher, self.inner.haystack);
+        let (last, _) = m.rfind(&haystack[start .. end])?;
+        let last = start + last;
+        
+        // Walk back to the first occurrence of the chain, which the search
+        // from the front always selects, and replay that search from there.
+        let mut first = last;
+        loop {
+            let from = core::cmp::max((first + 1).saturating_sub(len), start);
+            match m.rfind(&haystack[from .. first + len - 1]) {
+                Some((i, _)) => first = from + i,
+                None => break,
+            }
+        }
+        let mut pos = first;
+        self.chain.push(pos);
+        while let Some((i, _)) = m.find(&haystack[pos + len .. last + len]) {
+            pos += len + i;
+            self.chain.push(pos);
+        }
+        Some((pos, pos + len))
+    }
+    
+    // Without an allocator, the chain is walked again by every step.
+    #[cfg(feature="no_std")]
+    #[inline]
+    fn last_match(&mut self) -> Option<(usize, usize)> {
+        self.inner.find_back(|m, haystack| m.rfind_forward(haystack))
+    }
+}
+
+impl<'a, M: ForwardSearcher> Searcher<'a> for StrSearcher<'a, M> {
+    #[inline]
+    fn haystack(&self) -> &'a ByteStr {
+        self.inner.haystack
+    }
+    
+    #[inline]
+    fn cursor(&self) -> usize {
+        self.inner.start
+    }
+    
+    #[inline]
+    fn next(&mut self) -> SearchStep {
+        self.inner.next()
+    }
+    
+    #[inline]
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        self.inner.next_match()
+    }
+}
+
+impl<'a, M: DoubleEndedSearcher> RSearcher<'a> for StrSearcher<'a, M> {
+    #[inline]
+    fn cursor_back(&self) -> usize {
+        self.inner.end
+    }
+    
+    fn next_back(&mut self) -> SearchStep {
+        if self.len == 0 {
+            return self.inner.next_back();
+        }
+        let (start, end) = (self.inner.start, self.inner.end);
+        match self.last_match() {
+            Some((a, b)) if b == end => {
+                self.inner.end = a;
+                SearchStep::Match(a, b)
+            }
+            Some((_, b)) => {
+                // The match is found again by the next call.
+                self.inner.end = b;
+                SearchStep::Reject(b, end)
+            }
+            None if start != end => {
+                self.inner.end = start;
+                SearchStep::Reject(start, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        if self.len == 0 {
+            return self.inner.next_match_back();
+        }
+        let (a, b) = self.last_match()?;
+        self.inner.end = a;
+        Some((a, b))
+    }
+}
+
+// StrRSearcher
+/// `RSearcher` of `StrMatcher` and `AsciiCaseInsensitiveMatcher`, which
+/// works like `RFindSearcher`. The steps from the front find the matches of
+/// a chain of overlapping occurrences together, like `StrSearcher` does
+/// from the back.
+#[derive(Clone, Debug)]
+pub struct StrRSearcher<'a, M> {
+    inner: RFindSearcher<'a, M>,
+    // Length of the needle.
+    len: usize,
+    // Starts of the matches of the first chain, in decreasing order.
+    #[cfg(not(feature="no_std"))]
+    chain: Vec<usize>,
+}
+
+impl<'a, M: DoubleEndedSearcher> StrRSearcher<'a, M> {
+    #[inline]
+    fn new(inner: RFindSearcher<'a, M>, len: usize) -> Self {
+        Self {
+            inner,
+            len,
+            #[cfg(not(feature="no_std"))]
+            chain: Vec::new(),
+        }
+    }
+    
+    // Returns the first match that the search from the back would report in
+    // the part that has not been searched, for a non-empty needle.
+    #[cfg(not(feature="no_std"))]
+    fn first_match(&mut self) -> Option<(usize, usize)> {
+        let (start, end, len) = (self.inner.start, self.inner.end, self.len);
+        while self.chain.last().map_or(false, |&pos| pos < start) {
+            self.chain.pop();
+        }
+        match self.chain.last() {
+            Some(&pos) if pos + len <= end => return Some((pos, pos + len)),
+            // The search from the back has reached the matches that are
+            // left, which are the same ones.
+            Some(_) => {
+                self.chain.clear();
+                return None;
+            }
+            None => {}
+        }
+        
+        let (m, haystack) = (&self.inner.matcher, self.inner.haystack);
+        let (first, _) = m.find(&haystack[start .. end])?;
+        let first = start + first;
+        
+        // Walk forward to the last occurrence of the chain, which the search
+        // from the back always selects, and replay that search from there.
+        let mut last = first;
+        loop {
+            let to = core::cmp::min(last + 2 * len - 1, end);
+            match m.find(&haystack[last + 1 .. to]) {
+                Some((i, _)) => last += 1 + i,
+                None => break,
+            }
+        }
+        let mut pos = last;
+        self.chain.push(pos);
+        while let Some((i, _)) = m.rfind(&haystack[first .. pos]) {
+            pos = first + i;
+            self.chain.push(pos);
+        }
+        Some((pos, pos + len))
+    }
+    
+    // Without an allocator, the chain is walked again by every step.
+    #[cfg(feature="no_std")]
+    #[inline]
+    fn first_match(&mut self) -> Option<(usize, usize)> {
+        self.inner.find_front(|m, haystack| m.find_reverse(haystack))
+    }
+}
+
+impl<'a, M: ReverseSearcher> RSearcher<'a> for StrRSearcher<'a, M> {
+    #[inline]
+    fn cursor_back(&self) -> usize {
+        self.inner.end
+    }
+    
+    #[inline]
+    fn next_back(&mut self) -> SearchStep {
+        self.inner.next_back()
+    }
+    
+    #[inline]
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        self.inner.next_match_back()
+    }
+}
+
+impl<'a, M: DoubleEndedSearcher> Searcher<'a> for StrRSearcher<'a, M> {
+    #[inline]
+    fn haystack(&self) -> &'a ByteStr {
+        self.inner.haystack
+    }
+    
+    #[inline]
+    fn cursor(&self) -> usize {
+        self.inner.start
+    }
+    
+    fn next(&mut self) -> SearchStep {
+        if self.len == 0 {
+            return self.inner.next();
+        }
+        let (start, end) = (self.inner.start, self.inner.end);
+        match self.first_match() {
+            Some((a, b)) if a == start => {
+                self.inner.start = b;
+                SearchStep::Match(a, b)
+            }
+            Some((a, _)) => {
+                // The match is found again by the next call.
+                self.inner.start = a;
+                SearchStep::Reject(start, a)
+            }
+            None if start != end => {
+                self.inner.start = end;
+                SearchStep::Reject(start, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+    
+    #[inline]
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.len == 0 {
+            return self.inner.next_match();
+        }
+        let (a, b) = self.first_match()?;
+        self.inner.start = b;
+        Some((a, b))
+    }
+}
+
+// Implements `IntoSearcher` with `StrSearcher` and `IntoRSearcher` with
+// `StrRSearcher`.
+macro_rules! impl_str_searchers {
+    ($ty:ident) => {
+        impl<'m, 'a> IntoSearcher<'a> for $ty<'m> {
+            type Searcher = StrSearcher<'a, Self>;
+            
+            #[inline]
+            fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> Self::Searcher {
+                let len = self.needle.len();
+                StrSearcher::new(FindSearcher::new_at(haystack, self, start), len)
+            }
+        }
+        
+        impl<'m, 'a> IntoRSearcher<'a> for $ty<'m> {
+            type RSearcher = StrRSearcher<'a, Self>;
+            
+            #[inline]
+            fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> Self::RSearcher {
+                let len = self.needle.len();
+                StrRSearcher::new(RFindSearcher::new_at(haystack, self, end), len)
+            }
+        }
+    };
+}
+
+impl_str_searchers!(StrMatcher);
+
+// Finder
+/// Owned matcher for a byte string that searches forwards.
+///
+/// Unlike `StrMatcher`, it owns the needle, so it can be built once and
+/// reused for any number of searches, also from several threads. It is used
+/// by reference: `string.find(&finder)`.
+#[cfg(not(feature="no_std"))]
+#[derive(Clone, Debug)]
+pub struct Finder {
+    needle: ByteString,
+    two_way: Option<TwoWay>,
+}
+
+#[cfg(not(feature="no_std"))]
+impl Finder {
+    /// Creates a finder for `needle`, precomputing the tables used by the
+    /// searches.
+    pub fn new<B: ?Sized + AsRef<[u8]>>(needle: &B) -> Self {
+        let needle = ByteString::from_slice(needle.as_ref());
+        let two_way = if needle.is_empty() {
+            None
+        } else {
+            Some(TwoWay::new(needle.as_slice()))
+        };
+        Self { needle, two_way }
+    }
+    
+    /// Returns the needle of the finder.
+    #[inline]
+    pub fn needle(&self) -> &ByteStr {
+        &self.needle
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl Matcher for Finder {}
+
+#[cfg(not(feature="no_std"))]
+impl<'a> IntoMatcher for &'a Finder {
+    type Matcher = &'a Finder;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a Finder {
+        self
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl PrefixMatcher for Finder {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.as_slice().starts_with(self.needle.as_slice())
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl SufixMatcher for Finder {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.as_slice().ends_with(self.needle.as_slice())
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl FullMatcher for Finder {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        haystack == self.needle.as_byte_str()
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl ForwardSearcher for Finder {
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        match self.two_way {
+            Some(ref two_way) => {
+                two_way.find(haystack.as_slice(), self.needle.as_slice(), 0)
+                    .map(|i| (i, i + self.needle.len()))
+            }
+            None => Some((0, 0)),
+        }
+    }
+    
+    fn count_overlapping(&self, haystack: &ByteStr) -> usize {
+        match self.two_way {
+            Some(ref two_way) => two_way.count_overlapping(haystack.as_slice(), self.needle.as_slice()),
+            None => haystack.len() + 1,
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl<'m, 'a> IntoSearcher<'a> for &'m Finder {
+    type Searcher = FindSearcher<'a, &'m Finder>;
+    
+    #[inline]
+    fn into_searcher_at(self, haystack: &'a ByteStr, start: usize) -> FindSearcher<'a, &'m Finder> {
+        FindSearcher::new_at(haystack, self, start)
+    }
+}
+
+// FinderRev
+/// Owned matcher for a byte string that searches backwards.
+///
+/// It is the reverse counterpart of `Finder`, and uses the same tables:
+/// `string.rfind(&finder)`.
+#[cfg(not(feature="no_std"))]
+#[derive(Clone, Debug)]
+pub struct FinderRev {
+    finder: Finder,
+}
+
+#[cfg(not(feature="no_std"))]
+impl FinderRev {
+    /// Creates a finder for `needle`, precomputing the tables used by the
+    /// searches.
+    #[inline]
+    pub fn new<B: ?Sized + AsRef<[u8]>>(needle: &B) -> Self {
+        Self { finder: Finder::new(needle) }
+    }
+    
+    /// Returns the needle of the finder.
+    #[inline]
+    pub fn needle(&self) -> &ByteStr {
+        self.finder.needle()
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl Matcher for FinderRev {}
+
+#[cfg(not(feature="no_std"))]
+impl<'a> IntoMatcher for &'a FinderRev {
+    type Matcher = &'a FinderRev;
+    
+    #[inline]
+    fn into_matcher(self) -> &'a FinderRev {
+        self
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl PrefixMatcher for FinderRev {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        self.finder.is_prefix_of(haystack)
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl SufixMatcher for FinderRev {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        self.finder.is_sufix_of(haystack)
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl FullMatcher for FinderRev {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        self.finder.matches(haystack)
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl ReverseSearcher for FinderRev {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        let needle = self.finder.needle.as_slice();
+        match self.finder.two_way {
+            Some(ref two_way) => {
+                two_way.rfind(haystack.as_slice(), needle, haystack.len())
+                    .map(|i| (i, i + needle.len()))
+            }
+            None => Some((haystack.len(), haystack.len())),
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl<'m, 'a> IntoRSearcher<'a> for &'m FinderRev {
+    type RSearcher = RFindSearcher<'a, &'m FinderRev>;
+    
+    #[inline]
+    fn into_rsearcher_at(self, haystack: &'a ByteStr, end: usize) -> RFindSearcher<'a, &'m FinderRev> {
+        RFindSearcher::new_at(haystack, self, end)
+    }
+}
+
+// ByteMatcher
+#[derive(Clone, Debug)]
+pub struct ByteMatcher {
+    needle: u8,
+}
+
+impl ByteMatcher {
+    #[inline]
+    pub fn new(needle: u8) -> Self {
+        ByteMatcher { needle }
+    }
+}
+
+impl Matcher for ByteMatcher {}
+
+impl IntoMatcher for u8 {
+    type Matcher = ByteMatcher;
+    
+    fn into_matcher(self) -> ByteMatcher {
+        ByteMatcher::new(self)
+    }
 }
 
-// ReverseSearcher
-pub trait ReverseSearcher: Matcher {
-    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)>;
+impl PrefixMatcher for ByteMatcher {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.first().map_or(false, |&byte| byte == self.needle)
+    }
 }
 
-// DoubleEndedSearcher
-pub trait DoubleEndedSearcher: ForwardSearcher + ReverseSearcher {}
+impl SufixMatcher for ByteMatcher {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.last().map_or(false, |&byte| byte == self.needle)
+    }
+}
 
-// StrMatcher
-pub struct StrMatcher<'a> {
-    needle: &'a ByteStr,
+impl FullMatcher for ByteMatcher {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        haystack == [self.needle]
+    }
 }
 
-impl<'a> StrMatcher<'a> {
+impl ForwardSearcher for ByteMatcher {
     #[inline]
-    pub fn new(needle: &'a ByteStr) -> Self {
-        Self { needle: needle }
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        memchr::memchr(self.needle, haystack.as_slice()).map(|i| (i, i + 1))
+    }
+    
+    #[inline]
+    fn count_overlapping(&self, haystack: &ByteStr) -> usize {
+        haystack.iter().filter(|&&byte| byte == self.needle).count()
     }
 }
 
-impl<'a> Matcher for StrMatcher<'a> {}
+impl ReverseSearcher for ByteMatcher {
+    #[inline]
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        memchr::memrchr(self.needle, haystack.as_slice()).map(|i| (i, i + 1))
+    }
+}
 
-impl<'a, T: AsRef<[u8]>> IntoMatcher for &'a T {
-    type Matcher = StrMatcher<'a>;
+impl DoubleEndedSearcher for ByteMatcher {}
+
+impl_find_searchers!([] ByteMatcher, rev);
+
+// ByteSetMatcher
+/// Matcher that matches any single byte of a set.
+#[derive(Clone, Debug)]
+pub struct ByteSetMatcher {
+    bitmap: [u64; 4],
+    kind: ByteSetKind,
+}
+
+// Selects the search routine: small sets are searched with `memchr`
+// variants, larger sets with the bitmap.
+#[derive(Clone, Copy, Debug)]
+enum ByteSetKind {
+    Empty,
+    One(u8),
+    Two(u8, u8),
+    Three(u8, u8, u8),
+    Many,
+}
+
+impl ByteSetMatcher {
+    /// Creates a matcher that matches any of `bytes`. Repeated bytes are allowed.
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut bitmap = [0u64; 4];
+        let mut distinct = [0u8; 3];
+        let mut num_distinct = 0;
+        for &byte in bytes {
+            let (word, bit) = ((byte >> 6) as usize, byte & 0x3F);
+            if bitmap[word] & (1 << bit) == 0 {
+                bitmap[word] |= 1 << bit;
+                if num_distinct < distinct.len() {
+                    distinct[num_distinct] = byte;
+                }
+                num_distinct += 1;
+            }
+        }
+        
+        let kind = match num_distinct {
+            0 => ByteSetKind::Empty,
+            1 => ByteSetKind::One(distinct[0]),
+            2 => ByteSetKind::Two(distinct[0], distinct[1]),
+            3 => ByteSetKind::Three(distinct[0], distinct[1], distinct[2]),
+            _ => ByteSetKind::Many,
+        };
+        
+        Self { bitmap, kind }
+    }
     
+    /// Returns `true` if `byte` is in the set.
     #[inline]
-    fn into_matcher(self) -> StrMatcher<'a> {
-        StrMatcher::new(ByteStr::from_slice(self.as_ref()))
+    pub fn contains(&self, byte: u8) -> bool {
+        self.bitmap[(byte >> 6) as usize] & (1 << (byte & 0x3F)) != 0
     }
 }
 
-impl<'a> PrefixMatcher for StrMatcher<'a> {
+impl Matcher for ByteSetMatcher {}
+
+impl IntoMatcher for ByteSetMatcher {
+    type Matcher = ByteSetMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> ByteSetMatcher {
+        self
+    }
+}
+
+/// Wrapper to use a collection of bytes (such as `&[u8]` or `[u8; N]`) as a
+/// matcher that matches any single byte of the collection.
+#[derive(Clone, Copy, Debug)]
+pub struct ByteSet<T: AsRef<[u8]>>(pub T);
+
+impl<T: AsRef<[u8]>> IntoMatcher for ByteSet<T> {
+    type Matcher = ByteSetMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> ByteSetMatcher {
+        ByteSetMatcher::new(self.0.as_ref())
+    }
+}
+
+impl PrefixMatcher for ByteSetMatcher {
+    #[inline]
     fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
-        haystack.len() >= self.needle.len() && haystack[0 .. self.needle.len()] == self.needle[..]
+        haystack.first().map_or(false, |&byte| self.contains(byte))
     }
 }
 
-impl<'a> SufixMatcher for StrMatcher<'a> {
+impl SufixMatcher for ByteSetMatcher {
+    #[inline]
     fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
-        haystack.len() >= self.needle.len() &&
-            haystack[haystack.len() - self.needle.len() .. haystack.len()] == self.needle[..]
+        haystack.last().map_or(false, |&byte| self.contains(byte))
     }
 }
 
-impl<'a> FullMatcher for StrMatcher<'a> {
+impl FullMatcher for ByteSetMatcher {
     #[inline]
     fn matches(&self, haystack: &ByteStr) -> bool {
-        haystack == self.needle
+        haystack.len() == 1 && self.contains(haystack[0])
     }
 }
 
-impl<'a> ForwardSearcher for StrMatcher<'a> {
+impl ForwardSearcher for ByteSetMatcher {
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        let haystack = haystack.as_slice();
+        let pos = match self.kind {
+            ByteSetKind::Empty => None,
+            ByteSetKind::One(a) => memchr::memchr(a, haystack),
+            ByteSetKind::Two(a, b) => memchr::memchr2(a, b, haystack),
+            ByteSetKind::Three(a, b, c) => memchr::memchr3(a, b, c, haystack),
+            ByteSetKind::Many => haystack.iter().position(|&byte| self.contains(byte)),
+        };
+        pos.map(|i| (i, i + 1))
+    }
+    
+    #[inline]
+    fn count_overlapping(&self, haystack: &ByteStr) -> usize {
+        haystack.iter().filter(|&&byte| self.contains(byte)).count()
+    }
+}
+
+impl ReverseSearcher for ByteSetMatcher {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        let haystack = haystack.as_slice();
+        let pos = match self.kind {
+            ByteSetKind::Empty => None,
+            ByteSetKind::One(a) => memchr::memrchr(a, haystack),
+            ByteSetKind::Two(a, b) => memchr::memrchr2(a, b, haystack),
+            ByteSetKind::Three(a, b, c) => memchr::memrchr3(a, b, c, haystack),
+            ByteSetKind::Many => haystack.iter().rposition(|&byte| self.contains(byte)),
+        };
+        pos.map(|i| (i, i + 1))
+    }
+}
+
+impl DoubleEndedSearcher for ByteSetMatcher {}
+
+impl_find_searchers!([] ByteSetMatcher, rev);
+
+// PredicateMatcher
+/// Matcher that matches any single byte for which a predicate returns `true`.
+///
+/// Closures must be wrapped in a `PredicateMatcher` to be used as needles,
+/// since references to any `T: AsRef<[u8]>` are already needles.
+#[derive(Clone)]
+pub struct PredicateMatcher<F> {
+    // Matchers are used through shared references, so the predicate
+    // is kept in a `RefCell` to allow calling `FnMut` closures.
+    predicate: core::cell::RefCell<F>,
+}
+
+impl<F: FnMut(u8) -> bool> PredicateMatcher<F> {
+    /// Creates a matcher that matches the bytes for which `predicate` returns `true`.
+    #[inline]
+    pub fn new(predicate: F) -> Self {
+        Self { predicate: core::cell::RefCell::new(predicate) }
+    }
+    
+    #[inline]
+    fn test(&self, byte: u8) -> bool {
+        (*self.predicate.borrow_mut())(byte)
+    }
+}
+
+impl<F> core::fmt::Debug for PredicateMatcher<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.write_str("PredicateMatcher")
+    }
+}
+
+impl<F: FnMut(u8) -> bool> Matcher for PredicateMatcher<F> {}
+
+impl<F: FnMut(u8) -> bool> IntoMatcher for PredicateMatcher<F> {
+    type Matcher = PredicateMatcher<F>;
+    
+    #[inline]
+    fn into_matcher(self) -> PredicateMatcher<F> {
+        self
+    }
+}
+
+impl<F: FnMut(u8) -> bool> PrefixMatcher for PredicateMatcher<F> {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.first().map_or(false, |&byte| self.test(byte))
+    }
+}
+
+impl<F: FnMut(u8) -> bool> SufixMatcher for PredicateMatcher<F> {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.last().map_or(false, |&byte| self.test(byte))
+    }
+}
+
+impl<F: FnMut(u8) -> bool> FullMatcher for PredicateMatcher<F> {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        haystack.len() == 1 && self.test(haystack[0])
+    }
+}
+
+impl<F: FnMut(u8) -> bool> ForwardSearcher for PredicateMatcher<F> {
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        haystack.iter().position(|&byte| self.test(byte)).map(|i| (i, i + 1))
+    }
+}
+
+impl<F: FnMut(u8) -> bool> ReverseSearcher for PredicateMatcher<F> {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        haystack.iter().rposition(|&byte| self.test(byte)).map(|i| (i, i + 1))
+    }
+}
+
+impl<F: FnMut(u8) -> bool> DoubleEndedSearcher for PredicateMatcher<F> {}
+
+impl_find_searchers!([F: FnMut(u8) -> bool] PredicateMatcher<F>, rev);
+
+// CharMatcher
+/// Matcher that matches the UTF-8 encoding of a `char`.
+///
+/// UTF-8 is self-synchronizing, so the encoding of a char never matches in
+/// the middle of the encoding of another char. Invalid bytes around it do not
+/// prevent a match: `'é'` matches in `b"\xFF\xC3\xA9"`.
+#[derive(Clone, Debug)]
+pub struct CharMatcher {
+    bytes: [u8; 4],
+    len: usize,
+}
+
+impl CharMatcher {
+    /// Creates a matcher for `c`.
+    #[inline]
+    pub fn new(c: char) -> Self {
+        let mut bytes = [0; 4];
+        let len = c.encode_utf8(&mut bytes).len();
+        Self { bytes, len }
+    }
+    
+    #[inline]
+    fn needle(&self) -> &[u8] {
+        &self.bytes[.. self.len]
+    }
+}
+
+impl Matcher for CharMatcher {}
+
+impl IntoMatcher for char {
+    type Matcher = CharMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> CharMatcher {
+        CharMatcher::new(self)
+    }
+}
+
+impl PrefixMatcher for CharMatcher {
+    #[inline]
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.as_slice().starts_with(self.needle())
+    }
+}
+
+impl SufixMatcher for CharMatcher {
+    #[inline]
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.as_slice().ends_with(self.needle())
+    }
+}
+
+impl FullMatcher for CharMatcher {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        haystack == self.needle()
+    }
+}
+
+impl ForwardSearcher for CharMatcher {
     fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
-        for (i, window) in haystack.windows(self.needle.len()).enumerate() {
-            if window == self.needle {
-                return Some((i, i + self.needle.len()));
+        let haystack = haystack.as_slice();
+        let needle = self.needle();
+        // Look for the last byte, which is the most varied one.
+        let last = needle[needle.len() - 1];
+        let mut pos = needle.len() - 1;
+        while pos < haystack.len() {
+            let end = pos + memchr::memchr(last, &haystack[pos ..])? + 1;
+            if haystack[.. end].ends_with(needle) {
+                return Some((end - needle.len(), end));
             }
+            pos = end;
         }
         None
     }
 }
 
-impl<'a> ReverseSearcher for StrMatcher<'a> {
+impl ReverseSearcher for CharMatcher {
     fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
-        for (i, window) in haystack.windows(self.needle.len()).enumerate().rev() {
-            if window == self.needle {
-                return Some((i, i + self.needle.len()));
+        let haystack = haystack.as_slice();
+        let needle = self.needle();
+        let last = needle[needle.len() - 1];
+        let mut end = haystack.len();
+        while end >= needle.len() {
+            let pos = memchr::memrchr(last, &haystack[needle.len() - 1 .. end])? + needle.len() - 1;
+            if haystack[.. pos + 1].ends_with(needle) {
+                return Some((pos + 1 - needle.len(), pos + 1));
             }
+            end = pos;
         }
         None
     }
 }
 
-// ByteMatcher
-pub struct ByteMatcher {
-    needle: u8,
+impl DoubleEndedSearcher for CharMatcher {}
+
+impl_find_searchers!([] CharMatcher, rev);
+
+// CharPredicateMatcher
+/// Matcher that matches any `char` for which a predicate returns `true`.
+///
+/// The haystack is decoded as UTF-8 at each position. A match is always a
+/// whole, valid UTF-8 sequence. Invalid bytes, including truncated and
+/// overlong sequences, surrogates and code points above U+10FFFF, are never
+/// passed to the predicate and never match, but they do not hide the valid
+/// chars next to them. Searching forwards and backwards finds the same
+/// matches.
+#[derive(Clone)]
+pub struct CharPredicateMatcher<F> {
+    // Matchers are used through shared references, so the predicate
+    // is kept in a `RefCell` to allow calling `FnMut` closures.
+    predicate: core::cell::RefCell<F>,
 }
 
-impl ByteMatcher {
+impl<F: FnMut(char) -> bool> CharPredicateMatcher<F> {
+    /// Creates a matcher that matches the chars for which `predicate` returns `true`.
     #[inline]
-    pub fn new(needle: u8) -> Self {
-        ByteMatcher { needle: needle }
+    pub fn new(predicate: F) -> Self {
+        Self { predicate: core::cell::RefCell::new(predicate) }
+    }
+    
+    #[inline]
+    fn test(&self, c: char) -> bool {
+        (*self.predicate.borrow_mut())(c)
     }
 }
 
-impl Matcher for ByteMatcher {}
+impl<F> core::fmt::Debug for CharPredicateMatcher<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        f.write_str("CharPredicateMatcher")
+    }
+}
 
-impl IntoMatcher for u8 {
-    type Matcher = ByteMatcher;
+impl<F: FnMut(char) -> bool> Matcher for CharPredicateMatcher<F> {}
+
+impl<F: FnMut(char) -> bool> IntoMatcher for CharPredicateMatcher<F> {
+    type Matcher = CharPredicateMatcher<F>;
     
-    fn into_matcher(self) -> ByteMatcher {
-        ByteMatcher::new(self)
+    #[inline]
+    fn into_matcher(self) -> CharPredicateMatcher<F> {
+        self
     }
 }
 
-impl PrefixMatcher for ByteMatcher {
+impl<F: FnMut(char) -> bool> PrefixMatcher for CharPredicateMatcher<F> {
     #[inline]
     fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
-        haystack.first().map_or(false, |&byte| byte == self.needle)
+        utf8::decode(haystack.as_slice()).map_or(false, |(c, _)| self.test(c))
     }
 }
 
-impl SufixMatcher for ByteMatcher {
+impl<F: FnMut(char) -> bool> SufixMatcher for CharPredicateMatcher<F> {
     #[inline]
     fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
-        haystack.last().map_or(false, |&byte| byte == self.needle)
+        utf8::decode_last(haystack.as_slice()).map_or(false, |(c, _)| self.test(c))
     }
 }
 
-impl FullMatcher for ByteMatcher {
+impl<F: FnMut(char) -> bool> FullMatcher for CharPredicateMatcher<F> {
     #[inline]
     fn matches(&self, haystack: &ByteStr) -> bool {
-        haystack == [self.needle]
+        utf8::decode(haystack.as_slice()).map_or(false, |(c, len)| len == haystack.len() && self.test(c))
     }
 }
 
-impl ForwardSearcher for ByteMatcher {
+impl<F: FnMut(char) -> bool> ForwardSearcher for CharPredicateMatcher<F> {
     fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
-        for (i, &byte) in haystack.iter().enumerate() {
-            if byte == self.needle {
-                return Some((i, i + 1));
+        let haystack = haystack.as_slice();
+        let mut pos = 0;
+        while pos < haystack.len() {
+            match utf8::decode(&haystack[pos ..]) {
+                Some((c, len)) => {
+                    if self.test(c) {
+                        return Some((pos, pos + len));
+                    }
+                    pos += len;
+                }
+                None => pos += 1,
             }
         }
         None
     }
 }
 
-impl ReverseSearcher for ByteMatcher {
+impl<F: FnMut(char) -> bool> ReverseSearcher for CharPredicateMatcher<F> {
     fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
-        for (i, &byte) in haystack.iter().enumerate().rev() {
-            if byte == self.needle {
-                return Some((i, i + 1));
+        let haystack = haystack.as_slice();
+        let mut end = haystack.len();
+        while end > 0 {
+            match utf8::decode_last(&haystack[.. end]) {
+                Some((c, len)) => {
+                    if self.test(c) {
+                        return Some((end - len, end));
+                    }
+                    end -= len;
+                }
+                None => end -= 1,
             }
         }
         None
     }
 }
 
-impl DoubleEndedSearcher for ByteMatcher {}
+impl<F: FnMut(char) -> bool> DoubleEndedSearcher for CharPredicateMatcher<F> {}
+
+impl_find_searchers!([F: FnMut(char) -> bool] CharPredicateMatcher<F>, rev);
+
+// AsciiCaseInsensitive
+/// Wrapper to match a byte or a byte string ignoring ASCII case.
+///
+/// `AsciiCaseInsensitive(b'a')` matches `a` and `A`, and
+/// `AsciiCaseInsensitive(b"content-type")` matches `Content-Type`. Non-ASCII
+/// bytes are compared exactly.
+#[derive(Clone, Copy, Debug)]
+pub struct AsciiCaseInsensitive<T>(pub T);
+
+impl IntoMatcher for AsciiCaseInsensitive<u8> {
+    type Matcher = ByteSetMatcher;
+    
+    #[inline]
+    fn into_matcher(self) -> ByteSetMatcher {
+        ByteSetMatcher::new(&[self.0.to_ascii_lowercase(), self.0.to_ascii_uppercase()])
+    }
+}
+
+/// Matcher that matches a byte string ignoring ASCII case.
+#[derive(Clone, Debug)]
+pub struct AsciiCaseInsensitiveMatcher<'a> {
+    needle: &'a ByteStr,
+    two_way: Option<TwoWay>,
+}
+
+impl<'a> AsciiCaseInsensitiveMatcher<'a> {
+    /// Creates a matcher for `needle`, precomputing the critical factorization
+    /// of its lowercase version.
+    #[inline]
+    pub fn new(needle: &'a ByteStr) -> Self {
+        let two_way = if needle.is_empty() {
+            None
+        } else {
+            Some(TwoWay::new_by(needle.as_slice(), fold_ascii_case))
+        };
+        Self { needle, two_way }
+    }
+}
+
+#[inline]
+fn fold_ascii_case(byte: u8) -> u8 {
+    byte.to_ascii_lowercase()
+}
+
+impl<'a> Matcher for AsciiCaseInsensitiveMatcher<'a> {}
+
+macro_rules! impl_into_ascii_case_insensitive_matcher {
+    ($ty:ty) => {
+        impl<'a> IntoMatcher for AsciiCaseInsensitive<&'a $ty> {
+            type Matcher = AsciiCaseInsensitiveMatcher<'a>;
+            
+            #[inline]
+            fn into_matcher(self) -> AsciiCaseInsensitiveMatcher<'a> {
+                AsciiCaseInsensitiveMatcher::new(ByteStr::from_slice(self.0.as_ref()))
+            }
+        }
+    }
+}
+
+impl_into_ascii_case_insensitive_matcher!([u8]);
+impl_into_ascii_case_insensitive_matcher!(ByteStr);
+impl_into_ascii_case_insensitive_matcher!(str);
+#[cfg(not(feature="no_std"))]
+impl_into_ascii_case_insensitive_matcher!(ByteString);
+#[cfg(not(feature="no_std"))]
+impl_into_ascii_case_insensitive_matcher!(Vec<u8>);
+#[cfg(not(feature="no_std"))]
+impl_into_ascii_case_insensitive_matcher!(String);
+
+impl<'a, const N: usize> IntoMatcher for AsciiCaseInsensitive<&'a [u8; N]> {
+    type Matcher = AsciiCaseInsensitiveMatcher<'a>;
+    
+    #[inline]
+    fn into_matcher(self) -> AsciiCaseInsensitiveMatcher<'a> {
+        AsciiCaseInsensitiveMatcher::new(ByteStr::from_slice(self.0))
+    }
+}
+
+impl<'a> PrefixMatcher for AsciiCaseInsensitiveMatcher<'a> {
+    fn is_prefix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.len() >= self.needle.len() &&
+            haystack[0 .. self.needle.len()].eq_ignore_ascii_case(self.needle.as_slice())
+    }
+}
+
+impl<'a> SufixMatcher for AsciiCaseInsensitiveMatcher<'a> {
+    fn is_sufix_of(&self, haystack: &ByteStr) -> bool {
+        haystack.len() >= self.needle.len() &&
+            haystack[haystack.len() - self.needle.len() ..].eq_ignore_ascii_case(self.needle.as_slice())
+    }
+}
+
+impl<'a> FullMatcher for AsciiCaseInsensitiveMatcher<'a> {
+    #[inline]
+    fn matches(&self, haystack: &ByteStr) -> bool {
+        haystack.eq_ignore_ascii_case(self.needle)
+    }
+}
+
+impl<'a> ForwardSearcher for AsciiCaseInsensitiveMatcher<'a> {
+    fn find(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        match self.two_way {
+            Some(ref two_way) => {
+                two_way.find_by(haystack.as_slice(), self.needle.as_slice(), 0, fold_ascii_case)
+                    .map(|i| (i, i + self.needle.len()))
+            }
+            None => Some((0, 0)),
+        }
+    }
+    
+    fn count_overlapping(&self, haystack: &ByteStr) -> usize {
+        match self.two_way {
+            Some(ref two_way) => two_way.count_overlapping_by(haystack.as_slice(), self.needle.as_slice(), fold_ascii_case),
+            None => haystack.len() + 1,
+        }
+    }
+}
+
+impl<'a> ReverseSearcher for AsciiCaseInsensitiveMatcher<'a> {
+    fn rfind(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        match self.two_way {
+            Some(ref two_way) => {
+                two_way.rfind_by(haystack.as_slice(), self.needle.as_slice(), haystack.len(), fold_ascii_case)
+                    .map(|i| (i, i + self.needle.len()))
+            }
+            None => Some((haystack.len(), haystack.len())),
+        }
+    }
+}
+
+impl<'a> DoubleEndedSearcher for AsciiCaseInsensitiveMatcher<'a> {
+    #[inline]
+    fn rfind_forward(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        fixed_len_rfind_forward(self, self.needle.len(), haystack)
+    }
+    
+    #[inline]
+    fn find_reverse(&self, haystack: &ByteStr) -> Option<(usize, usize)> {
+        fixed_len_find_reverse(self, self.needle.len(), haystack)
+    }
+}
+
+impl_str_searchers!(AsciiCaseInsensitiveMatcher);
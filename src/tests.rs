@@ -7,8 +7,76 @@
 
 use core::iter::FromIterator;
 use ByteStr;
+use ByteSet;
+use AsciiCaseInsensitive;
 #[cfg(not(feature="no_std"))]
 use ByteString;
+#[cfg(not(feature="no_std"))]
+use AhoCorasickMatcher;
+#[cfg(not(feature="no_std"))]
+use MatchKind;
+#[cfg(not(feature="no_std"))]
+use regex::Regex;
+#[cfg(not(feature="no_std"))]
+use GlobMatcher;
+#[cfg(not(feature="no_std"))]
+use GlobMode;
+#[cfg(not(feature="no_std"))]
+use LineBreaks;
+
+// Returns a xorshift generator of numbers below the given bound, so that
+// the random tests are reproducible.
+#[cfg(not(feature="no_std"))]
+fn xorshift(mut seed: u32) -> impl FnMut(u32) -> u32 {
+    move |n| {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed % n
+    }
+}
+
+// Concatenates fewer than `max_pieces` random `pieces`.
+#[cfg(not(feature="no_std"))]
+fn random_bytes<R: FnMut(u32) -> u32>(random: &mut R, max_pieces: u32, pieces: &[&[u8]]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for _ in 0 .. random(max_pieces) {
+        bytes.extend_from_slice(pieces[random(pieces.len() as u32) as usize]);
+    }
+    bytes
+}
+
+// Same as `random_bytes`, for pieces that are valid UTF-8.
+#[cfg(not(feature="no_std"))]
+fn random_string<R: FnMut(u32) -> u32>(random: &mut R, max_pieces: u32, pieces: &[&str]) -> String {
+    let mut string = String::new();
+    for _ in 0 .. random(max_pieces) {
+        string.push_str(pieces[random(pieces.len() as u32) as usize]);
+    }
+    string
+}
+
+#[cfg(not(feature="no_std"))]
+fn reversed<T>(mut items: Vec<T>) -> Vec<T> {
+    items.reverse();
+    items
+}
+
+// Takes items from both ends in a random order, returning them in the
+// order of the front.
+#[cfg(not(feature="no_std"))]
+fn mixed<I: DoubleEndedIterator, R: FnMut(u32) -> u32>(mut iter: I, random: &mut R) -> Vec<I::Item> {
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    loop {
+        let item = if random(2) == 0 { iter.next().map(|item| front.push(item)) } else { iter.next_back().map(|item| back.push(item)) };
+        if item.is_none() {
+            break;
+        }
+    }
+    front.extend(reversed(back));
+    front
+}
 
 #[test]
 fn find_test_1() {
@@ -167,3 +235,1733 @@ fn match_test_4() {
     assert_eq!(Vec::from_iter(string.match_indices(b'X').rev()), expected);
     assert_eq!(Vec::from_iter(string.match_indices_mut(b'X').rev().map(|(i, s)| (i, s as _))), expected);
 }
+
+#[test]
+fn find_test_3() {
+    let string = ByteStr::from_slice(b"GCATCGCAGAGAGTATACAGTACG");
+    assert_eq!(string.find(b"GCAGAGAG"), Some(5));
+    assert_eq!(string.rfind(b"GCAGAGAG"), Some(13));
+    assert_eq!(string.find(b"GCAGAGAT"), None);
+    assert_eq!(string.rfind(b"GCAGAGAT"), None);
+}
+
+#[test]
+fn find_test_4() {
+    // Compare against a naive search for every needle and haystack over a
+    // two-byte alphabet, covering periodic and non-periodic needles.
+    fn naive_find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).position(|w| w == needle)
+    }
+    
+    fn naive_rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).rposition(|w| w == needle).map(|i| i + needle.len())
+    }
+    
+    fn gen(len: usize, bits: usize) -> [u8; 16] {
+        let mut buf = [0; 16];
+        for (i, byte) in buf[.. len].iter_mut().enumerate() {
+            *byte = if bits & (1 << i) != 0 { b'b' } else { b'a' };
+        }
+        buf
+    }
+    
+    for haystack_len in 0 .. 10 {
+        for haystack_bits in 0 .. 1 << haystack_len {
+            let haystack_buf = gen(haystack_len, haystack_bits);
+            let haystack = &haystack_buf[.. haystack_len];
+            for needle_len in 1 .. 6 {
+                for needle_bits in 0 .. 1 << needle_len {
+                    let needle_buf = gen(needle_len, needle_bits);
+                    let needle = &needle_buf[.. needle_len];
+                    let string = ByteStr::from_slice(haystack);
+                    assert_eq!(string.find(&needle), naive_find(haystack, needle));
+                    assert_eq!(string.rfind(&needle), naive_rfind(haystack, needle));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn split_test_7() {
+    let mut string = ByteString::from_slice(b"aaaabaaaabaaaa");
+    let forwards = [
+        ByteStr::from_slice(b"a"),
+        ByteStr::from_slice(b"ab"),
+        ByteStr::from_slice(b"ab"),
+        ByteStr::empty(),
+    ];
+    let backwards = [
+        ByteStr::from_slice(b"a"),
+        ByteStr::from_slice(b"ba"),
+        ByteStr::from_slice(b"ba"),
+        ByteStr::empty(),
+    ];
+    
+    assert_eq!(Vec::from_iter(string.split(b"aaa").rev()), forwards);
+    assert_eq!(Vec::from_iter(string.split_mut(b"aaa").rev()), forwards);
+    assert_eq!(Vec::from_iter(string.rsplit(b"aaa").rev()), backwards);
+    assert_eq!(Vec::from_iter(string.rsplit_mut(b"aaa").rev()), backwards);
+    
+    let mut iter = string.split(b"aaa");
+    assert_eq!(iter.next(), Some(ByteStr::empty()));
+    assert_eq!(iter.next_back(), Some(ByteStr::from_slice(b"a")));
+    assert_eq!(iter.next_back(), Some(ByteStr::from_slice(b"ab")));
+    assert_eq!(iter.next(), Some(ByteStr::from_slice(b"ab")));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn match_test_5() {
+    // Consuming from both ends must give the same matches as consuming from
+    // one end, for every haystack over a two-byte alphabet.
+    let needles: [&[u8]; 5] = [b"aa", b"aaa", b"aba", b"abaab", b"abab"];
+    for haystack_len in 0 .. 11 {
+        for bits in 0 .. 1 << haystack_len {
+            let mut buf = [0; 16];
+            for (i, byte) in buf[.. haystack_len].iter_mut().enumerate() {
+                *byte = if bits & (1 << i) != 0 { b'b' } else { b'a' };
+            }
+            let string = ByteStr::from_slice(&buf[.. haystack_len]);
+            for needle in needles.iter() {
+                let forwards = string.match_indices(needle);
+                let backwards = string.rmatch_indices(needle);
+                assert!(forwards.clone().eq(string.match_indices(needle).rev().collect::<Vec<_>>().into_iter().rev()));
+                assert!(backwards.clone().eq(string.rmatch_indices(needle).rev().collect::<Vec<_>>().into_iter().rev()));
+                
+                // Alternate between both ends.
+                let mut iter = string.match_indices(needle);
+                let mut front = Vec::new();
+                let mut back = Vec::new();
+                while let Some(m) = iter.next() {
+                    front.push(m);
+                    match iter.next_back() {
+                        Some(m) => back.push(m),
+                        None => break,
+                    }
+                }
+                front.extend(back.into_iter().rev());
+                assert!(forwards.eq(front.into_iter()));
+                
+                let split = string.split(needle);
+                let rsplit = string.rsplit(needle);
+                assert!(split.clone().eq(string.split(needle).rev().collect::<Vec<_>>().into_iter().rev()));
+                assert!(rsplit.clone().eq(string.rsplit(needle).rev().collect::<Vec<_>>().into_iter().rev()));
+            }
+        }
+    }
+}
+
+#[test]
+fn find_test_5() {
+    // Exercise the vectorized and word-at-a-time paths with every length
+    // and match position up to a few vector widths.
+    let mut buf = [b'.'; 100];
+    for len in 0 .. buf.len() {
+        for pos in 0 .. len {
+            buf[pos] = b'X';
+            let string = ByteStr::from_slice(&buf[.. len]);
+            assert_eq!(string.find(b'X'), Some(pos));
+            assert_eq!(string.rfind(b'X'), Some(pos + 1));
+            assert_eq!(::memchr::swar::find(::memchr::One(b'X'), &buf[.. len]), Some(pos));
+            assert_eq!(::memchr::swar::rfind(::memchr::One(b'X'), &buf[.. len]), Some(pos));
+            buf[pos] = b'.';
+        }
+        let string = ByteStr::from_slice(&buf[.. len]);
+        assert_eq!(string.find(b'X'), None);
+        assert_eq!(string.rfind(b'X'), None);
+        assert_eq!(::memchr::swar::find(::memchr::One(b'X'), &buf[.. len]), None);
+        assert_eq!(::memchr::swar::rfind(::memchr::One(b'X'), &buf[.. len]), None);
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn split_test_8() {
+    let mut string = ByteString::from_slice(b"a b\tc,,d");
+    let expected = [
+        ByteStr::from_slice(b"a"),
+        ByteStr::from_slice(b"b"),
+        ByteStr::from_slice(b"c"),
+        ByteStr::empty(),
+        ByteStr::from_slice(b"d"),
+    ];
+    
+    assert_eq!(Vec::from_iter(string.split(ByteSet(b" \t,"))), expected);
+    assert_eq!(Vec::from_iter(string.split_mut(ByteSet([b' ', b'\t', b',']))), expected);
+    assert_eq!(Vec::from_iter(string.rsplit(ByteSet(b" \t,")).rev()), expected);
+    assert!(string.starts_with(ByteSet(b"abc")));
+    assert!(!string.ends_with(ByteSet(b"abc")));
+}
+
+#[test]
+fn find_test_6() {
+    // Sets of one, two, three and more bytes use different search routines.
+    let sets: [&[u8]; 5] = [b"", b"X", b"XY", b"XYZ", b"WXYZ"];
+    let mut buf = [b'.'; 70];
+    for len in 0 .. buf.len() {
+        for pos in 0 .. len {
+            for set in sets.iter() {
+                for &byte in set.iter() {
+                    buf[pos] = byte;
+                    let string = ByteStr::from_slice(&buf[.. len]);
+                    assert_eq!(string.find(ByteSet(set)), Some(pos));
+                    assert_eq!(string.rfind(ByteSet(set)), Some(pos + 1));
+                    buf[pos] = b'.';
+                }
+                let string = ByteStr::from_slice(&buf[.. len]);
+                assert_eq!(string.find(ByteSet(set)), None);
+                assert_eq!(string.rfind(ByteSet(set)), None);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn split_test_9() {
+    use PredicateMatcher;
+    
+    let mut string = ByteString::from_slice(b"aa bb\tcc\n\ndd");
+    let expected = [
+        ByteStr::from_slice(b"aa"),
+        ByteStr::from_slice(b"bb"),
+        ByteStr::from_slice(b"cc"),
+        ByteStr::empty(),
+        ByteStr::from_slice(b"dd"),
+    ];
+    
+    assert_eq!(Vec::from_iter(string.split(PredicateMatcher::new(|b: u8| b.is_ascii_whitespace()))), expected);
+    assert_eq!(Vec::from_iter(string.split_mut(PredicateMatcher::new(|b: u8| b.is_ascii_whitespace()))), expected);
+    assert_eq!(Vec::from_iter(string.split(PredicateMatcher::new(|b: u8| b.is_ascii_whitespace())).rev().rev()), expected);
+    
+    let mut calls = 0;
+    assert_eq!(string.find(PredicateMatcher::new(|b: u8| { calls += 1; b == b'\t' })), Some(5));
+    assert_eq!(calls, 6);
+    
+    assert!(string.starts_with(PredicateMatcher::new(|b: u8| b == b'a')));
+    assert!(string.ends_with(PredicateMatcher::new(|b: u8| b == b'd')));
+    assert_eq!(string.rfind(PredicateMatcher::new(|b: u8| b.is_ascii_whitespace())), Some(10));
+    
+    // Any reference to a type that implements `AsRef<[u8]>` is a needle.
+    struct Buffer(Vec<u8>);
+    impl AsRef<[u8]> for Buffer {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+    let needle = Buffer(b"\n\n".to_vec());
+    assert_eq!(string.find(&needle), Some(8));
+    assert_eq!(Vec::from_iter(string.split(&needle)), [&b"aa bb\tcc"[..], b"dd"]);
+    assert!(string.contains(&&b"bb"[..]));
+    assert!(string.starts_with(&"aa"));
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn aho_corasick_test_1() {
+    let string = ByteStr::from_slice(b"xabcdabx");
+    
+    let first = AhoCorasickMatcher::new(&[&b"ab"[..], b"abcd", b"bc"]);
+    assert_eq!(first.pattern_count(), 3);
+    assert_eq!(first.find_pattern(string), Some((0, 1, 3)));
+    assert_eq!(Vec::from_iter(string.pattern_matches(&first)), [(0, 1, 3), (0, 5, 7)]);
+    
+    let longest = AhoCorasickMatcher::with_match_kind(MatchKind::LeftmostLongest, &[&b"ab"[..], b"abcd", b"bc"]);
+    assert_eq!(longest.find_pattern(string), Some((1, 1, 5)));
+    assert_eq!(Vec::from_iter(string.pattern_matches(&longest)), [(1, 1, 5), (0, 5, 7)]);
+    
+    assert_eq!(string.find(&first), Some(1));
+    assert_eq!(Vec::from_iter(string.split(&longest)), [&b"x"[..], b"", b"x"]);
+    assert_eq!(Vec::from_iter(string.match_indices(&first).map(|(i, m)| (i, m.len()))), [(1, 2), (5, 2)]);
+    
+    let empty = AhoCorasickMatcher::new(&[&b""[..], b"a"]);
+    assert_eq!(Vec::from_iter(ByteStr::from_slice(b"ab").pattern_matches(&empty)), [(0, 0, 0), (0, 1, 1), (0, 2, 2)]);
+    
+    let none = AhoCorasickMatcher::new(Vec::<Vec<u8>>::new());
+    assert_eq!(none.find_pattern(string), None);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn aho_corasick_test_2() {
+    // Compare with a naive search on pseudo-random inputs.
+    fn naive(patterns: &[Vec<u8>], kind: MatchKind, haystack: &[u8]) -> Option<(usize, usize, usize)> {
+        for start in 0 ..= haystack.len() {
+            let mut best: Option<(usize, usize, usize)> = None;
+            for (i, pattern) in patterns.iter().enumerate() {
+                if haystack[start ..].starts_with(pattern) {
+                    let better = match best {
+                        None => true,
+                        Some((_, _, end)) => kind == MatchKind::LeftmostLongest && start + pattern.len() > end,
+                    };
+                    if better {
+                        best = Some((i, start, start + pattern.len()));
+                    }
+                }
+            }
+            if best.is_some() {
+                return best;
+            }
+        }
+        None
+    }
+    
+    let mut random = xorshift(0x2545_f491);
+    for _ in 0 .. 2000 {
+        let patterns = Vec::from_iter((0 .. 1 + random(5)).map(|_| {
+            Vec::from_iter((0 .. random(5)).map(|_| b'a' + random(3) as u8))
+        }));
+        let haystack = Vec::from_iter((0 .. random(12)).map(|_| b'a' + random(3) as u8));
+        for &kind in [MatchKind::LeftmostFirst, MatchKind::LeftmostLongest].iter() {
+            let matcher = AhoCorasickMatcher::with_match_kind(kind, &patterns);
+            assert_eq!(matcher.find_pattern(ByteStr::from_slice(&haystack)), naive(&patterns, kind, &haystack),
+                       "patterns: {:?}, haystack: {:?}", patterns, haystack);
+            
+            // The searcher gives the same matches as searching what remains.
+            let mut expected = Vec::new();
+            let mut pos = 0;
+            while let Some((pattern, a, b)) = naive(&patterns, kind, &haystack[pos ..]) {
+                expected.push((pattern, pos + a, pos + b));
+                pos += if a == b { b + 1 } else { b };
+                if pos > haystack.len() {
+                    break;
+                }
+            }
+            assert_eq!(Vec::from_iter(ByteStr::from_slice(&haystack).pattern_matches(&matcher)), expected,
+                       "patterns: {:?}, haystack: {:?}", patterns, haystack);
+        }
+    }
+}
+
+#[test]
+fn ascii_case_test_1() {
+    let string = ByteStr::from_slice(b"Content-Type: text/HTML; charset=utf-8");
+    
+    assert!(string.starts_with(AsciiCaseInsensitive(b"content-type:")));
+    assert!(string.ends_with(AsciiCaseInsensitive(b"UTF-8")));
+    assert!(!string.starts_with(AsciiCaseInsensitive(b"content-length")));
+    assert_eq!(string.find(AsciiCaseInsensitive(b"html")), Some(19));
+    assert_eq!(string.rfind(AsciiCaseInsensitive(b"T")), Some(35));
+    assert_eq!(string.find(AsciiCaseInsensitive(b't')), Some(3));
+    assert_eq!(string.rfind(AsciiCaseInsensitive(b'c')), Some(26));
+    assert_eq!(string.find(AsciiCaseInsensitive("")), Some(0));
+    
+    let mut parts = string.split(AsciiCaseInsensitive(b"; CHARSET="));
+    assert_eq!(parts.next().unwrap(), &b"Content-Type: text/HTML"[..]);
+    assert_eq!(parts.next().unwrap(), &b"utf-8"[..]);
+    assert_eq!(parts.next(), None);
+    
+    assert!(string.eq_ignore_ascii_case(b"CONTENT-TYPE: TEXT/HTML; CHARSET=UTF-8"));
+    assert!(!string.eq_ignore_ascii_case(b"Content-Type"));
+    assert!(ByteStr::from_slice(b"\xC3\xA9").eq_ignore_ascii_case(b"\xC3\xA9"));
+    assert!(!ByteStr::from_slice(b"\xC3\xA9").eq_ignore_ascii_case(b"\xC3\x89"));
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn ascii_case_test_2() {
+    let mut string = ByteString::from_slice(b"Hello, W\xF6rld!");
+    assert_eq!(string.to_ascii_uppercase().as_byte_str(), b"HELLO, W\xF6RLD!");
+    assert_eq!(string.to_ascii_lowercase().as_byte_str(), b"hello, w\xF6rld!");
+    string.make_ascii_uppercase();
+    assert_eq!(string.as_byte_str(), b"HELLO, W\xF6RLD!");
+    string[.. 5].make_ascii_lowercase();
+    assert_eq!(string.as_byte_str(), b"hello, W\xF6RLD!");
+    
+    // Compare with a naive search on the lowercase strings.
+    let haystack = ByteStr::from_slice(b"aAbBaBAbaABabAAbaBBab");
+    for len in 0 .. 5 {
+        for start in 0 .. haystack.len() - len {
+            let needle = &haystack[start .. start + len];
+            let lower_needle = needle.to_ascii_lowercase();
+            let lower = haystack.to_ascii_lowercase();
+            
+            assert_eq!(haystack.find(AsciiCaseInsensitive(needle)), lower.find(&lower_needle));
+            assert_eq!(haystack.rfind(AsciiCaseInsensitive(needle)), lower.rfind(&lower_needle));
+            if len == 0 {
+                continue;
+            }
+            assert_eq!(Vec::from_iter(haystack.match_indices(AsciiCaseInsensitive(needle)).map(|(i, _)| i)),
+                       Vec::from_iter(lower.match_indices(&lower_needle).map(|(i, _)| i)));
+            assert_eq!(Vec::from_iter(haystack.match_indices(AsciiCaseInsensitive(needle)).rev().map(|(i, _)| i)),
+                       Vec::from_iter(lower.match_indices(&lower_needle).rev().map(|(i, _)| i)));
+            assert_eq!(Vec::from_iter(haystack.rmatch_indices(AsciiCaseInsensitive(needle)).map(|(i, _)| i)),
+                       Vec::from_iter(lower.rmatch_indices(&lower_needle).map(|(i, _)| i)));
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn regex_test_1() {
+    use ForwardSearcher;
+    use ReverseSearcher;
+    
+    let string = ByteStr::from_slice(b"key1 = 12, key2=345 ,key3= 6");
+    let regex = Regex::new(r"\s*,\s*").unwrap();
+    assert_eq!(Vec::from_iter(string.split(&regex)), [&b"key1 = 12"[..], b"key2=345", b"key3= 6"]);
+    assert_eq!(Vec::from_iter(string.rsplit(&regex)), [&b"key3= 6"[..], b"key2=345", b"key1 = 12"]);
+    
+    let regex = Regex::new(r"[0-9]+").unwrap();
+    assert_eq!(string.find(&regex), Some(3));
+    assert_eq!(string.rfind(&regex), Some(28));
+    assert_eq!(Vec::from_iter(string.matches(&regex)), [&b"1"[..], b"12", b"2", b"345", b"3", b"6"]);
+    assert_eq!(Vec::from_iter(string.match_indices(&regex).map(|(i, _)| i)), [3, 7, 14, 16, 24, 27]);
+    assert_eq!(Vec::from_iter(string.rmatches(&regex)), [&b"6"[..], b"3", b"345", b"2", b"12", b"1"]);
+    
+    let regex = Regex::new(r"(?i)KEY\d").unwrap();
+    assert!(string.starts_with(&regex));
+    assert!(!string.ends_with(&regex));
+    assert!(string.ends_with(&Regex::new(r"(?i)KEY\d=\s*\d").unwrap()));
+    assert!(!string.contains(&Regex::new(r"^=").unwrap()));
+    
+    // Leftmost-first semantics.
+    let string = ByteStr::from_slice(b"samwise");
+    assert_eq!(Regex::new("sam|samwise").unwrap().find(string), Some((0, 3)));
+    assert_eq!(Regex::new("samwise|sam").unwrap().find(string), Some((0, 7)));
+    assert_eq!(Regex::new("s.*?").unwrap().find(string), Some((0, 1)));
+    assert_eq!(Regex::new("s.*").unwrap().find(string), Some((0, 7)));
+    assert_eq!(Regex::new("s.*?s").unwrap().find(string), Some((0, 6)));
+    
+    // Reverse searches report the longest match that ends last.
+    assert_eq!(Regex::new("s.*?").unwrap().rfind(string), Some((0, 7)));
+    assert_eq!(Regex::new("s[a-z]").unwrap().rfind(string), Some((5, 7)));
+    assert_eq!(Regex::new("w|$").unwrap().rfind(string), Some((7, 7)));
+    
+    // Non-UTF-8 bytes.
+    let string = ByteStr::from_slice(b"ab\xFF\xFEcd\xC3\xA9");
+    let regex = Regex::new(r"[\x80-\xFF]+").unwrap();
+    assert_eq!(Vec::from_iter(string.split(&regex)), [&b"ab"[..], b"cd", b""]);
+    assert_eq!(Regex::new("é").unwrap().find(string), Some((6, 8)));
+    assert_eq!(Regex::new(r"^.{4}").unwrap().find(string), Some((0, 4)));
+    
+    // Iterators keep the whole string as context for the anchors.
+    let string = ByteStr::from_slice(b"aaa");
+    let start = Regex::new("^a").unwrap();
+    let end = Regex::new("a$").unwrap();
+    assert_eq!(Vec::from_iter(string.split(&start)), [&b""[..], b"aa"]);
+    assert_eq!(Vec::from_iter(string.split(&start).rev()), [&b"aa"[..], b""]);
+    assert_eq!(Vec::from_iter(string.split(&end)), [&b"aa"[..], b""]);
+    assert_eq!(Vec::from_iter(string.split(&end).rev()), [&b""[..], b"aa"]);
+    assert_eq!(Vec::from_iter(string.rsplit(&end)), [&b""[..], b"aa"]);
+    assert_eq!(Vec::from_iter(string.rsplit(&start)), [&b"aa"[..], b""]);
+    assert_eq!(Vec::from_iter(string.match_indices(&start).map(|(i, _)| i)), [0]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices(&end).map(|(i, _)| i)), [2]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices(&Regex::new("^|a*").unwrap()).map(|(i, _)| i)), [0, 0]);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn regex_test_3() {
+    use ReverseSearcher;
+    
+    // The forward and backward steps of a split with a regex must give the
+    // pieces found from the front, in whichever order they are taken.
+    let patterns = ["^a", "a$", "^|b", "a*", "b|$", "^a|b*$", "ab?", "(?:^b)*a", "b*a?"];
+    for pattern in patterns.iter() {
+        let regex = Regex::new(pattern).unwrap();
+        for len in 0 .. 7 {
+            for bits in 0u32 .. 1 << len {
+                let string = Vec::from_iter((0 .. len).map(|i| if bits >> i & 1 != 0 { b'b' } else { b'a' }));
+                let string = ByteStr::from_slice(&string);
+                // Without `$`, the matches from the back are the ones that
+                // `rfind` finds in the part that has not been searched yet.
+                if !pattern.contains('$') {
+                    let mut expected = Vec::new();
+                    let mut end = string.len();
+                    while let Some((a, b)) = regex.rfind(&string[.. end]) {
+                        expected.push((a, b));
+                        match (a == b, b) {
+                            (true, 0) => break,
+                            (true, b) => end = b - 1,
+                            (false, _) => end = a,
+                        }
+                    }
+                    let found = Vec::from_iter(string.rmatch_indices(&regex).map(|(i, s)| (i, i + s.len())));
+                    assert_eq!(found, expected, "{} {:?}", pattern, string);
+                }
+                
+                let pieces = Vec::from_iter(string.split(&regex));
+                assert_eq!(reversed(Vec::from_iter(string.split(&regex).rev())), pieces, "{} {:?}", pattern, string);
+                
+                // The iterators over a mutable string see the whole string,
+                // like the others.
+                let mut owned = ByteString::from_slice(string.as_slice());
+                assert_eq!(Vec::from_iter(owned.split_mut(&regex).map(|s| s.to_vec())),
+                           Vec::from_iter(pieces.iter().map(|s| s.to_vec())), "{} {:?}", pattern, string);
+                assert_eq!(Vec::from_iter(owned.rsplit_mut(&regex).map(|s| s.to_vec())),
+                           Vec::from_iter(string.rsplit(&regex).map(|s| s.to_vec())), "{} {:?}", pattern, string);
+                assert_eq!(Vec::from_iter(owned.split_inclusive_mut(&regex).rev().map(|s| s.to_vec())),
+                           Vec::from_iter(string.split_inclusive(&regex).rev().map(|s| s.to_vec())), "{} {:?}", pattern, string);
+                assert_eq!(Vec::from_iter(owned.match_indices_mut(&regex).map(|(i, s)| (i, s.len()))),
+                           Vec::from_iter(string.match_indices(&regex).map(|(i, s)| (i, s.len()))), "{} {:?}", pattern, string);
+                
+                for mask in 0u32 .. 1 << pieces.len() {
+                    let mut iter = string.split(&regex);
+                    let (mut front, mut back) = (Vec::new(), Vec::new());
+                    for i in 0 .. pieces.len() {
+                        if mask >> i & 1 != 0 {
+                            front.push(iter.next().unwrap());
+                        } else {
+                            back.push(iter.next_back().unwrap());
+                        }
+                    }
+                    assert!(iter.next().is_none() && iter.next_back().is_none(), "{} {:?}", pattern, string);
+                    front.extend(reversed(back));
+                    assert_eq!(front, pieces, "{} {:?} {:b}", pattern, string, mask);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn regex_test_2() {
+    use FullMatcher;
+    use PrefixMatcher;
+    
+    let regex = Regex::new(r"(?P<name>[a-z]+)(?:-(\d+))?(?<end>!)?").unwrap();
+    assert_eq!(regex.captures_len(), 4);
+    
+    let string = ByteStr::from_slice(b"12 abc-34 de");
+    let captures = regex.captures(string).unwrap();
+    assert_eq!(captures.len(), 4);
+    assert_eq!(&captures[0], b"abc-34");
+    assert_eq!(captures.get(1), Some(ByteStr::from_slice(b"abc")));
+    assert_eq!(captures.name("name"), Some(ByteStr::from_slice(b"abc")));
+    assert_eq!(captures.pos(2), Some((7, 9)));
+    assert_eq!(captures.get(3), None);
+    assert_eq!(captures.name("end"), None);
+    assert_eq!(captures.name("other"), None);
+    assert!(regex.captures(ByteStr::from_slice(b"123")).is_none());
+    
+    assert!(regex.matches(ByteStr::from_slice(b"abc-34!")));
+    assert!(!regex.matches(ByteStr::from_slice(b"abc-34!!")));
+    assert!(regex.is_prefix_of(ByteStr::from_slice(b"abc-34!!")));
+    assert!(!regex.is_prefix_of(ByteStr::from_slice(b"-abc")));
+    assert!(Regex::new("a|ab").unwrap().matches(ByteStr::from_slice(b"ab")));
+    
+    for &(pattern, position) in [("a(b", 1), ("a)", 1), ("*", 0), ("a**", 2), ("[a", 0), ("[z-a]", 1),
+                                 (r"\x4", 0), (r"\q", 0), ("a{2,1}", 1), ("(?P<1>a)", 4), ("[é]", 1)].iter() {
+        let error = Regex::new(pattern).unwrap_err();
+        assert_eq!(error.position(), position, "{}: {}", pattern, error);
+    }
+    
+    // Compiled regexes and globs can be shared between threads, which search
+    // with automata of their own while the cache is busy.
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Regex>();
+    assert_send_sync::<GlobMatcher>();
+    let regex = std::sync::Arc::new(Regex::new(r"[a-z]+-\d+").unwrap());
+    let threads = Vec::from_iter((0 .. 4).map(|i| {
+        let regex = regex.clone();
+        std::thread::spawn(move || {
+            let string = ByteString::from(format!("x abc-{} y", i).repeat(100));
+            let count = string.matches(&*regex).count();
+            let captures = regex.captures(&string).and_then(|captures| captures.pos(0));
+            (count, captures)
+        })
+    }));
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), (100, Some((2, 7))));
+    }
+    let cloned = (*regex).clone();
+    assert_eq!(ByteStr::from_slice(b"ab-12").find(&cloned), Some(0));
+}
+
+#[test]
+#[cfg(not(feature="no_std"))]
+fn glob_test_1() {
+    use FullMatcher;
+    use ForwardSearcher;
+    
+    let glob = GlobMatcher::new("*.log").unwrap();
+    assert!(glob.matches(ByteStr::from_slice(b"server.log")));
+    assert!(glob.matches(ByteStr::from_slice(b".log")));
+    assert!(glob.matches(ByteStr::from_slice(b"var/log/server.log")));
+    assert!(!glob.matches(ByteStr::from_slice(b"server.log.1")));
+    
+    let glob = GlobMatcher::new("data-??-[0-9]*").unwrap();
+    assert!(glob.matches(ByteStr::from_slice(b"data-ab-1")));
+    assert!(glob.matches(ByteStr::from_slice(b"data-\xFF\xFE-42.csv")));
+    assert!(!glob.matches(ByteStr::from_slice(b"data-a-1")));
+    assert!(!glob.matches(ByteStr::from_slice(b"data-ab-x1")));
+    
+    let glob = GlobMatcher::new("[!a-c]x[]]").unwrap();
+    assert!(glob.matches(ByteStr::from_slice(b"dx]")));
+    assert!(!glob.matches(ByteStr::from_slice(b"bx]")));
+    
+    let glob = GlobMatcher::new(r"\*\?[\]\-]").unwrap();
+    assert!(glob.matches(ByteStr::from_slice(b"*?-")));
+    assert!(glob.matches(ByteStr::from_slice(b"*?]")));
+    assert!(!glob.matches(ByteStr::from_slice(b"a?-")));
+    
+    // Unanchored searches.
+    let string = ByteStr::from_slice(b"a.txt, b.log, c.log");
+    let glob = GlobMatcher::new("?.log").unwrap();
+    assert_eq!(glob.find(string), Some((7, 12)));
+    assert_eq!(string.find(&glob), Some(7));
+    assert_eq!(Vec::from_iter(string.matches(&glob)), [&b"b.log"[..], b"c.log"]);
+    assert_eq!(Vec::from_iter(string.split(&GlobMatcher::new(",*.").unwrap())), [&b"a.txt"[..], b"log"]);
+    
+    for &(pattern, position) in [("[a", 0), ("ab[", 2), ("a\\", 1), ("[z-a]", 0), ("[a\\", 0)].iter() {
+        let error = GlobMatcher::new(pattern).unwrap_err();
+        assert_eq!(error.position(), position, "{}: {}", pattern, error);
+    }
+}
+
+#[test]
+#[cfg(not(feature="no_std"))]
+fn glob_test_2() {
+    use FullMatcher;
+    
+    let cases: &[(&str, &[&str], &[&str])] = &[
+        ("*.rs", &["lib.rs", ".rs"], &["src/lib.rs", "lib.rs/x"]),
+        ("src/?.rs", &["src/a.rs"], &["src//.rs", "src/ab.rs"]),
+        ("[!a]", &["b"], &["a", "/"]),
+        ("**", &["", "a", "a/b/c"], &[]),
+        ("**/*.rs", &["lib.rs", "src/lib.rs", "src/a/b.rs"], &["lib.rc", "src/lib.rs/x"]),
+        ("src/**", &["src/", "src/a", "src/a/b"], &["src", "lib/src/a"]),
+        ("src/**/mod.rs", &["src/mod.rs", "src/a/mod.rs", "src/a/b/mod.rs"], &["src/amod.rs", "mod.rs"]),
+        ("a**b", &["ab", "axxb"], &["a/b"]),
+    ];
+    for &(pattern, matching, not_matching) in cases {
+        let glob = GlobMatcher::with_mode(GlobMode::Path, pattern).unwrap();
+        for path in matching {
+            assert!(glob.matches(ByteStr::from_slice(path.as_bytes())), "{} {}", pattern, path);
+        }
+        for path in not_matching {
+            assert!(!glob.matches(ByteStr::from_slice(path.as_bytes())), "{} {}", pattern, path);
+        }
+    }
+    
+    // In plain mode `/` is an ordinary byte.
+    let glob = GlobMatcher::new("src/*.rs").unwrap();
+    assert!(glob.matches(ByteStr::from_slice(b"src/a/b.rs")));
+    assert_eq!(glob.mode(), GlobMode::Plain);
+    assert_eq!(glob.pattern(), ByteStr::from_slice(b"src/*.rs"));
+}
+
+#[test]
+fn searcher_test_1() {
+    use IntoMatcher;
+    use IntoSearcher;
+    use IntoRSearcher;
+    use Searcher;
+    use RSearcher;
+    use SearchStep;
+    
+    let string = ByteStr::from_slice(b"xaaxxaaaax");
+    let mut searcher = b"aa".into_matcher().into_searcher(string);
+    assert_eq!(searcher.haystack(), string);
+    assert_eq!(searcher.next(), SearchStep::Reject(0, 1));
+    assert_eq!(searcher.next(), SearchStep::Match(1, 3));
+    assert_eq!(searcher.cursor(), 3);
+    assert_eq!(searcher.next(), SearchStep::Reject(3, 5));
+    assert_eq!(searcher.next(), SearchStep::Match(5, 7));
+    assert_eq!(searcher.next(), SearchStep::Match(7, 9));
+    assert_eq!(searcher.next(), SearchStep::Reject(9, 10));
+    assert_eq!(searcher.next(), SearchStep::Done);
+    
+    let mut searcher = b"aa".into_matcher().into_searcher(string);
+    assert_eq!(searcher.next_reject(), Some((0, 1)));
+    assert_eq!(searcher.next_match_back(), Some((7, 9)));
+    assert_eq!(searcher.next_reject_back(), Some((3, 5)));
+    assert_eq!(searcher.next_match(), Some((1, 3)));
+    assert_eq!(searcher.next_match(), None);
+    assert_eq!(searcher.cursor(), 3);
+    assert_eq!(searcher.cursor_back(), 3);
+    
+    // Reverse searchers select the matches from the back.
+    let string = ByteStr::from_slice(b"aaaaa");
+    let mut searcher = b"aa".into_matcher().into_rsearcher(string);
+    assert_eq!(searcher.next_back(), SearchStep::Match(3, 5));
+    assert_eq!(searcher.next(), SearchStep::Reject(0, 1));
+    assert_eq!(searcher.next(), SearchStep::Match(1, 3));
+    assert_eq!(searcher.next_back(), SearchStep::Done);
+    assert_eq!(searcher.next(), SearchStep::Done);
+}
+
+#[test]
+#[cfg(not(feature="no_std"))]
+fn searcher_test_2() {
+    use IntoSearcher;
+    use Searcher;
+    use SearchStep;
+    
+    // Regex searchers keep the context of the whole haystack.
+    let string = ByteStr::from_slice(b"aaa");
+    let regex = Regex::new("^a").unwrap();
+    assert_eq!(Vec::from_iter(string.match_indices(&regex).map(|(i, _)| i)), [0]);
+    assert_eq!(Vec::from_iter(string.split(&regex)), [&b""[..], b"aa"]);
+    
+    let string = ByteStr::from_slice(b"one two  three");
+    let regex = Regex::new(" +").unwrap();
+    let mut searcher = (&regex).into_searcher(string);
+    assert_eq!(searcher.next(), SearchStep::Reject(0, 3));
+    assert_eq!(searcher.next(), SearchStep::Match(3, 4));
+    assert_eq!(searcher.next_reject(), Some((4, 7)));
+    assert_eq!(searcher.next_match(), Some((7, 9)));
+    assert_eq!(searcher.next_match(), None);
+    
+    // Many matches in a long haystack.
+    let string = ByteString::from_slice(&b"ab".repeat(100000));
+    let regex = Regex::new("b").unwrap();
+    assert_eq!(string.matches(&regex).count(), 100000);
+    let matcher = AhoCorasickMatcher::new(&["a", "b"]);
+    assert_eq!(string.matches(&matcher).count(), 200000);
+}
+
+#[test]
+#[cfg(not(feature="no_std"))]
+fn searcher_test_3() {
+    use IntoMatcher;
+    use IntoSearcher;
+    use IntoRSearcher;
+    use Searcher;
+    use RSearcher;
+    use SearchStep;
+    
+    // Substring searchers step through chains of overlapping occurrences
+    // from the other end, in any order.
+    let mut random = xorshift(0x2545_f491);
+    for _ in 0 .. 300 {
+        let string = ByteString::from_slice(&random_bytes(&mut random, 40, &[b"a", b"aa", b"ab", b"A", b"b"]));
+        for &needle in &[&b"aa"[..], b"aaa", b"aba", b"Aa"] {
+            let forward = Vec::from_iter(string.split(AsciiCaseInsensitive(needle)));
+            let backward = Vec::from_iter(string.rsplit(AsciiCaseInsensitive(needle)));
+            assert_eq!(mixed(string.split(AsciiCaseInsensitive(needle)), &mut random), forward);
+            assert_eq!(mixed(string.rsplit(AsciiCaseInsensitive(needle)), &mut random), backward);
+            let forward = Vec::from_iter(string.split(needle));
+            let backward = Vec::from_iter(string.rsplit(needle));
+            assert_eq!(mixed(string.split(needle), &mut random), forward);
+            assert_eq!(mixed(string.rsplit(needle), &mut random), backward);
+            
+            let matches = Vec::from_iter(string.match_indices(needle).map(|(i, m)| (i, i + m.len())));
+            let mut searcher = needle.into_matcher().into_searcher(&string);
+            let mut end = string.len();
+            let mut found = Vec::new();
+            loop {
+                match searcher.next_back() {
+                    SearchStep::Match(a, b) => { assert_eq!(b, end); found.push((a, b)); end = a; }
+                    SearchStep::Reject(a, b) => { assert_eq!(b, end); end = a; }
+                    SearchStep::Done => break,
+                }
+            }
+            assert_eq!(end, 0);
+            assert_eq!(reversed(found), matches);
+            
+            let matches = Vec::from_iter(string.rmatch_indices(needle).map(|(i, m)| (i, i + m.len())));
+            let mut searcher = needle.into_matcher().into_rsearcher(&string);
+            let mut start = 0;
+            let mut found = Vec::new();
+            loop {
+                match searcher.next() {
+                    SearchStep::Match(a, b) => { assert_eq!(a, start); found.push((a, b)); start = b; }
+                    SearchStep::Reject(a, b) => { assert_eq!(a, start); start = b; }
+                    SearchStep::Done => break,
+                }
+            }
+            assert_eq!(start, string.len());
+            assert_eq!(reversed(found), matches);
+        }
+    }
+    
+    // Each step from the back only walks the chain once.
+    let string = ByteString::from_slice(&b"a".repeat(100000));
+    assert_eq!(string.split(b"aa").rev().count(), 50001);
+    assert_eq!(string.rsplit(AsciiCaseInsensitive(b"AA")).rev().count(), 50001);
+}
+
+#[test]
+#[cfg(not(feature="no_std"))]
+fn overlapping_test_1() {
+    use Finder;
+    
+    let string = ByteStr::from_slice(b"aaaa");
+    assert_eq!(Vec::from_iter(string.matches(b"aa")), [&b"aa"[..], b"aa"]);
+    assert_eq!(Vec::from_iter(string.matches_overlapping(b"aa")), [&b"aa"[..], b"aa", b"aa"]);
+    assert_eq!(Vec::from_iter(string.match_indices_overlapping(b"aa").map(|(i, _)| i)), [0, 1, 2]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices_overlapping(b"aa").map(|(i, _)| i)), [2, 1, 0]);
+    assert_eq!(string.rmatches_overlapping(b"aaa").count(), 2);
+    assert_eq!(string.count_overlapping(b"aa"), 3);
+    assert_eq!(string.count_overlapping(b"aaaaa"), 0);
+    assert_eq!(string.count_overlapping(b'a'), 4);
+    
+    let string = ByteStr::from_slice(b"abcabcab");
+    assert_eq!(Vec::from_iter(string.match_indices_overlapping(b"cab").map(|(i, _)| i)), [2, 5]);
+    assert_eq!(Vec::from_iter(string.match_indices_overlapping(ByteSet(b"ac")).map(|(i, _)| i)), [0, 2, 3, 5, 6]);
+    
+    // Empty matches are found at every position.
+    let string = ByteStr::from_slice(b"ab");
+    assert_eq!(Vec::from_iter(string.match_indices_overlapping(b"").map(|(i, _)| i)), [0, 1, 2]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices_overlapping(b"").map(|(i, _)| i)), [2, 1, 0]);
+    assert_eq!(string.count_overlapping(b""), 3);
+    
+    // With a regex, the leftmost match starting at each position.
+    let string = ByteStr::from_slice(b"xaaa");
+    let regex = Regex::new("a+").unwrap();
+    assert_eq!(Vec::from_iter(string.matches_overlapping(&regex)), [&b"aaa"[..], b"aa", b"a"]);
+    assert_eq!(string.count_overlapping(&regex), 3);
+    let string = ByteStr::from_slice(b"aaba");
+    let regex = Regex::new("^a").unwrap();
+    assert_eq!(Vec::from_iter(string.match_indices_overlapping(&regex).map(|(i, _)| i)), [0]);
+    let regex = Regex::new("a$").unwrap();
+    assert_eq!(Vec::from_iter(string.rmatch_indices_overlapping(&regex).map(|(i, _)| i)), [3]);
+    
+    // Compare with a naive search.
+    let mut random = xorshift(0x2545_f491);
+    for _ in 0 .. 500 {
+        let haystack: Vec<u8> = (0 .. 20).map(|_| b"ab"[random(2) as usize]).collect();
+        let needle: Vec<u8> = (0 .. 1 + random(4)).map(|_| b"ab"[random(2) as usize]).collect();
+        let string = ByteStr::from_slice(&haystack);
+        let expected: Vec<usize> = (0 ..= haystack.len() - needle.len())
+            .filter(|&i| haystack[i ..].starts_with(&needle))
+            .collect();
+        assert_eq!(Vec::from_iter(string.match_indices_overlapping(&needle).map(|(i, _)| i)), expected);
+        assert_eq!(Vec::from_iter(string.rmatch_indices_overlapping(&needle).map(|(i, _)| i)), reversed(expected.clone()));
+        assert_eq!(string.count_overlapping(&needle), expected.len());
+    }
+    
+    // Long and periodic needles skip ahead after each match.
+    for _ in 0 .. 500 {
+        let period: Vec<u8> = (0 .. 1 + random(3)).map(|_| b"ab"[random(2) as usize]).collect();
+        let haystack: Vec<u8> = (0 .. random(80)).map(|i| if random(16) == 0 { b'b' } else { period[i as usize % period.len()] }).collect();
+        let needle: Vec<u8> = (0 .. 1 + random(12)).map(|i| period[i as usize % period.len()]).collect();
+        let string = ByteStr::from_slice(&haystack);
+        let expected = haystack.windows(needle.len()).filter(|&window| window == &needle[..]).count();
+        assert_eq!(string.count_overlapping(&needle), expected, "{:?} in {:?}", needle, string);
+        assert_eq!(string.count_overlapping(&Finder::new(&needle)), expected);
+        assert_eq!(string.count_overlapping(AsciiCaseInsensitive(&needle.to_ascii_uppercase())), expected);
+        assert_eq!(string.count_overlapping(needle[0]), haystack.iter().filter(|&&byte| byte == needle[0]).count());
+        assert_eq!(string.count_overlapping(ByteSet(&needle[.. 1])), haystack.iter().filter(|&&byte| byte == needle[0]).count());
+    }
+}
+
+#[test]
+#[cfg(not(feature="no_std"))]
+fn finder_test_1() {
+    use Finder;
+    use FinderRev;
+    
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Finder>();
+    assert_send_sync::<FinderRev>();
+    
+    let finder = Finder::new("\r\n");
+    let finder_rev = FinderRev::new(b"\r\n");
+    assert_eq!(finder.needle(), ByteStr::from_slice(b"\r\n"));
+    
+    let string = ByteStr::from_slice(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n");
+    assert_eq!(string.find(&finder), Some(14));
+    assert_eq!(string.rfind(&finder_rev), Some(27));
+    assert!(string.contains(&finder));
+    assert!(string.ends_with(&finder));
+    assert!(!string.starts_with(&finder_rev));
+    assert_eq!(Vec::from_iter(string.split(&finder)), [&b"GET / HTTP/1.1"[..], b"Host: x", b"", b""]);
+    assert_eq!(Vec::from_iter(string.rsplitn(2, &finder_rev)), [&b""[..], b"GET / HTTP/1.1\r\nHost: x\r\n"]);
+    assert_eq!(string.count_overlapping(&finder), 3);
+    
+    // Reuse the same finder from several threads.
+    let finder = std::sync::Arc::new(finder);
+    let threads: Vec<_> = (0 .. 4).map(|i| {
+        let finder = finder.clone();
+        std::thread::spawn(move || {
+            let buffer = ByteString::from_slice(&b"a\r\n".repeat(i + 1));
+            buffer.matches(&*finder).count()
+        })
+    }).collect();
+    let counts: Vec<usize> = threads.into_iter().map(|thread| thread.join().unwrap()).collect();
+    assert_eq!(counts, [1, 2, 3, 4]);
+    
+    let empty = Finder::new(b"");
+    assert_eq!(string.find(&empty), Some(0));
+    assert_eq!(string.rfind(&FinderRev::new(b"")), Some(string.len()));
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn combinators_test_1() {
+    use MatcherExt;
+    use combinators::{not, repeat};
+    use FullMatcher;
+    use IntoMatcher;
+    
+    let string = ByteStr::from_slice(b"one, two;three,,four");
+    let separator = b','.or(b';');
+    assert_eq!(Vec::from_iter(string.split(separator.clone())), [&b"one"[..], b" two", b"three", b"", b"four"]);
+    assert_eq!(Vec::from_iter(string.rsplit(separator.clone())), [&b"four"[..], b"", b"three", b" two", b"one"]);
+    assert_eq!(string.find(b"two".or(b"one")), Some(0));
+    assert_eq!(string.rfind(b"two".or(b"one")), Some(8));
+    
+    let string = ByteStr::from_slice(b"a=1, bb=22, c=");
+    let assignment = repeat(not(ByteSet(b" ,=")), 1, usize::MAX).then(b'=').then(repeat(ByteSet(b"0123456789"), 1, usize::MAX));
+    assert_eq!(Vec::from_iter(string.matches(&assignment)), [&b"a=1"[..], b"bb=22"]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices(&assignment)), [(5, ByteStr::from_slice(b"bb=22")), (0, ByteStr::from_slice(b"a=1"))]);
+    assert!(string.starts_with(&assignment));
+    assert!(!string.ends_with(&assignment));
+    assert!(assignment.matches(ByteStr::from_slice(b"key=42")));
+    assert!(!assignment.matches(ByteStr::from_slice(b"key=42 ")));
+    
+    // `then` reports a match only when the second matcher follows the first.
+    let string = ByteStr::from_slice(b"abacad");
+    assert_eq!(string.find(b"a".then(b"d")), Some(4));
+    assert_eq!(string.rfind(b"a".then(b"b")), Some(2));
+    assert_eq!(string.find(b"a".then(b"e")), None);
+    
+    let digits = repeat(ByteSet(b"0123456789"), 2, 3);
+    let string = ByteStr::from_slice(b"1 12345 67");
+    assert_eq!(Vec::from_iter(string.matches(&digits)), [&b"123"[..], b"45", b"67"]);
+    assert_eq!(Vec::from_iter(string.rmatches(&digits)), [&b"67"[..], b"345", b"12"]);
+    assert_eq!(string.find(repeat(b'x', 0, usize::MAX)), Some(0));
+    assert_eq!(string.rfind(repeat(b'7', 0, 1)), Some(10));
+    assert!(!repeat(b'1', 2, 2).into_matcher().matches(ByteStr::from_slice(b"111")));
+    
+    let not_space = not(ByteSet(b" "));
+    assert!(!not_space.contains(b' '));
+    assert!(not_space.contains(b'\0'));
+    assert_eq!(ByteStr::from_slice(b"   x ").find(not_space), Some(3));
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn combinators_test_2() {
+    use MatcherExt;
+    use IntoSearcher;
+    use IntoRSearcher;
+    use Searcher;
+    use RSearcher;
+    use SearchStep;
+    use FindSearcher;
+    use RFindSearcher;
+    
+    fn steps<'a, S: Searcher<'a>>(mut searcher: S) -> Vec<SearchStep> {
+        let mut steps = Vec::new();
+        loop {
+            match searcher.next() {
+                SearchStep::Done => return steps,
+                step => steps.push(step),
+            }
+        }
+    }
+    
+    fn steps_back<'a, S: RSearcher<'a>>(mut searcher: S) -> Vec<SearchStep> {
+        let mut steps = Vec::new();
+        loop {
+            match searcher.next_back() {
+                SearchStep::Done => return steps,
+                step => steps.push(step),
+            }
+        }
+    }
+    
+    // `Or` searchers keep the pending match of each side, and step like
+    // searching both sides again after each match.
+    let mut random = xorshift(0x2545_f491);
+    let needles: [&[u8]; 6] = [b"", b"a", b"b", b"ab", b"ba", b"aba"];
+    for _ in 0 .. 300 {
+        let string = ByteString::from_slice(&random_bytes(&mut random, 20, &[b"a", b"b", b"ab", b"c"]));
+        let string = string.as_byte_str();
+        let first = needles[random(needles.len() as u32) as usize];
+        let second = needles[random(needles.len() as u32) as usize];
+        let or = first.or(second);
+        assert_eq!(steps((&or).into_searcher(string)), steps(FindSearcher::new(string, &or)), "{:?} {:?} {:?}", first, second, string);
+        assert_eq!(steps_back((&or).into_rsearcher(string)), steps_back(RFindSearcher::new(string, &or)), "{:?} {:?} {:?}", first, second, string);
+    }
+    
+    // Only the side that is consumed searches again.
+    let mut string = ByteString::from_slice(&b"b".repeat(200000));
+    string.push(b'a');
+    assert_eq!(string.split(b'b'.or(b'a')).count(), 200002);
+    assert_eq!(string.rsplit(b'a'.or(b'b')).count(), 200002);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn approx_test_1() {
+    use HammingMatcher;
+    
+    let matcher = HammingMatcher::new(b"GATTACA", 1);
+    let string = ByteStr::from_slice(b"CCGATTACCTTGATTACACCGTTTACA");
+    assert_eq!(matcher.find_with_distance(string), Some((2, 9, 1)));
+    assert_eq!(string.find(&matcher), Some(2));
+    assert_eq!(Vec::from_iter(string.match_indices(&matcher).map(|(i, s)| (i, matcher.distance(s)))), [(2, Some(1)), (11, Some(0)), (20, Some(1))]);
+    assert_eq!(Vec::from_iter(string.split(&matcher)), [&b"CC"[..], b"TT", b"CC", b""]);
+    assert_eq!(matcher.distance(ByteStr::from_slice(b"GTTTACA")), Some(1));
+    assert_eq!(matcher.distance(ByteStr::from_slice(b"GTTTACC")), None);
+    assert_eq!(matcher.distance(ByteStr::from_slice(b"GATTAC")), None);
+    assert_eq!(HammingMatcher::new(b"GATTACA", 0).find_with_distance(string), Some((11, 18, 0)));
+    assert_eq!(HammingMatcher::new(b"", 2).find_with_distance(string), Some((0, 0, 0)));
+    assert_eq!(HammingMatcher::new(b"GATTACA", 9).find_with_distance(string), Some((0, 7, 6)));
+    
+    let mut random = xorshift(0x2545_f491);
+    for round in 0 .. 300 {
+        // A few long needles to cover several blocks of the bit vectors.
+        let needle_len = if round % 20 == 0 { 60 + random(80) } else { 1 + random(8) } as usize;
+        let needle: Vec<u8> = (0 .. needle_len).map(|_| b'a' + random(3) as u8).collect();
+        let haystack: Vec<u8> = (0 .. random(40) as usize + needle_len).map(|_| b'a' + random(3) as u8).collect();
+        let k = random(needle_len as u32 / 2 + 2) as usize;
+        let matcher = HammingMatcher::new(&needle, k);
+        let expected = haystack.windows(needle_len).enumerate().filter_map(|(start, window)| {
+            let distance = window.iter().zip(&needle).filter(|&(a, b)| a != b).count();
+            if distance <= k { Some((start, start + needle_len, distance)) } else { None }
+        }).next();
+        assert_eq!(matcher.find_with_distance(ByteStr::from_slice(&haystack)), expected,
+                   "needle {:?}, k {}, haystack {:?}", ByteStr::from_slice(&needle), k, ByteStr::from_slice(&haystack));
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn approx_test_2() {
+    use EditDistanceMatcher;
+    
+    fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+        let mut row: Vec<usize> = (0 ..= a.len()).collect();
+        for (j, &y) in b.iter().enumerate() {
+            let mut previous = row.clone();
+            previous[0] = j + 1;
+            for i in 1 ..= a.len() {
+                previous[i] = (row[i - 1] + (a[i - 1] != y) as usize).min(row[i] + 1).min(previous[i - 1] + 1);
+            }
+            row = previous;
+        }
+        row[a.len()]
+    }
+    
+    // Leftmost end within the limit, extended while the distance decreases,
+    // and then the longest match with that distance.
+    fn naive_find(needle: &[u8], k: usize, haystack: &[u8]) -> Option<(usize, usize, usize)> {
+        // Lowest distance of a substring ending at each position, with the
+        // textbook column by column dynamic programming.
+        let mut column: Vec<usize> = (0 ..= needle.len()).collect();
+        let mut best = vec![needle.len()];
+        for &y in haystack {
+            let mut next = vec![0];
+            for i in 1 ..= needle.len() {
+                next.push((column[i - 1] + (needle[i - 1] != y) as usize).min(column[i] + 1).min(next[i - 1] + 1));
+            }
+            column = next;
+            best.push(column[needle.len()]);
+        }
+        let mut end = (0 ..= haystack.len()).find(|&end| best[end] <= k)?;
+        while end < haystack.len() && best[end + 1] < best[end] {
+            end += 1;
+        }
+        let distance = best[end];
+        let start = (0 ..= end).find(|&start| edit_distance(needle, &haystack[start .. end]) == distance).unwrap();
+        Some((start, end, distance))
+    }
+    
+    let matcher = EditDistanceMatcher::new(b"abc", 1);
+    let string = ByteStr::from_slice(b"xabcx abx acbc");
+    assert_eq!(matcher.find_with_distance(string), Some((1, 4, 0)));
+    assert_eq!(Vec::from_iter(string.match_indices(&matcher).map(|(i, s)| (i, matcher.distance(s)))), [(1, Some(0)), (6, Some(1)), (10, Some(1)), (12, Some(1))]);
+    assert_eq!(matcher.distance(ByteStr::from_slice(b"ac")), Some(1));
+    assert_eq!(matcher.distance(ByteStr::from_slice(b"ca")), None);
+    
+    let mut random = xorshift(0x2545_f491);
+    for round in 0 .. 300 {
+        // A few long needles to cover several blocks of the bit vectors.
+        let needle_len = if round % 20 == 0 { 60 + random(80) } else { random(8) } as usize;
+        let needle: Vec<u8> = (0 .. needle_len).map(|_| b'a' + random(3) as u8).collect();
+        let haystack: Vec<u8> = (0 .. random(40) as usize + needle_len / 2).map(|_| b'a' + random(3) as u8).collect();
+        let k = random(needle_len as u32 / 2 + 2) as usize;
+        let matcher = EditDistanceMatcher::new(&needle, k);
+        assert_eq!(matcher.find_with_distance(ByteStr::from_slice(&haystack)), naive_find(&needle, k, &haystack),
+                   "needle {:?}, k {}, haystack {:?}", ByteStr::from_slice(&needle), k, ByteStr::from_slice(&haystack));
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn char_test_1() {
+    let string = ByteStr::from_slice("a→b→ç\u{1F600}é".as_bytes());
+    assert_eq!(string.find('→'), Some(1));
+    assert_eq!(string.rfind('→'), Some(8));
+    assert_eq!(string.find('é'), Some(14));
+    assert_eq!(string.find('\u{1F600}'), Some(10));
+    assert_eq!(string.find('x'), None);
+    assert_eq!(Vec::from_iter(string.split('→')), [&b"a"[..], b"b", "ç\u{1F600}é".as_bytes()]);
+    assert_eq!(Vec::from_iter(string.rsplit("→b")), ["→ç\u{1F600}é".as_bytes(), b"a"]);
+    assert!(string.starts_with("a→"));
+    assert!(string.ends_with('é'));
+    
+    // The encoding of a char is found between invalid bytes.
+    let string = ByteStr::from_slice(b"\xFF\xC3\xA9\xC3\xE9\xA9");
+    assert_eq!(Vec::from_iter(string.match_indices('é')), [(1, ByteStr::from_slice("é".as_bytes()))]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices('é')), [(1, ByteStr::from_slice("é".as_bytes()))]);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn char_test_2() {
+    use CharPredicateMatcher;
+    
+    let string = ByteStr::from_slice("día 1, año 2".as_bytes());
+    assert_eq!(Vec::from_iter(string.matches(CharPredicateMatcher::new(char::is_alphabetic)).map(|s| s.len())), [1, 2, 1, 1, 2, 1]);
+    assert_eq!(Vec::from_iter(string.split(CharPredicateMatcher::new(|c: char| !c.is_alphabetic())).filter(|s| !s.is_empty())),
+               ["día".as_bytes(), "año".as_bytes()]);
+    
+    // Every position that starts a valid char is a candidate, whatever
+    // comes before it, and invalid sequences never match.
+    let mut random = xorshift(0x2545_f491);
+    let pieces: [&[u8]; 12] = [b"a", b" ", "é".as_bytes(), "→".as_bytes(), "\u{1F600}".as_bytes(), b"\x80", b"\xC3", b"\xE2\x86",
+                               b"\xF0\x9F\x98", b"\xC0\xAF", b"\xED\xA0\x80", b"\xF4\x90\x80\x80"];
+    for _ in 0 .. 500 {
+        let haystack = random_bytes(&mut random, 12, &pieces);
+        let expected: Vec<(usize, char)> = (0 .. haystack.len()).filter_map(|i| {
+            (1 ..= 4).filter_map(|len| haystack.get(i .. i + len))
+                .filter_map(|bytes| std::str::from_utf8(bytes).ok())
+                .map(|s| s.chars().next().unwrap())
+                .next()
+                .filter(|&c| c != ' ')
+                .map(|c| (i, c))
+        }).collect();
+        let string = ByteStr::from_slice(&haystack);
+        let matcher = || CharPredicateMatcher::new(|c: char| c != ' ');
+        let found = Vec::from_iter(string.match_indices(matcher()).map(|(i, s)| (i, std::str::from_utf8(s.as_slice()).unwrap().chars().next().unwrap())));
+        assert_eq!(found, expected, "{:?}", string);
+        let found_back = Vec::from_iter(string.rmatch_indices(matcher()).map(|(i, s)| (i, std::str::from_utf8(s.as_slice()).unwrap().chars().next().unwrap())));
+        assert_eq!(reversed(found_back), expected, "{:?}", string);
+        for &(i, c) in &expected {
+            assert_eq!(string.find(c).map(|j| j <= i), Some(true));
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn empty_needle_test_1() {
+    let string = ByteStr::from_slice(b"abc");
+    assert_eq!(string.find(b""), Some(0));
+    assert_eq!(string.rfind(b""), Some(3));
+    assert!(string.contains(b""));
+    assert_eq!(Vec::from_iter(string.split(b"")), [&b""[..], b"a", b"b", b"c", b""]);
+    assert_eq!(Vec::from_iter(string.split(b"").rev()), [&b""[..], b"c", b"b", b"a", b""]);
+    assert_eq!(Vec::from_iter(string.rsplit(b"")), [&b""[..], b"c", b"b", b"a", b""]);
+    assert_eq!(Vec::from_iter(string.splitn(3, b"")), [&b""[..], b"a", b"bc"]);
+    assert_eq!(Vec::from_iter(string.rsplitn(3, b"")), [&b""[..], b"c", b"ab"]);
+    assert_eq!(Vec::from_iter(string.match_indices(b"")), [(0, ByteStr::empty()), (1, ByteStr::empty()), (2, ByteStr::empty()), (3, ByteStr::empty())]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices(b"").map(|(i, _)| i)), [3, 2, 1, 0]);
+    
+    let mut iter = string.split(b"");
+    assert_eq!(iter.next(), Some(ByteStr::empty()));
+    assert_eq!(iter.next_back(), Some(ByteStr::empty()));
+    assert_eq!(iter.next_back(), Some(ByteStr::from_slice(b"c")));
+    assert_eq!(iter.next(), Some(ByteStr::from_slice(b"a")));
+    assert_eq!(iter.next(), Some(ByteStr::from_slice(b"b")));
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+    
+    assert_eq!(Vec::from_iter(ByteStr::empty().split(b"")), [ByteStr::empty(), ByteStr::empty()]);
+    assert_eq!(Vec::from_iter(ByteStr::empty().matches(b"")), [ByteStr::empty()]);
+    assert_eq!(Vec::from_iter(ByteStr::empty().matches(b"").rev()), [ByteStr::empty()]);
+    
+    let mut string = ByteString::from_slice(b"ab");
+    assert_eq!(Vec::from_iter(string.split_mut(b"").map(|s| s.len())), [0, 1, 1, 0]);
+    assert_eq!(Vec::from_iter(string.rsplit_mut(b"").map(|s| s.len())), [0, 1, 1, 0]);
+    assert_eq!(Vec::from_iter(string.match_indices_mut(b"").map(|(i, _)| i)), [0, 1, 2]);
+    assert_eq!(Vec::from_iter(string.rmatch_indices_mut(b"").map(|(i, _)| i)), [2, 1, 0]);
+    
+    // Regexes that can match the empty string behave the same way.
+    let regex = Regex::new("x*").unwrap();
+    let string = ByteStr::from_slice(b"axxb");
+    assert_eq!(Vec::from_iter(string.match_indices(&regex).map(|(i, s)| (i, s.len()))), [(0, 0), (1, 2), (3, 0), (4, 0)]);
+    assert_eq!(Vec::from_iter(string.split(&regex)), [&b""[..], b"a", b"", b"b", b""]);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn str_conformance_test_1() {
+    fn bytes<'a, I: Iterator<Item = &'a str>>(iter: I) -> Vec<&'a [u8]> {
+        iter.map(str::as_bytes).collect()
+    }
+    
+    fn indices<'a, I: Iterator<Item = (usize, &'a str)>>(iter: I) -> Vec<(usize, &'a [u8])> {
+        iter.map(|(i, s)| (i, s.as_bytes())).collect()
+    }
+    
+    let mut random = xorshift(0x2545_f491);
+    let needles = ["", "a", "b", "aa", "ab", "ba", "aba", "aaa"];
+    for _ in 0 .. 500 {
+        let haystack = random_string(&mut random, 8, &["a", "a", "b"]);
+        let string = ByteStr::from_slice(haystack.as_bytes());
+        for &needle in &needles {
+            let message = format!("{:?} in {:?}", needle, haystack);
+            let n = needle.as_bytes();
+            assert_eq!(string.find(n), haystack.find(needle), "{}", message);
+            assert_eq!(string.rfind(n), haystack.rfind(needle).map(|i| i + needle.len()), "{}", message);
+            assert_eq!(string.contains(n), haystack.contains(needle), "{}", message);
+            assert_eq!(string.starts_with(n), haystack.starts_with(needle), "{}", message);
+            assert_eq!(string.ends_with(n), haystack.ends_with(needle), "{}", message);
+            
+            let split = bytes(haystack.split(needle));
+            let rsplit = bytes(haystack.rsplit(needle));
+            let matches = bytes(haystack.matches(needle));
+            let rmatches = bytes(haystack.rmatches(needle));
+            let match_indices = indices(haystack.match_indices(needle));
+            let rmatch_indices = indices(haystack.rmatch_indices(needle));
+            assert_eq!(Vec::from_iter(string.split(n).map(ByteStr::as_slice)), split, "{}", message);
+            assert_eq!(Vec::from_iter(string.rsplit(n).map(ByteStr::as_slice)), rsplit, "{}", message);
+            assert_eq!(Vec::from_iter(string.matches(n).map(ByteStr::as_slice)), matches, "{}", message);
+            assert_eq!(Vec::from_iter(string.rmatches(n).map(ByteStr::as_slice)), rmatches, "{}", message);
+            assert_eq!(Vec::from_iter(string.match_indices(n).map(|(i, s)| (i, s.as_slice()))), match_indices, "{}", message);
+            assert_eq!(Vec::from_iter(string.rmatch_indices(n).map(|(i, s)| (i, s.as_slice()))), rmatch_indices, "{}", message);
+            for count in 0 .. 4 {
+                assert_eq!(Vec::from_iter(string.splitn(count, n).map(ByteStr::as_slice)), bytes(haystack.splitn(count, needle)), "{}", message);
+                assert_eq!(Vec::from_iter(string.rsplitn(count, n).map(ByteStr::as_slice)), bytes(haystack.rsplitn(count, needle)), "{}", message);
+            }
+            
+            // Iterating from the back gives the same items in reverse order,
+            // also when both ends are mixed.
+            assert_eq!(Vec::from_iter(string.split(n).rev().map(ByteStr::as_slice)), reversed(split.clone()), "{}", message);
+            assert_eq!(Vec::from_iter(mixed(string.split(n), &mut random).into_iter().map(ByteStr::as_slice)), split, "{}", message);
+            assert_eq!(Vec::from_iter(string.matches(n).rev().map(ByteStr::as_slice)), reversed(matches.clone()), "{}", message);
+            
+            let mut owned = ByteString::from_slice(haystack.as_bytes());
+            assert_eq!(Vec::from_iter(owned.split_mut(n).map(|s| s.to_vec())), split, "{}", message);
+            assert_eq!(Vec::from_iter(owned.split_mut(n).rev().map(|s| s.to_vec())), reversed(split.clone()), "{}", message);
+            assert_eq!(Vec::from_iter(owned.rsplit_mut(n).map(|s| s.to_vec())), rsplit, "{}", message);
+            assert_eq!(Vec::from_iter(owned.splitn_mut(2, n).map(|s| s.to_vec())), bytes(haystack.splitn(2, needle)), "{}", message);
+            assert_eq!(Vec::from_iter(owned.rsplitn_mut(2, n).map(|s| s.to_vec())), bytes(haystack.rsplitn(2, needle)), "{}", message);
+            assert_eq!(Vec::from_iter(owned.match_indices_mut(n).map(|(i, s)| (i, s.to_vec()))),
+                       Vec::from_iter(match_indices.iter().map(|&(i, s)| (i, s.to_vec()))), "{}", message);
+            assert_eq!(Vec::from_iter(owned.rmatch_indices_mut(n).map(|(i, s)| (i, s.to_vec()))),
+                       Vec::from_iter(rmatch_indices.iter().map(|&(i, s)| (i, s.to_vec()))), "{}", message);
+            assert_eq!(Vec::from_iter(owned.matches_mut(n).rev().map(|s| s.to_vec())), reversed(matches.clone()), "{}", message);
+        }
+    }
+}
+
+#[test]
+fn trim_test_1() {
+    let string = ByteStr::from_slice(b" \t key: value \r\n");
+    assert_eq!(string.trim(), ByteStr::from_slice(b"key: value"));
+    assert_eq!(string.trim_start(), ByteStr::from_slice(b"key: value \r\n"));
+    assert_eq!(string.trim_end(), ByteStr::from_slice(b" \t key: value"));
+    assert_eq!(ByteStr::from_slice(b" \x0b ").trim(), ByteStr::from_slice(b"\x0b"));
+    assert_eq!(ByteStr::from_slice(b"  ").trim(), ByteStr::empty());
+    
+    let (key, value) = string.trim().split_once(b':').unwrap();
+    assert_eq!((key, value.trim_start()), (ByteStr::from_slice(b"key"), ByteStr::from_slice(b"value")));
+    assert_eq!(string.split_once(b"="), None);
+    assert_eq!(ByteStr::from_slice(b"a.b.c").rsplit_once(b'.'), Some((ByteStr::from_slice(b"a.b"), ByteStr::from_slice(b"c"))));
+    
+    let string = ByteStr::from_slice(b"xxaxbxx");
+    assert_eq!(string.strip_prefix(b'x'), Some(ByteStr::from_slice(b"xaxbxx")));
+    assert_eq!(string.strip_suffix(b"xx"), Some(ByteStr::from_slice(b"xxaxb")));
+    assert_eq!(string.strip_prefix(b"a"), None);
+    assert_eq!(string.trim_start_matches(b'x'), ByteStr::from_slice(b"axbxx"));
+    assert_eq!(string.trim_end_matches(b'x'), ByteStr::from_slice(b"xxaxb"));
+    assert_eq!(string.trim_matches(b'x'), ByteStr::from_slice(b"axb"));
+    assert_eq!(string.trim_matches(ByteSet(b"xa")), ByteStr::from_slice(b"b"));
+    assert_eq!(string.trim_matches(b""), string);
+    assert_eq!(string.trim_matches(AsciiCaseInsensitive(b"X")), ByteStr::from_slice(b"axb"));
+    
+    let mut bytes = *b" GET /index.html ";
+    let string = ByteStr::from_slice_mut(&mut bytes);
+    let (method, path) = string.trim_mut().split_once_mut(b' ').unwrap();
+    method.make_ascii_lowercase();
+    path.strip_prefix_mut(b'/').unwrap().make_ascii_uppercase();
+    assert_eq!(&bytes, b" get /INDEX.HTML ");
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn trim_test_2() {
+    let mut random = xorshift(0x2545_f491);
+    for _ in 0 .. 500 {
+        let haystack = random_string(&mut random, 8, &["a", "b", " ", "\n"]);
+        let string = ByteStr::from_slice(haystack.as_bytes());
+        assert_eq!(string.trim().as_slice(), haystack.trim().as_bytes());
+        assert_eq!(string.trim_start().as_slice(), haystack.trim_start().as_bytes());
+        assert_eq!(string.trim_end().as_slice(), haystack.trim_end().as_bytes());
+        for &needle in &["a", "b", "ab", "aa", "a b"] {
+            let n = needle.as_bytes();
+            assert_eq!(string.strip_prefix(n).map(ByteStr::as_slice), haystack.strip_prefix(needle).map(str::as_bytes));
+            assert_eq!(string.strip_suffix(n).map(ByteStr::as_slice), haystack.strip_suffix(needle).map(str::as_bytes));
+            assert_eq!(string.trim_start_matches(n).as_slice(), haystack.trim_start_matches(needle).as_bytes());
+            assert_eq!(string.trim_end_matches(n).as_slice(), haystack.trim_end_matches(needle).as_bytes());
+            assert_eq!(string.split_once(n).map(|(a, b)| (a.as_slice(), b.as_slice())), haystack.split_once(needle).map(|(a, b)| (a.as_bytes(), b.as_bytes())));
+            assert_eq!(string.rsplit_once(n).map(|(a, b)| (a.as_slice(), b.as_slice())), haystack.rsplit_once(needle).map(|(a, b)| (a.as_bytes(), b.as_bytes())));
+        }
+        let n = b'a';
+        assert_eq!(string.trim_matches(n).as_slice(), haystack.trim_matches('a').as_bytes());
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn split_terminator_test_1() {
+    let string = ByteStr::from_slice(b"a\nb\n\nc\n");
+    assert_eq!(Vec::from_iter(string.split_terminator(b'\n')), [&b"a"[..], b"b", b"", b"c"]);
+    assert_eq!(Vec::from_iter(string.rsplit_terminator(b'\n')), [&b"c"[..], b"", b"b", b"a"]);
+    assert_eq!(Vec::from_iter(string.split_inclusive(b'\n')), [&b"a\n"[..], b"b\n", b"\n", b"c\n"]);
+    assert_eq!(Vec::from_iter(string.rsplit_inclusive(b'\n')), [&b"c\n"[..], b"\n", b"b\n", b"a\n"]);
+    assert_eq!(Vec::from_iter(ByteStr::from_slice(b"a\nb").split_inclusive(b'\n')), [&b"a\n"[..], b"b"]);
+    assert_eq!(ByteStr::empty().split_terminator(b'\n').next(), None);
+    assert_eq!(ByteStr::empty().split_inclusive(b'\n').next_back(), None);
+    
+    let mut owned = ByteString::from_slice(b"k=v;x=y;");
+    for record in owned.split_terminator_mut(b';') {
+        if let Some(key) = record.strip_suffix_mut(b"v") {
+            key.make_ascii_uppercase();
+        }
+    }
+    assert_eq!(owned.as_slice(), b"K=v;x=y;");
+    assert_eq!(Vec::from_iter(owned.rsplit_inclusive_mut(b';').map(|s| s.to_vec())), [&b"x=y;"[..], b"K=v;"]);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn split_terminator_test_2() {
+    fn bytes<'a, I: Iterator<Item = &'a str>>(iter: I) -> Vec<&'a [u8]> {
+        iter.map(str::as_bytes).collect()
+    }
+    
+    let mut random = xorshift(0x2545_f491);
+    for _ in 0 .. 500 {
+        let haystack = random_string(&mut random, 8, &["a", "a", "b"]);
+        let string = ByteStr::from_slice(haystack.as_bytes());
+        for &needle in &["", "a", "b", "ab", "aa"] {
+            let message = format!("{:?} in {:?}", needle, haystack);
+            let n = needle.as_bytes();
+            let terminator = bytes(haystack.split_terminator(needle));
+            let inclusive = bytes(haystack.split_inclusive(needle));
+            assert_eq!(Vec::from_iter(string.split_terminator(n).map(ByteStr::as_slice)), terminator, "{}", message);
+            assert_eq!(Vec::from_iter(string.rsplit_terminator(n).map(ByteStr::as_slice)), bytes(haystack.rsplit_terminator(needle)), "{}", message);
+            assert_eq!(Vec::from_iter(string.split_inclusive(n).map(ByteStr::as_slice)), inclusive, "{}", message);
+            // The reverse splits only agree with the forward ones when the
+            // matches do not overlap.
+            let unambiguous = reversed(Vec::from_iter(haystack.match_indices(needle))) == Vec::from_iter(haystack.rmatch_indices(needle));
+            if unambiguous {
+                assert_eq!(Vec::from_iter(string.rsplit_inclusive(n).map(ByteStr::as_slice)), reversed(inclusive.clone()), "{}", message);
+            }
+            
+            assert_eq!(Vec::from_iter(string.split_terminator(n).rev().map(ByteStr::as_slice)), reversed(terminator.clone()), "{}", message);
+            assert_eq!(Vec::from_iter(string.split_inclusive(n).rev().map(ByteStr::as_slice)), reversed(inclusive.clone()), "{}", message);
+            assert_eq!(Vec::from_iter(string.rsplit_inclusive(n).rev()), reversed(Vec::from_iter(string.rsplit_inclusive(n))), "{}", message);
+            assert_eq!(Vec::from_iter(mixed(string.split_terminator(n), &mut random).into_iter().map(ByteStr::as_slice)), terminator, "{}", message);
+            assert_eq!(Vec::from_iter(mixed(string.split_inclusive(n), &mut random).into_iter().map(ByteStr::as_slice)), inclusive, "{}", message);
+            
+            let mut owned = ByteString::from_slice(haystack.as_bytes());
+            assert_eq!(Vec::from_iter(owned.split_terminator_mut(n).map(|s| s.to_vec())), terminator, "{}", message);
+            assert_eq!(Vec::from_iter(owned.rsplit_terminator_mut(n).map(|s| s.to_vec())), bytes(haystack.rsplit_terminator(needle)), "{}", message);
+            assert_eq!(Vec::from_iter(owned.split_inclusive_mut(n).map(|s| s.to_vec())), inclusive, "{}", message);
+            if unambiguous {
+                assert_eq!(Vec::from_iter(owned.rsplit_inclusive_mut(n).map(|s| s.to_vec())), reversed(inclusive.clone()), "{}", message);
+            }
+            assert_eq!(Vec::from_iter(mixed(owned.split_terminator_mut(n), &mut random).into_iter().map(|s| s.to_vec())), terminator, "{}", message);
+            assert_eq!(Vec::from_iter(mixed(owned.split_inclusive_mut(n), &mut random).into_iter().map(|s| s.to_vec())), inclusive, "{}", message);
+            if unambiguous {
+                assert_eq!(Vec::from_iter(mixed(owned.rsplit_inclusive_mut(n), &mut random).into_iter().map(|s| s.to_vec())), reversed(inclusive.clone()), "{}", message);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn lines_test_1() {
+    let string = ByteStr::from_slice(b"one\r\ntwo\n\nthree\rfour\x0cfive\xc2\x85six\n");
+    assert_eq!(Vec::from_iter(string.lines()), [&b"one"[..], b"two", b"", b"three\rfour\x0cfive\xc2\x85six"]);
+    assert_eq!(Vec::from_iter(string.lines_with_terminator()), [&b"one\r\n"[..], b"two\n", b"\n", b"three\rfour\x0cfive\xc2\x85six\n"]);
+    assert_eq!(Vec::from_iter(string.lines_by(LineBreaks::Any)), [&b"one"[..], b"two", b"", b"three", b"four", b"five", b"six"]);
+    assert_eq!(Vec::from_iter(string.lines_by(LineBreaks::Any).rev()), [&b"six"[..], b"five", b"four", b"three", b"", b"two", b"one"]);
+    assert_eq!(Vec::from_iter(string.line_indices().enumerate().map(|(n, (i, line))| (n, (i, line.as_slice())))), [(0, (0, &b"one"[..])), (1, (5, b"two")), (2, (9, b"")), (3, (10, b"three\rfour\x0cfive\xc2\x85six"))]);
+    assert_eq!(ByteStr::from_slice(b"a\r").lines().next(), Some(ByteStr::from_slice(b"a\r")));
+    assert_eq!(ByteStr::from_slice(b"\n").lines().next_back(), Some(ByteStr::empty()));
+    assert_eq!(ByteStr::empty().lines().next(), None);
+    
+    let mut owned = ByteString::from_slice(b"ab\r\ncd");
+    for line in owned.lines_mut().rev() {
+        line.make_ascii_uppercase();
+        line.reverse();
+    }
+    assert_eq!(owned.as_slice(), b"BA\r\nDC");
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn lines_test_2() {
+    // Splits `string` at every line break, keeping the terminators.
+    fn reference(string: &[u8], breaks: LineBreaks) -> Vec<&[u8]> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        while i < string.len() {
+            let next = string.get(i + 1).cloned();
+            let len = match (breaks, string[i], next) {
+                (_, b'\r', Some(b'\n')) => 2,
+                (_, b'\n', _) => 1,
+                (LineBreaks::Any, b'\r', _) | (LineBreaks::Any, b'\x0b', _) | (LineBreaks::Any, b'\x0c', _) => 1,
+                (LineBreaks::Any, b'\xc2', Some(b'\x85')) => 2,
+                _ => 0,
+            };
+            if len == 0 {
+                i += 1;
+            } else {
+                i += len;
+                lines.push(&string[start .. i]);
+                start = i;
+            }
+        }
+        if start < string.len() {
+            lines.push(&string[start ..]);
+        }
+        lines
+    }
+    
+    fn strip(line: &[u8], breaks: LineBreaks) -> &[u8] {
+        let mut end = line.len();
+        if line.ends_with(b"\r\n") || (breaks == LineBreaks::Any && line.ends_with(b"\xc2\x85")) {
+            end -= 2;
+        } else if line.ends_with(b"\n") || (breaks == LineBreaks::Any && (line.ends_with(b"\r") || line.ends_with(b"\x0b") || line.ends_with(b"\x0c"))) {
+            end -= 1;
+        }
+        &line[.. end]
+    }
+    
+    let mut random = xorshift(0x2545_f491);
+    let alphabet: [&[u8]; 7] = [b"a", b"\n", b"\r", b"\x0b", b"\x0c", b"\xc2", b"\x85"];
+    for _ in 0 .. 1000 {
+        let bytes = random_bytes(&mut random, 10, &alphabet);
+        let string = ByteStr::from_slice(&bytes);
+        for &breaks in &[LineBreaks::Newline, LineBreaks::Any] {
+            let message = format!("{:?} in {:?}", breaks, string);
+            let with_terminator = reference(&bytes, breaks);
+            let lines = Vec::from_iter(with_terminator.iter().map(|line| strip(line, breaks)));
+            if breaks == LineBreaks::Newline {
+                if let Ok(s) = ::core::str::from_utf8(&bytes) {
+                    assert_eq!(Vec::from_iter(s.lines().map(str::as_bytes)), lines, "{}", message);
+                }
+            }
+            
+            assert_eq!(Vec::from_iter(string.lines_by(breaks).map(ByteStr::as_slice)), lines, "{}", message);
+            assert_eq!(Vec::from_iter(string.lines_with_terminator_by(breaks).map(ByteStr::as_slice)), with_terminator, "{}", message);
+            assert_eq!(reversed(Vec::from_iter(string.lines_by(breaks).rev().map(ByteStr::as_slice))), lines, "{}", message);
+            assert_eq!(reversed(Vec::from_iter(string.lines_with_terminator_by(breaks).rev().map(ByteStr::as_slice))), with_terminator, "{}", message);
+            
+            let front = mixed(string.line_indices_by(breaks), &mut random);
+            let mut offset = 0;
+            for (&(start, line), &expected) in front.iter().zip(&with_terminator) {
+                assert_eq!((start, line.as_slice()), (offset, strip(expected, breaks)), "{}", message);
+                offset += expected.len();
+            }
+            assert_eq!(front.len(), lines.len(), "{}", message);
+            
+            let mut owned = ByteString::from_slice(&bytes);
+            assert_eq!(Vec::from_iter(owned.lines_by_mut(breaks).map(|line| line.to_vec())), lines, "{}", message);
+            assert_eq!(reversed(Vec::from_iter(owned.lines_with_terminator_by_mut(breaks).rev().map(|line| line.to_vec()))), with_terminator, "{}", message);
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn whitespace_test_1() {
+    let string = ByteStr::from_slice(b"  cpu0 \t 123\x0b456\n\xe2\x80\x83x\xc2\xa0y\xff\xc2 \xe3\x80\x80");
+    assert_eq!(Vec::from_iter(string.split_ascii_whitespace()), [&b"cpu0"[..], b"123\x0b456", b"\xe2\x80\x83x\xc2\xa0y\xff\xc2", b"\xe3\x80\x80"]);
+    assert_eq!(Vec::from_iter(string.split_whitespace()), [&b"cpu0"[..], b"123", b"456", b"x", b"y\xff\xc2"]);
+    assert_eq!(Vec::from_iter(string.split_whitespace().rev()), [&b"y\xff\xc2"[..], b"x", b"456", b"123", b"cpu0"]);
+    assert_eq!(ByteStr::from_slice(b" \t ").split_whitespace().next(), None);
+    assert_eq!(ByteStr::empty().split_ascii_whitespace().next_back(), None);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn whitespace_test_2() {
+    let mut random = xorshift(0x2545_f491);
+    let pieces: [&[u8]; 10] = [b"a", b" ", b"\t", b"\x0b", b"\n", b"\xc2\x85", b"\xe2\x80\xa8", b"\xe3\x80\x80", b"\xe2\x80\x8b", b"\xc2"];
+    for _ in 0 .. 1000 {
+        let bytes = random_bytes(&mut random, 8, &pieces);
+        let string = ByteStr::from_slice(&bytes);
+        let ascii = Vec::from_iter(bytes.split(u8::is_ascii_whitespace).filter(|field| !field.is_empty()));
+        assert_eq!(Vec::from_iter(string.split_ascii_whitespace().map(ByteStr::as_slice)), ascii, "{:?}", string);
+        assert_eq!(reversed(Vec::from_iter(string.split_ascii_whitespace().rev().map(ByteStr::as_slice))), ascii, "{:?}", string);
+        
+        let fields = Vec::from_iter(string.split_whitespace().map(ByteStr::as_slice));
+        assert_eq!(reversed(Vec::from_iter(string.split_whitespace().rev().map(ByteStr::as_slice))), fields, "{:?}", string);
+        if let Ok(s) = ::core::str::from_utf8(&bytes) {
+            assert_eq!(fields, Vec::from_iter(s.split_whitespace().map(str::as_bytes)), "{:?}", string);
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn replace_test_1() {
+    use std::borrow::Cow;
+    
+    let string = ByteStr::from_slice(b"cat, dog, cat and bird");
+    assert_eq!(string.replace("cat", "ox").as_slice(), b"ox, dog, ox and bird");
+    assert_eq!(string.replacen(b',', b"", 1).as_slice(), b"cat dog, cat and bird");
+    assert_eq!(string.replace("", "-").as_slice(), "-c-a-t-,- -d-o-g-,- -c-a-t- -a-n-d- -b-i-r-d-".as_bytes());
+    let counted = string.replace_with(ByteSet(b"aeiou"), |vowel| {
+        if vowel == b"a" { Cow::Borrowed(ByteStr::from_slice(b"4")) } else { Cow::Owned(vowel.to_ascii_uppercase()) }
+    });
+    assert_eq!(counted.as_slice(), b"c4t, dOg, c4t 4nd bIrd");
+    
+    let mut owned = ByteString::from_slice(b"aaa-bbb-aaa");
+    let (ptr, capacity) = (owned.as_ptr(), owned.capacity());
+    owned.replace_in_place("aaa", "x");
+    assert_eq!(owned.as_slice(), b"x-bbb-x");
+    assert_eq!((owned.as_ptr(), owned.capacity()), (ptr, capacity));
+    owned.replace_in_place("-", "--");
+    assert_eq!(owned.as_slice(), b"x--bbb--x");
+    owned.reserve(4);
+    let (ptr, capacity) = (owned.as_ptr(), owned.capacity());
+    owned.replace_in_place(b'x', b"yyy");
+    assert_eq!(owned.as_slice(), b"yyy--bbb--yyy");
+    assert_eq!((owned.as_ptr(), owned.capacity()), (ptr, capacity));
+    
+    // The matches are found in the whole string, like in `replace`.
+    for &(pattern, haystack) in &[("^a", "aaa"), ("a$", "aaa"), ("^b|a", "bab"), ("a+", "a-aaa-aa")] {
+        let regex = Regex::new(pattern).unwrap();
+        let string = ByteStr::from_slice(haystack.as_bytes());
+        for &to in &["", "X", "XY", "XYZW"] {
+            let mut owned = string.to_byte_string();
+            owned.replace_in_place(&regex, to);
+            assert_eq!(owned, string.replace(&regex, to), "{:?} -> {:?} in {:?}", pattern, to, haystack);
+        }
+    }
+    let mut owned = ByteString::from_slice(b"aaa");
+    owned.replace_in_place(&Regex::new("^a").unwrap(), "X");
+    assert_eq!(owned.as_slice(), b"Xaa");
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn replace_test_2() {
+    let mut random = xorshift(0x2545_f491);
+    for _ in 0 .. 500 {
+        let haystack = random_string(&mut random, 10, &["a", "a", "b"]);
+        let string = ByteStr::from_slice(haystack.as_bytes());
+        for &from in &["", "a", "b", "ab", "aa"] {
+            for &to in &["", "x", "xy", "xyz"] {
+                let message = format!("{:?} -> {:?} in {:?}", from, to, haystack);
+                let expected = haystack.replace(from, to);
+                assert_eq!(string.replace(from, to).as_slice(), expected.as_bytes(), "{}", message);
+                let count = random(4) as usize;
+                assert_eq!(string.replacen(from, to, count).as_slice(), haystack.replacen(from, to, count).as_bytes(), "{}", message);
+                assert_eq!(string.replace_with(from, |_| ::std::borrow::Cow::Borrowed(ByteStr::from_slice(to.as_bytes()))).as_slice(), expected.as_bytes(), "{}", message);
+                let mut owned = ByteString::from_slice(haystack.as_bytes());
+                owned.replace_in_place(from, to);
+                assert_eq!(owned.as_slice(), expected.as_bytes(), "{}", message);
+            }
+        }
+    }
+}
+
+#[test]
+fn utf8_test_1() {
+    assert_eq!(ByteStr::from_slice(b"caf\xc3\xa9").to_str(), Ok("caf\u{e9}"));
+    let error = ByteStr::from_slice(b"abc\xe2\x82x").to_str().unwrap_err();
+    assert_eq!((error.valid_up_to(), error.error_len()), (3, Some(2)));
+    let error = ByteStr::from_slice(b"abcdefghijkl\xf0\x9f\x98").to_str().unwrap_err();
+    assert_eq!((error.valid_up_to(), error.error_len()), (12, None));
+    let error = ByteStr::from_slice(b"\xed\xa0\x80").to_str().unwrap_err();
+    assert_eq!((error.valid_up_to(), error.error_len()), (0, Some(1)));
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn utf8_test_2() {
+    assert_eq!(ByteStr::from_slice(b"a\xf0\x9f\x98b\xffc").to_str_lossy(), "a\u{fffd}b\u{fffd}c");
+    assert_eq!(ByteString::from_slice(b"ok").into_string(), Ok(String::from("ok")));
+    let error = ByteString::from_slice(b"no\xc0").into_string().unwrap_err();
+    assert_eq!(error.utf8_error().valid_up_to(), 2);
+    assert_eq!(error.into_byte_string().as_slice(), b"no\xc0");
+    assert_eq!(ByteString::from_slice(b"\xe0\x80x").into_string_lossy(), "\u{fffd}\u{fffd}x");
+    
+    let mut random = xorshift(0x2545_f491);
+    let pieces = ["a", "abcdefghijklmnop", "\u{e9}", "\u{20ac}", "\u{1f600}", "\u{10ffff}", "\u{d7ff}"];
+    for _ in 0 .. 5000 {
+        let mut bytes = Vec::new();
+        for _ in 0 .. random(8) {
+            match random(3) {
+                0 => bytes.push(random(256) as u8),
+                _ => bytes.extend_from_slice(pieces[random(pieces.len() as u32) as usize].as_bytes()),
+            }
+        }
+        let string = ByteStr::from_slice(&bytes);
+        match (string.to_str(), ::std::str::from_utf8(&bytes)) {
+            (Ok(a), Ok(b)) => assert_eq!(a, b),
+            (Err(a), Err(b)) => assert_eq!((a.valid_up_to(), a.error_len()), (b.valid_up_to(), b.error_len()), "{:?}", string),
+            (a, b) => panic!("{:?}: {:?} {:?}", string, a, b),
+        }
+        let lossy = String::from_utf8_lossy(&bytes);
+        assert_eq!(string.to_str_lossy(), lossy);
+        assert_eq!(ByteString::from_slice(&bytes).into_string_lossy(), lossy);
+        assert_eq!(ByteString::from_slice(&bytes).into_string().map_err(|error| error.into_byte_string()), String::from_utf8(bytes.clone()).map_err(|_| ByteString::from_vec(bytes.clone())));
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn display_test_1() {
+    let string = ByteStr::from_slice(b"caf\xc3\xa9 \"\\\t\xff\xf0\x9f\x98");
+    assert_eq!(format!("{}", string), "caf\u{e9} \"\\\t\u{fffd}\u{fffd}");
+    assert_eq!(format!("{}", string.to_byte_string()), format!("{}", string));
+    assert_eq!(format!("{}", string.display_escaped()), "caf\\xc3\\xa9 \"\\\\\\t\\xff\\xf0\\x9f\\x98");
+    assert_eq!(format!("{:?}", string), "b\"caf\\xc3\\xa9 \\\"\\\\\\t\\xff\\xf0\\x9f\\x98\"");
+    assert_eq!(format!("{}", ByteStr::from_slice(b"\x00\xab\x7f").display_hex()), "00ab7f");
+    assert_eq!(format!("{}", string.display_truncated(4)), "caf...");
+    assert_eq!(format!("{}", string.display_truncated(5)), "caf\u{e9}...");
+    assert_eq!(format!("{}", string.display_truncated(9)), "caf\u{e9} \"\\\t...");
+    assert_eq!(format!("{}", string.display_truncated(10)), "caf\u{e9} \"\\\t\u{fffd}...");
+    assert_eq!(format!("{}", string.display_truncated(12)), "caf\u{e9} \"\\\t\u{fffd}\u{fffd}...");
+    assert_eq!(format!("{}", string.display_truncated(13)), format!("{}", string));
+    
+    assert_eq!(format!("[{:>5}]", ByteStr::from_slice(b"ab")), "[   ab]");
+    assert_eq!(format!("[{:-^6.3}]", ByteStr::from_slice(b"caf\xc3\xa9")), "[-caf--]");
+    assert_eq!(format!("[{:5}]", ByteStr::from_slice(b"a\xffb")), "[a\u{fffd}b  ]");
+    assert_eq!(format!("[{:*>4.2}]", ByteStr::from_slice(b"\xff\xffb")), "[**\u{fffd}\u{fffd}]");
+    assert_eq!(format!("[{:>8}]", string.display_truncated(4)), "[  caf...]");
+    assert_eq!(format!("[{:.4}]", string.display_truncated(4)), "[caf.]");
+    
+    let mut random = xorshift(0x2545_f491);
+    let pieces = ["a", "\u{e9}", "\u{20ac}", "\u{1f600}"];
+    for _ in 0 .. 1000 {
+        let mut bytes = Vec::new();
+        for _ in 0 .. random(8) {
+            match random(3) {
+                0 => bytes.push(random(256) as u8),
+                _ => bytes.extend_from_slice(pieces[random(pieces.len() as u32) as usize].as_bytes()),
+            }
+        }
+        let string = ByteStr::from_slice(&bytes);
+        assert_eq!(format!("{}", string), String::from_utf8_lossy(&bytes));
+        let (width, precision) = (random(10) as usize, random(10) as usize);
+        assert_eq!(format!("{:^w$.p$}", string, w = width, p = precision), format!("{:^w$.p$}", String::from_utf8_lossy(&bytes), w = width, p = precision));
+        let max = random(12) as usize;
+        let truncated = format!("{}", string.display_truncated(max));
+        if bytes.len() <= max {
+            assert_eq!(truncated, String::from_utf8_lossy(&bytes));
+        } else {
+            // The cut leaves out at most the first 3 bytes of a sequence.
+            let shown = &truncated[.. truncated.len() - 3];
+            assert!((max.saturating_sub(3) ..= max).any(|cut| String::from_utf8_lossy(&bytes[.. cut]) == shown), "{:?} {}", string, max);
+            assert!(String::from_utf8_lossy(&bytes).starts_with(shown), "{:?} {}", string, max);
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn chars_test_1() {
+    let string = ByteStr::from_slice(b"a\xc3\xa9\xe2\x82\xff\xf0\x9f\x98\x80\x80\xef\xbf\xbd");
+    assert_eq!(String::from_iter(string.chars()), "a\u{e9}\u{fffd}\u{fffd}\u{1f600}\u{fffd}\u{fffd}");
+    assert_eq!(Vec::from_iter(string.char_indices().rev()), [(11, '\u{fffd}'), (10, '\u{fffd}'), (6, '\u{1f600}'), (5, '\u{fffd}'), (3, '\u{fffd}'), (1, '\u{e9}'), (0, 'a')]);
+    let chunks = Vec::from_iter(string.utf8_chunks().map(|(valid, invalid)| (valid, invalid.as_slice())));
+    assert_eq!(chunks, [("a\u{e9}", &b"\xe2\x82"[..]), ("", b"\xff"), ("\u{1f600}", b"\x80"), ("\u{fffd}", b"")]);
+    assert_eq!(reversed(Vec::from_iter(string.utf8_chunks().rev().map(|(valid, invalid)| (valid, invalid.as_slice())))), chunks);
+    assert_eq!(ByteStr::empty().utf8_chunks().next(), None);
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn chars_test_2() {
+    let mut random = xorshift(0x2545_f491);
+    let pieces: [&[u8]; 12] = [b"a", b"\xc3\xa9", b"\xe2\x82\xac", b"\xf0\x9f\x98\x80", b"\xc3", b"\xe2\x82", b"\xf0\x9f\x98", b"\x80", b"\xbf", b"\xed\xa0", b"\xf4\x90", b"\xff"];
+    for _ in 0 .. 2000 {
+        let bytes = random_bytes(&mut random, 8, &pieces);
+        let string = ByteStr::from_slice(&bytes);
+        let lossy = String::from_utf8_lossy(&bytes);
+        
+        let chars = Vec::from_iter(string.chars());
+        assert_eq!(chars, Vec::from_iter(lossy.chars()), "{:?}", string);
+        assert_eq!(reversed(Vec::from_iter(string.chars().rev())), chars, "{:?}", string);
+        
+        // Mixes both ends, and checks that the offsets point to the
+        // sequences that were decoded.
+        let front = mixed(string.char_indices(), &mut random);
+        assert_eq!(Vec::from_iter(front.iter().map(|&(_, c)| c)), chars, "{:?}", string);
+        for window in front.windows(2) {
+            let ((start, c), (end, _)) = (window[0], window[1]);
+            assert_eq!(String::from_utf8_lossy(&bytes[start .. end]), c.to_string(), "{:?}", string);
+        }
+        
+        let chunks = Vec::from_iter(string.utf8_chunks().map(|(valid, invalid)| (valid, invalid.as_slice())));
+        let mut expected = Vec::new();
+        let mut rest = &bytes[..];
+        while !rest.is_empty() {
+            let (valid_len, invalid_len) = match std::str::from_utf8(rest) {
+                Ok(_) => (rest.len(), 0),
+                Err(error) => (error.valid_up_to(), error.error_len().unwrap_or(rest.len() - error.valid_up_to())),
+            };
+            let valid = std::str::from_utf8(&rest[.. valid_len]).unwrap();
+            expected.push((valid, &rest[valid_len .. valid_len + invalid_len]));
+            rest = &rest[valid_len + invalid_len ..];
+        }
+        assert_eq!(chunks, expected, "{:?}", string);
+        assert_eq!(reversed(Vec::from_iter(string.utf8_chunks().rev().map(|(valid, invalid)| (valid, invalid.as_slice())))), expected, "{:?}", string);
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+#[test]
+fn ord_test_1() {
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+    use std::collections::HashMap;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hash;
+    use std::hash::Hasher;
+    
+    fn hash<T: ?Sized + Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+    
+    let strings: [&[u8]; 7] = [b"", b"a", b"ab", b"abc", b"b", b"\xff", b"a\x00"];
+    for &a in &strings {
+        for &b in &strings {
+            let (x, y) = (ByteStr::from_slice(a), ByteString::from_slice(b));
+            assert_eq!(x.cmp(ByteStr::from_slice(b)), a.cmp(b));
+            assert_eq!(ByteString::from_slice(a).cmp(&y), a.cmp(b));
+            assert_eq!(x.partial_cmp(b), a.partial_cmp(b));
+            assert_eq!(x.partial_cmp(&b.to_vec()), a.partial_cmp(b));
+            assert_eq!(x.partial_cmp(&y), a.partial_cmp(b));
+            assert_eq!(y.partial_cmp(x), b.partial_cmp(a));
+            assert_eq!(y.partial_cmp(&Cow::Borrowed(x)), b.partial_cmp(a));
+            assert_eq!(b.partial_cmp(x), b.partial_cmp(a));
+            assert_eq!(y == x, a == b);
+            assert_eq!(y == a, a == b);
+            assert_eq!(y == a.to_vec(), a == b);
+        }
+        assert_eq!(hash(ByteStr::from_slice(a)), hash(a));
+        assert_eq!(hash(&ByteString::from_slice(a)), hash(a));
+    }
+    let (x, y) = (ByteStr::from_slice(b"hello"), ByteString::from_slice(b"help"));
+    assert!(x < "help");
+    assert!("help" > x);
+    assert!(*x < "help");
+    assert_eq!((*x).partial_cmp(&b"hell"[..]), Some(std::cmp::Ordering::Greater));
+    assert_eq!((*x).partial_cmp(&b"hello"[..]), Some(std::cmp::Ordering::Equal));
+    let help = String::from("help");
+    assert!(*x < help);
+    assert!(help > *x);
+    assert!(y > *x);
+    assert!(*x < y);
+    assert_eq!(y, "help");
+    assert_eq!("help", y);
+    assert_eq!(y, help);
+    
+    let mut hash_map = HashMap::new();
+    hash_map.insert(ByteString::from_slice(b"key"), 1);
+    assert_eq!(hash_map.get(ByteStr::from_slice(b"key")), Some(&1));
+    assert_eq!(hash_map.get(&b"key"[..]), Some(&1));
+    let mut tree_map = BTreeMap::new();
+    tree_map.insert(ByteString::from_slice(b"b"), 2);
+    tree_map.insert(ByteString::from_slice(b"a"), 1);
+    assert_eq!(tree_map.get(ByteStr::from_slice(b"a")), Some(&1));
+    assert_eq!(Vec::from_iter(tree_map.values()), [&1, &2]);
+}
@@ -0,0 +1,312 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Two-Way string matching algorithm (Crochemore and Perrin, 1991).
+//!
+//! The needle is split at a critical factorization `needle = u v` computed
+//! from its maximal suffixes. A search compares `v` left to right and then
+//! `u` right to left, shifting by the period of the needle on a mismatch in
+//! `u`, which gives linear time and constant extra space.
+//!
+//! The reverse search uses the critical factorization of the reversed needle
+//! and compares `u` right to left before `v` left to right.
+//!
+//! The `_by` variants compare bytes after mapping them with a folding
+//! function, which allows searching modulo an equivalence such as ASCII
+//! case. The same function must be used for preprocessing and searching.
+
+use core;
+
+// TwoWay
+#[derive(Clone, Debug)]
+pub struct TwoWay {
+    // Critical factorization index used by the forward search.
+    crit_pos: usize,
+    // Critical factorization index used by the reverse search.
+    crit_pos_back: usize,
+    // Period of the needle, or a lower bound of the shift when the
+    // needle has a long period.
+    period: usize,
+    // Bloom-like filter over the low 6 bits of every byte of the needle
+    // (or of its first period when it is periodic).
+    byteset: u64,
+    // `true` when the needle is not periodic; in that case the matched
+    // prefix is not remembered between shifts.
+    long_period: bool,
+}
+
+impl TwoWay {
+    /// Preprocesses `needle`. `needle` must not be empty.
+    #[inline]
+    pub fn new(needle: &[u8]) -> Self {
+        Self::new_by(needle, |byte| byte)
+    }
+    
+    /// Preprocesses `needle` for searches that compare bytes mapped with
+    /// `fold`. `needle` must not be empty.
+    pub fn new_by<F: Fn(u8) -> u8>(needle: &[u8], fold: F) -> Self {
+        debug_assert!(!needle.is_empty());
+        
+        let (crit_pos_false, period_false) = maximal_suffix(needle, false, &fold);
+        let (crit_pos_true, period_true) = maximal_suffix(needle, true, &fold);
+        
+        let (crit_pos, period) = if crit_pos_false > crit_pos_true {
+            (crit_pos_false, period_false)
+        } else {
+            (crit_pos_true, period_true)
+        };
+        
+        if period + crit_pos <= needle.len() &&
+            (0 .. crit_pos).all(|i| fold(needle[i]) == fold(needle[period + i])) {
+            // Short period: `needle` is periodic with period `period`.
+            let crit_pos_back = needle.len() - core::cmp::max(
+                reverse_maximal_suffix(needle, period, false, &fold),
+                reverse_maximal_suffix(needle, period, true, &fold));
+            
+            Self {
+                crit_pos,
+                crit_pos_back,
+                period,
+                byteset: byteset_create(&needle[.. period], &fold),
+                long_period: false,
+            }
+        } else {
+            // Long period: the shift `max(|u|, |v|) + 1` is always safe.
+            Self {
+                crit_pos,
+                crit_pos_back: crit_pos,
+                period: core::cmp::max(crit_pos, needle.len() - crit_pos) + 1,
+                byteset: byteset_create(needle, &fold),
+                long_period: true,
+            }
+        }
+    }
+    
+    #[inline]
+    fn byteset_contains(&self, byte: u8) -> bool {
+        (self.byteset >> (byte & 0x3F)) & 1 != 0
+    }
+    
+    /// Returns the start of the first occurrence of `needle` in `haystack`
+    /// at or after `position`.
+    ///
+    /// `needle` must be the same needle that was passed to `new`.
+    #[inline]
+    pub fn find(&self, haystack: &[u8], needle: &[u8], position: usize) -> Option<usize> {
+        self.find_by(haystack, needle, position, |byte| byte)
+    }
+    
+    /// Same as `find`, comparing bytes mapped with `fold`.
+    ///
+    /// `needle` and `fold` must be the same that were passed to `new_by`.
+    pub fn find_by<F: Fn(u8) -> u8>(&self, haystack: &[u8], needle: &[u8], position: usize, fold: F) -> Option<usize> {
+        self.search_by(haystack, needle, position, fold, |_| false)
+    }
+    
+    /// Returns the number of occurrences of `needle` in `haystack`,
+    /// including the ones that overlap.
+    ///
+    /// `needle` must be the same needle that was passed to `new`.
+    #[inline]
+    pub fn count_overlapping(&self, haystack: &[u8], needle: &[u8]) -> usize {
+        self.count_overlapping_by(haystack, needle, |byte| byte)
+    }
+    
+    /// Same as `count_overlapping`, comparing bytes mapped with `fold`.
+    ///
+    /// `needle` and `fold` must be the same that were passed to `new_by`.
+    pub fn count_overlapping_by<F: Fn(u8) -> u8>(&self, haystack: &[u8], needle: &[u8], fold: F) -> usize {
+        let mut count = 0;
+        self.search_by(haystack, needle, 0, fold, |_| {
+            count += 1;
+            true
+        });
+        count
+    }
+    
+    // Searches forwards from `position`, passing each occurrence to `report`
+    // until it returns `false`, and returns that occurrence. The search goes
+    // on after an occurrence as after a mismatch in `u`, since the next one
+    // cannot start before the period, which keeps the total time linear.
+    fn search_by<F, R>(&self, haystack: &[u8], needle: &[u8], mut position: usize, fold: F, mut report: R) -> Option<usize>
+        where F: Fn(u8) -> u8,
+              R: FnMut(usize) -> bool
+    {
+        let needle_last = needle.len() - 1;
+        // Length of the needle prefix already known to match at `position`.
+        let mut memory = 0;
+        
+        'search: loop {
+            let tail_byte = match haystack.get(position + needle_last) {
+                Some(&byte) => fold(byte),
+                None => return None,
+            };
+            
+            // Quickly skip windows whose last byte does not occur in the needle.
+            if !self.byteset_contains(tail_byte) {
+                position += needle.len();
+                memory = 0;
+                continue 'search;
+            }
+            
+            // Compare the right part of the needle.
+            let start = if self.long_period { self.crit_pos } else { core::cmp::max(self.crit_pos, memory) };
+            for i in start .. needle.len() {
+                if fold(needle[i]) != fold(haystack[position + i]) {
+                    position += i - self.crit_pos + 1;
+                    memory = 0;
+                    continue 'search;
+                }
+            }
+            
+            // Compare the left part of the needle.
+            let start = if self.long_period { 0 } else { memory };
+            let matched = (start .. self.crit_pos).rev().all(|i| fold(needle[i]) == fold(haystack[position + i]));
+            if matched && !report(position) {
+                return Some(position);
+            }
+            position += self.period;
+            if !self.long_period {
+                memory = needle.len() - self.period;
+            }
+        }
+    }
+    
+    /// Returns the start of the last occurrence of `needle` in `haystack`
+    /// that ends at or before `end`.
+    ///
+    /// `needle` must be the same needle that was passed to `new`.
+    #[inline]
+    pub fn rfind(&self, haystack: &[u8], needle: &[u8], end: usize) -> Option<usize> {
+        self.rfind_by(haystack, needle, end, |byte| byte)
+    }
+    
+    /// Same as `rfind`, comparing bytes mapped with `fold`.
+    ///
+    /// `needle` and `fold` must be the same that were passed to `new_by`.
+    pub fn rfind_by<F: Fn(u8) -> u8>(&self, haystack: &[u8], needle: &[u8], mut end: usize, fold: F) -> Option<usize> {
+        // Start of the needle suffix already known to match ending at `end`.
+        let mut memory = needle.len();
+        
+        'search: loop {
+            let front_byte = match end.checked_sub(needle.len()) {
+                Some(start) => fold(haystack[start]),
+                None => return None,
+            };
+            
+            // Quickly skip windows whose first byte does not occur in the needle.
+            if !self.byteset_contains(front_byte) {
+                end -= needle.len();
+                memory = needle.len();
+                continue 'search;
+            }
+            
+            let start = end - needle.len();
+            
+            // Compare the left part of the needle.
+            let crit = if self.long_period { self.crit_pos_back } else { core::cmp::min(self.crit_pos_back, memory) };
+            for i in (0 .. crit).rev() {
+                if fold(needle[i]) != fold(haystack[start + i]) {
+                    end -= self.crit_pos_back - i;
+                    memory = needle.len();
+                    continue 'search;
+                }
+            }
+            
+            // Compare the right part of the needle.
+            let needle_end = if self.long_period { needle.len() } else { memory };
+            for i in self.crit_pos_back .. needle_end {
+                if fold(needle[i]) != fold(haystack[start + i]) {
+                    end -= self.period;
+                    if !self.long_period {
+                        memory = self.period;
+                    }
+                    continue 'search;
+                }
+            }
+            
+            return Some(start);
+        }
+    }
+}
+
+#[inline]
+fn byteset_create<F: Fn(u8) -> u8>(bytes: &[u8], fold: &F) -> u64 {
+    bytes.iter().fold(0, |set, &byte| set | (1 << (fold(byte) & 0x3F)))
+}
+
+// Computes the maximal suffix of `arr` for the byte order given by
+// `order_greater`, returning its start index and period.
+fn maximal_suffix<F: Fn(u8) -> u8>(arr: &[u8], order_greater: bool, fold: &F) -> (usize, usize) {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+    
+    while let Some(&a) = arr.get(right + offset) {
+        let (a, b) = (fold(a), fold(arr[left + offset]));
+        if (a < b && !order_greater) || (a > b && order_greater) {
+            // Suffix is smaller, period is the entire prefix so far.
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            // Advance through repetition of the current period.
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            // Suffix is larger, start over from the current location.
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+    (left, period)
+}
+
+// Computes the maximal suffix of the reversed `arr`, returning its length.
+// The search stops once a period equal to `known_period` is found, which
+// is enough to obtain a critical factorization.
+fn reverse_maximal_suffix<F: Fn(u8) -> u8>(arr: &[u8], known_period: usize, order_greater: bool, fold: &F) -> usize {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+    let n = arr.len();
+    
+    while right + offset < n {
+        let a = fold(arr[n - (1 + right + offset)]);
+        let b = fold(arr[n - (1 + left + offset)]);
+        if (a < b && !order_greater) || (a > b && order_greater) {
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+        if period == known_period {
+            break;
+        }
+    }
+    debug_assert!(period <= known_period);
+    left
+}
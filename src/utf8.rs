@@ -0,0 +1,293 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! UTF-8 decoding helpers.
+//!
+//! The decoding is strict, as in `std::str::from_utf8`: overlong forms,
+//! surrogates and code points above U+10FFFF are invalid.
+
+use core;
+#[cfg(not(feature="no_std"))]
+use std;
+#[cfg(not(feature="no_std"))]
+use ByteStr;
+#[cfg(not(feature="no_std"))]
+use ByteString;
+
+/// Returns whether `byte` is a continuation byte.
+#[inline]
+pub fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Returns the length of the sequence started by `byte`, or 0 if it cannot
+/// start one.
+#[inline]
+pub fn width(byte: u8) -> usize {
+    match byte {
+        0x00 ..= 0x7F => 1,
+        0xC2 ..= 0xDF => 2,
+        0xE0 ..= 0xEF => 3,
+        0xF0 ..= 0xF4 => 4,
+        _ => 0,
+    }
+}
+
+/// Returns the range allowed for the second byte of a sequence started by
+/// `lead`, which excludes overlong forms, surrogates and code points above
+/// U+10FFFF.
+#[inline]
+fn second_byte_range(lead: u8) -> (u8, u8) {
+    match lead {
+        0xE0 => (0xA0, 0xBF),
+        0xED => (0x80, 0x9F),
+        0xF0 => (0x90, 0xBF),
+        0xF4 => (0x80, 0x8F),
+        _ => (0x80, 0xBF),
+    }
+}
+
+/// Decodes the char at the start of `bytes`, returning it with its length.
+pub fn decode(bytes: &[u8]) -> Option<(char, usize)> {
+    let lead = *bytes.first()?;
+    let width = width(lead);
+    match width {
+        0 => return None,
+        1 => return Some((lead as char, 1)),
+        _ => {}
+    }
+    if bytes.len() < width {
+        return None;
+    }
+    let (low, high) = second_byte_range(lead);
+    if bytes[1] < low || bytes[1] > high || !bytes[2 .. width].iter().all(|&byte| is_continuation(byte)) {
+        return None;
+    }
+    let mut code = (lead as u32) & (0x7F >> width);
+    for &byte in &bytes[1 .. width] {
+        code = (code << 6) | (byte as u32 & 0x3F);
+    }
+    core::char::from_u32(code).map(|c| (c, width))
+}
+
+/// Decodes the char at the end of `bytes`, returning it with its length.
+pub fn decode_last(bytes: &[u8]) -> Option<(char, usize)> {
+    for width in 1 ..= bytes.len().min(4) {
+        let start = bytes.len() - width;
+        if !is_continuation(bytes[start]) {
+            return decode(&bytes[start ..]).filter(|&(_, len)| len == width);
+        }
+    }
+    None
+}
+
+/// Decodes the char at the start of `bytes`, which is not empty, returning
+/// it with its length. An invalid sequence gives `None` with the length of
+/// its maximal subpart, which is the longest prefix of a valid sequence but
+/// at least a byte. That is what `String::from_utf8_lossy` replaces with a
+/// U+FFFD.
+pub fn decode_lossy(bytes: &[u8]) -> (Option<char>, usize) {
+    let lead = bytes[0];
+    match width(lead) {
+        0 => (None, 1),
+        1 => (Some(lead as char), 1),
+        width => {
+            let (low, high) = second_byte_range(lead);
+            for k in 1 .. width {
+                match bytes.get(k) {
+                    Some(&byte) if k == 1 && byte >= low && byte <= high => {}
+                    Some(&byte) if k > 1 && is_continuation(byte) => {}
+                    _ => return (None, k),
+                }
+            }
+            let (c, len) = decode(bytes).unwrap();
+            (Some(c), len)
+        }
+    }
+}
+
+/// Like `decode_lossy`, for the char at the end of `bytes`, giving the same
+/// result as decoding all of `bytes` with `decode_lossy` from the front.
+pub fn decode_last_lossy(bytes: &[u8]) -> (Option<char>, usize) {
+    // Sequences start with a byte that is not a continuation byte, and the
+    // continuation bytes that are not part of one are replaced one by one.
+    for width in 1 ..= bytes.len().min(4) {
+        let start = bytes.len() - width;
+        if !is_continuation(bytes[start]) {
+            let (c, len) = decode_lossy(&bytes[start ..]);
+            if len == width {
+                return (c, len);
+            }
+            break;
+        }
+    }
+    (None, 1)
+}
+
+/// Returns the number of ASCII bytes at the start of `bytes`.
+#[inline]
+fn ascii_len(bytes: &[u8]) -> usize {
+    const WORD: usize = core::mem::size_of::<usize>();
+    const HIGH_BITS: usize = usize::MAX / 0xFF * 0x80;
+    
+    // Whole words are checked at once, and the byte that ends the run is
+    // then found in the first word that has a high bit.
+    let mut i = 0;
+    while i + WORD <= bytes.len() {
+        let mut word = [0; WORD];
+        word.copy_from_slice(&bytes[i .. i + WORD]);
+        if usize::from_ne_bytes(word) & HIGH_BITS != 0 {
+            break;
+        }
+        i += WORD;
+    }
+    while i < bytes.len() && bytes[i] < 0x80 {
+        i += 1;
+    }
+    i
+}
+
+/// Checks that `bytes` is valid UTF-8.
+pub fn validate(bytes: &[u8]) -> Result<(), Utf8Error> {
+    let mut i = 0;
+    while i < bytes.len() {
+        let lead = bytes[i];
+        if lead < 0x80 {
+            i += ascii_len(&bytes[i ..]);
+            continue;
+        }
+        
+        let error = |error_len| Err(Utf8Error { valid_up_to: i, error_len });
+        let width = width(lead);
+        if width == 0 {
+            return error(Some(1));
+        }
+        let (low, high) = second_byte_range(lead);
+        for k in 1 .. width {
+            match bytes.get(i + k) {
+                None => return error(None),
+                Some(&byte) if k == 1 && (byte < low || byte > high) => return error(Some(1)),
+                Some(&byte) if !is_continuation(byte) => return error(Some(k)),
+                Some(_) => {}
+            }
+        }
+        i += width;
+    }
+    Ok(())
+}
+
+/// Converts `bytes` into a `String` if it is valid UTF-8.
+#[cfg(not(feature="no_std"))]
+pub fn into_string(bytes: ByteString) -> Result<String, FromUtf8Error> {
+    match validate(bytes.as_slice()) {
+        Ok(()) => Ok(unsafe { String::from_utf8_unchecked(bytes.into_vec()) }),
+        Err(error) => Err(FromUtf8Error { bytes, error }),
+    }
+}
+
+/// Converts `bytes` into a string, replacing each maximal invalid
+/// subsequence with U+FFFD REPLACEMENT CHARACTER, like
+/// `String::from_utf8_lossy`.
+#[cfg(not(feature="no_std"))]
+pub fn to_str_lossy(bytes: &[u8]) -> std::borrow::Cow<'_, str> {
+    let mut error = match validate(bytes) {
+        Ok(()) => return std::borrow::Cow::Borrowed(unsafe { core::str::from_utf8_unchecked(bytes) }),
+        Err(error) => error,
+    };
+    let mut result = String::with_capacity(bytes.len() + 2);
+    let mut rest = bytes;
+    loop {
+        let (valid, invalid) = rest.split_at(error.valid_up_to);
+        result.push_str(unsafe { core::str::from_utf8_unchecked(valid) });
+        result.push('\u{FFFD}');
+        rest = &invalid[error.error_len.unwrap_or(invalid.len()) ..];
+        match validate(rest) {
+            Ok(()) => break,
+            Err(next) => error = next,
+        }
+    }
+    result.push_str(unsafe { core::str::from_utf8_unchecked(rest) });
+    std::borrow::Cow::Owned(result)
+}
+
+// Utf8Error
+/// Error returned when a string is not valid UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Utf8Error {
+    valid_up_to: usize,
+    error_len: Option<usize>,
+}
+
+impl Utf8Error {
+    /// Returns the length of the valid UTF-8 prefix of the string.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+    
+    /// Returns the length of the invalid sequence that starts at
+    /// `valid_up_to`, which is 1 to 3 bytes, or `None` if the string ends
+    /// in the middle of a sequence that could still be valid.
+    #[inline]
+    pub fn error_len(&self) -> Option<usize> {
+        self.error_len
+    }
+}
+
+impl core::fmt::Display for Utf8Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> Result<(), core::fmt::Error> {
+        match self.error_len {
+            Some(len) => write!(f, "invalid utf-8 sequence of {} bytes from index {}", len, self.valid_up_to),
+            None => write!(f, "incomplete utf-8 byte sequence from index {}", self.valid_up_to),
+        }
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl std::error::Error for Utf8Error {}
+
+// FromUtf8Error
+/// Error returned by `ByteString::into_string`, which keeps the string that
+/// could not be converted.
+#[cfg(not(feature="no_std"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromUtf8Error {
+    bytes: ByteString,
+    error: Utf8Error,
+}
+
+#[cfg(not(feature="no_std"))]
+impl FromUtf8Error {
+    /// Returns the string that could not be converted.
+    #[inline]
+    pub fn as_byte_str(&self) -> &ByteStr {
+        &self.bytes
+    }
+    
+    /// Returns the string that could not be converted, without clones or
+    /// allocation.
+    #[inline]
+    pub fn into_byte_string(self) -> ByteString {
+        self.bytes
+    }
+    
+    /// Returns the details of the error.
+    #[inline]
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl std::fmt::Display for FromUtf8Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        self.error.fmt(f)
+    }
+}
+
+#[cfg(not(feature="no_std"))]
+impl std::error::Error for FromUtf8Error {}